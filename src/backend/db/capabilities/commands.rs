@@ -0,0 +1,61 @@
+use crate::backend::jobs::capabilities::HostCapabilities;
+use anyhow::Result;
+use rusqlite::{Connection, params};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub struct HostCapabilitiesUpsert {
+    pub host_id: String,
+    pub capabilities: HostCapabilities,
+}
+
+/// One row per host, overwritten on every probe like `host_facts`.
+pub async fn upsert_host_capabilities(
+    conn: &Arc<Mutex<Connection>>,
+    data: &HostCapabilitiesUpsert,
+) -> Result<()> {
+    let caps = &data.capabilities;
+    let conn = conn.lock().await;
+    conn.execute(
+        r#"
+        INSERT INTO host_capabilities (
+            host_id,
+            os,
+            shell,
+            bash,
+            nvidia_smi,
+            rocm_smi,
+            docker,
+            systemctl,
+            lscpu,
+            sudo,
+            updated_at
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, CURRENT_TIMESTAMP)
+        ON CONFLICT(host_id) DO UPDATE SET
+            os = excluded.os,
+            shell = excluded.shell,
+            bash = excluded.bash,
+            nvidia_smi = excluded.nvidia_smi,
+            rocm_smi = excluded.rocm_smi,
+            docker = excluded.docker,
+            systemctl = excluded.systemctl,
+            lscpu = excluded.lscpu,
+            sudo = excluded.sudo,
+            updated_at = excluded.updated_at
+        "#,
+        params![
+            data.host_id,
+            caps.os,
+            caps.shell,
+            caps.bash,
+            caps.nvidia_smi,
+            caps.rocm_smi,
+            caps.docker,
+            caps.systemctl,
+            caps.lscpu,
+            caps.sudo,
+        ],
+    )?;
+    Ok(())
+}
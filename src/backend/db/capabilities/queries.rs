@@ -0,0 +1,50 @@
+use anyhow::Result;
+use rusqlite::Connection;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub struct HostCapabilitiesRow {
+    pub host_id: String,
+    pub os: String,
+    pub shell: Option<String>,
+    pub bash: bool,
+    pub nvidia_smi: bool,
+    pub rocm_smi: bool,
+    pub docker: bool,
+    pub systemctl: bool,
+    pub lscpu: bool,
+    pub sudo: bool,
+    pub updated_at: String,
+}
+
+pub async fn fetch_host_capabilities_all(
+    conn: &Arc<Mutex<Connection>>,
+) -> Result<Vec<HostCapabilitiesRow>> {
+    let conn = conn.lock().await;
+    let mut stmt = conn.prepare(
+        "SELECT host_id, os, shell, bash, nvidia_smi, rocm_smi, docker, systemctl, lscpu, \
+                sudo, updated_at \
+         FROM host_capabilities",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(HostCapabilitiesRow {
+            host_id: row.get::<_, String>(0)?,
+            os: row.get::<_, String>(1)?,
+            shell: row.get::<_, Option<String>>(2)?,
+            bash: row.get::<_, bool>(3)?,
+            nvidia_smi: row.get::<_, bool>(4)?,
+            rocm_smi: row.get::<_, bool>(5)?,
+            docker: row.get::<_, bool>(6)?,
+            systemctl: row.get::<_, bool>(7)?,
+            lscpu: row.get::<_, bool>(8)?,
+            sudo: row.get::<_, bool>(9)?,
+            updated_at: row.get::<_, String>(10)?,
+        })
+    })?;
+    let mut results = vec![];
+    for r in rows {
+        results.push(r?);
+    }
+    Ok(results)
+}
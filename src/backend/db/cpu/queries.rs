@@ -47,10 +47,11 @@ pub async fn fetch_latest_cpu_detail_all(
 ) -> Result<Vec<CpuDetailRow>> {
     let conn = conn.lock().await;
     let mut stmt = conn.prepare(
-        "SELECT c.host_id, c.model_name, c.core_count, c.usage_percent, c.per_core_json \
+        "SELECT c.host_id, COALESCE(NULLIF(c.model_name, ''), f.cpu_model, ''), c.core_count, c.usage_percent, c.per_core_json \
          FROM cpu_results c \
          JOIN (SELECT host_id, MAX(timestamp) AS max_ts FROM cpu_results GROUP BY host_id) t \
-           ON c.host_id = t.host_id AND c.timestamp = t.max_ts",
+           ON c.host_id = t.host_id AND c.timestamp = t.max_ts \
+         LEFT JOIN host_facts f ON f.host_id = c.host_id",
     )?;
     let rows = stmt.query_map([], |row| {
         let per_core_json: String = row.get(4)?;
@@ -77,17 +78,19 @@ pub struct CpuTimelineRow {
     pub timestamp: String,
 }
 
+/// Raw samples collected at or after `since` (seconds since the epoch).
 pub async fn fetch_cpu_usage_timeline(
     conn: &Arc<Mutex<Connection>>,
+    since: i64,
 ) -> Result<Vec<CpuTimelineRow>> {
     let conn = conn.lock().await;
     let mut stmt = conn.prepare(
         "SELECT host_id, usage_percent, timestamp \
          FROM cpu_results \
-         ORDER BY timestamp DESC, host_id \
-         LIMIT 1000",
+         WHERE timestamp >= datetime(?1, 'unixepoch') \
+         ORDER BY timestamp DESC, host_id",
     )?;
-    let rows = stmt.query_map([], |row| {
+    let rows = stmt.query_map([since], |row| {
         Ok(CpuTimelineRow {
             host_id: row.get::<_, String>(0)?,
             usage_percent: row.get::<_, f64>(1)? as f32,
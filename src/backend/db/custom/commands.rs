@@ -0,0 +1,27 @@
+use crate::backend::jobs::custom::CustomSample;
+use anyhow::Result;
+use rusqlite::{Connection, params};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub struct CustomResultInsert {
+    pub host_id: String,
+    pub job_name: String,
+    pub sample: CustomSample,
+}
+
+pub async fn store_custom_result(
+    conn: &Arc<Mutex<Connection>>,
+    data: &CustomResultInsert,
+) -> Result<()> {
+    let value_json = serde_json::to_string(&data.sample)?;
+    let conn = conn.lock().await;
+    conn.execute(
+        r#"
+        INSERT INTO job_results (host_id, job_name, value_json)
+        VALUES (?1, ?2, ?3)
+        "#,
+        params![data.host_id, data.job_name, value_json],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use crate::backend::jobs::custom::CustomSample;
+use anyhow::Result;
+use rusqlite::Connection;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub struct CustomResultRow {
+    pub host_id: String,
+    pub job_name: String,
+    pub sample: CustomSample,
+    pub timestamp: String,
+}
+
+/// Newest first, like the other timeline queries.
+pub async fn fetch_custom_timeline(conn: &Arc<Mutex<Connection>>) -> Result<Vec<CustomResultRow>> {
+    let conn = conn.lock().await;
+    let mut stmt = conn.prepare(
+        "SELECT host_id, job_name, value_json, timestamp \
+         FROM job_results \
+         WHERE job_name LIKE 'custom:%' \
+         ORDER BY timestamp DESC, id DESC \
+         LIMIT 5000",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+        ))
+    })?;
+    let mut results = vec![];
+    for r in rows {
+        let (host_id, job_name, value_json, timestamp) = r?;
+        let sample = serde_json::from_str(&value_json).unwrap_or_default();
+        results.push(CustomResultRow {
+            host_id,
+            job_name,
+            sample,
+            timestamp,
+        });
+    }
+    Ok(results)
+}
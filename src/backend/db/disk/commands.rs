@@ -8,38 +8,64 @@ use tokio::sync::Mutex;
 #[derive(Serialize)]
 pub struct DiskResultInsert {
     pub host_id: String,
+    pub device: String,
+    pub fs_type: Option<String>,
     pub mount_point: String,
     pub total_mb: u64,
     pub used_mb: u64,
     pub available_mb: u64,
     pub used_percent: f32,
+    pub inodes_total: Option<u64>,
+    pub inodes_used: Option<u64>,
+    pub inodes_used_percent: Option<f32>,
 }
 
-pub async fn store_disk_result(
+/// Stores every volume of one `df` run under a single timestamp, so the latest
+/// sample of a host is summed as a whole.
+pub async fn store_disk_sample(
     conn: &Arc<Mutex<Connection>>,
-    data: &DiskResultInsert,
+    volumes: &[DiskResultInsert],
 ) -> Result<()> {
-    let conn = conn.lock().await;
-    conn.execute(
-        r#"
-        INSERT INTO disk_results (
-            host_id,
-            mount_point,
-            total_mb,
-            used_mb,
-            available_mb,
-            used_percent
-        )
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
-        "#,
-        params![
-            data.host_id,
-            data.mount_point,
-            data.total_mb,
-            data.used_mb,
-            data.available_mb,
-            data.used_percent,
-        ],
-    )?;
+    let mut conn = conn.lock().await;
+    let tx = conn.transaction()?;
+    let timestamp: String = tx.query_row("SELECT CURRENT_TIMESTAMP", [], |row| row.get(0))?;
+
+    for data in volumes {
+        tx.execute(
+            r#"
+            INSERT INTO disk_results (
+                host_id,
+                device,
+                fs_type,
+                mount_point,
+                total_mb,
+                used_mb,
+                available_mb,
+                used_percent,
+                inodes_total,
+                inodes_used,
+                inodes_used_percent,
+                timestamp
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+            "#,
+            params![
+                data.host_id,
+                data.device,
+                data.fs_type,
+                data.mount_point,
+                data.total_mb,
+                data.used_mb,
+                data.available_mb,
+                data.used_percent,
+                data.inodes_total.map(|v| v as i64),
+                data.inodes_used.map(|v| v as i64),
+                data.inodes_used_percent,
+                timestamp,
+            ],
+        )?;
+    }
+
+    tx.commit()?;
     Ok(())
 }
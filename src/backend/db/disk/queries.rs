@@ -14,11 +14,16 @@ pub struct DiskResultRow {
 pub struct DiskVolumeRow {
     #[allow(dead_code)]
     pub host_id: String,
+    pub device: Option<String>,
+    pub fs_type: Option<String>,
     pub mount_point: String,
     pub total_mb: u64,
     pub used_mb: u64,
     pub available_mb: u64,
     pub used_percent: f32,
+    pub inodes_total: Option<u64>,
+    pub inodes_used: Option<u64>,
+    pub inodes_used_percent: Option<f32>,
 }
 
 pub async fn fetch_latest_disk_all(conn: &Arc<Mutex<Connection>>) -> Result<Vec<DiskResultRow>> {
@@ -26,7 +31,7 @@ pub async fn fetch_latest_disk_all(conn: &Arc<Mutex<Connection>>) -> Result<Vec<
     let mut stmt = conn.prepare(
         "SELECT 
             d.host_id,
-            SUM(CASE WHEN d.mount_point = '/' THEN d.total_mb + d.used_mb + d.available_mb ELSE 0 END) AS total_capacity,
+            SUM(d.total_mb) AS total_capacity,
             SUM(d.used_mb) AS total_usage
         FROM disk_results d
         JOIN (SELECT host_id, MAX(timestamp) AS max_ts FROM disk_results GROUP BY host_id) t
@@ -61,7 +66,12 @@ pub async fn fetch_latest_disk_volumes(
             d.total_mb,
             d.used_mb,
             d.available_mb,
-            d.used_percent
+            d.used_percent,
+            d.device,
+            d.fs_type,
+            d.inodes_total,
+            d.inodes_used,
+            d.inodes_used_percent
         FROM disk_results d
         JOIN (SELECT host_id, MAX(timestamp) AS max_ts FROM disk_results WHERE host_id = ?1 GROUP BY host_id) t
           ON d.host_id = t.host_id AND d.timestamp = t.max_ts
@@ -77,6 +87,11 @@ pub async fn fetch_latest_disk_volumes(
             used_mb: row.get::<_, i64>(3)? as u64,
             available_mb: row.get::<_, i64>(4)? as u64,
             used_percent: row.get::<_, f64>(5)? as f32,
+            device: row.get::<_, Option<String>>(6)?,
+            fs_type: row.get::<_, Option<String>>(7)?,
+            inodes_total: row.get::<_, Option<i64>>(8)?.map(|v| v as u64),
+            inodes_used: row.get::<_, Option<i64>>(9)?.map(|v| v as u64),
+            inodes_used_percent: row.get::<_, Option<f64>>(10)?.map(|v| v as f32),
         })
     })?;
 
@@ -0,0 +1,78 @@
+use anyhow::Result;
+use rusqlite::{Connection, params};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Serialize)]
+pub struct HostFactsUpsert {
+    pub host_id: String,
+    pub os_name: String,
+    pub kernel_release: String,
+    pub arch: String,
+    pub uname: String,
+    pub cpu_model: String,
+    pub cpu_count: u32,
+    pub cpu_sockets: Option<u32>,
+    pub vendor: Option<String>,
+    pub product: Option<String>,
+    pub total_memory_mb: u64,
+    pub virtualization: Option<String>,
+}
+
+/// Facts only change on upgrades or hardware swaps, so a single row per host
+/// is kept and overwritten on every collection.
+pub async fn upsert_host_facts(
+    conn: &Arc<Mutex<Connection>>,
+    data: &HostFactsUpsert,
+) -> Result<()> {
+    let conn = conn.lock().await;
+    conn.execute(
+        r#"
+        INSERT INTO host_facts (
+            host_id,
+            os_name,
+            kernel_release,
+            arch,
+            uname,
+            cpu_model,
+            cpu_count,
+            cpu_sockets,
+            vendor,
+            product,
+            total_memory_mb,
+            virtualization,
+            updated_at
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, CURRENT_TIMESTAMP)
+        ON CONFLICT(host_id) DO UPDATE SET
+            os_name = excluded.os_name,
+            kernel_release = excluded.kernel_release,
+            arch = excluded.arch,
+            uname = excluded.uname,
+            cpu_model = excluded.cpu_model,
+            cpu_count = excluded.cpu_count,
+            cpu_sockets = excluded.cpu_sockets,
+            vendor = excluded.vendor,
+            product = excluded.product,
+            total_memory_mb = excluded.total_memory_mb,
+            virtualization = excluded.virtualization,
+            updated_at = excluded.updated_at
+        "#,
+        params![
+            data.host_id,
+            data.os_name,
+            data.kernel_release,
+            data.arch,
+            data.uname,
+            data.cpu_model,
+            data.cpu_count as i64,
+            data.cpu_sockets.map(|v| v as i64),
+            data.vendor,
+            data.product,
+            data.total_memory_mb as i64,
+            data.virtualization,
+        ],
+    )?;
+    Ok(())
+}
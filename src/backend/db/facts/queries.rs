@@ -0,0 +1,50 @@
+use anyhow::Result;
+use rusqlite::Connection;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub struct HostFactsRow {
+    pub host_id: String,
+    pub os_name: String,
+    pub kernel_release: String,
+    pub arch: String,
+    pub cpu_model: String,
+    pub cpu_count: u32,
+    pub cpu_sockets: Option<u32>,
+    pub vendor: Option<String>,
+    pub product: Option<String>,
+    pub total_memory_mb: u64,
+    pub virtualization: Option<String>,
+    pub updated_at: String,
+}
+
+pub async fn fetch_host_facts_all(conn: &Arc<Mutex<Connection>>) -> Result<Vec<HostFactsRow>> {
+    let conn = conn.lock().await;
+    let mut stmt = conn.prepare(
+        "SELECT host_id, os_name, kernel_release, arch, cpu_model, cpu_count, cpu_sockets, \
+                vendor, product, total_memory_mb, virtualization, updated_at \
+         FROM host_facts",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(HostFactsRow {
+            host_id: row.get::<_, String>(0)?,
+            os_name: row.get::<_, String>(1)?,
+            kernel_release: row.get::<_, String>(2)?,
+            arch: row.get::<_, String>(3)?,
+            cpu_model: row.get::<_, String>(4)?,
+            cpu_count: row.get::<_, i64>(5)? as u32,
+            cpu_sockets: row.get::<_, Option<i64>>(6)?.map(|v| v as u32),
+            vendor: row.get::<_, Option<String>>(7)?,
+            product: row.get::<_, Option<String>>(8)?,
+            total_memory_mb: row.get::<_, i64>(9)? as u64,
+            virtualization: row.get::<_, Option<String>>(10)?,
+            updated_at: row.get::<_, String>(11)?,
+        })
+    })?;
+    let mut results = vec![];
+    for r in rows {
+        results.push(r?);
+    }
+    Ok(results)
+}
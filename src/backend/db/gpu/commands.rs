@@ -7,37 +7,120 @@ use tokio::sync::Mutex;
 #[derive(Serialize)]
 pub struct GpuResultInsert {
     pub host_id: String,
+    pub gpu_key: String,
     pub gpu_index: u32,
     pub name: String,
     pub memory_total_mb: Option<u64>,
     pub memory_used_mb: Option<u64>,
     pub temperature_c: Option<f32>,
+    pub gpu_uuid: Option<String>,
+    pub pci_bus_id: Option<String>,
+    pub parent_uuid: Option<String>,
+    pub mig_device_index: Option<u32>,
+    pub utilization_gpu: Option<f32>,
+    pub utilization_memory: Option<f32>,
+    pub power_draw_w: Option<f32>,
+    pub power_limit_w: Option<f32>,
+    pub vendor: String,
     pub raw_output: Option<String>,
 }
 
-pub async fn store_gpu_result(conn: &Arc<Mutex<Connection>>, data: &GpuResultInsert) -> Result<()> {
-    let conn = conn.lock().await;
-    conn.execute(
-        r#"
-        INSERT INTO gpu_results (
-            host_id,
-            gpu_index,
-            name,
-            memory_total_mb,
-            memory_used_mb,
-            temperature_c,
-            raw_output
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-        "#,
-        params![
-            data.host_id,
-            data.gpu_index as i64,
-            data.name,
-            data.memory_total_mb.map(|v| v as i64),
-            data.memory_used_mb.map(|v| v as i64),
-            data.temperature_c,
-            data.raw_output,
-        ],
-    )?;
+#[derive(Serialize)]
+pub struct GpuProcessInsert {
+    pub host_id: String,
+    pub gpu_index: u32,
+    pub gpu_uuid: Option<String>,
+    pub pid: u32,
+    pub user: String,
+    pub process_name: String,
+    pub used_memory_mb: Option<u64>,
+}
+
+/// Stores every GPU and compute process of one sample under a single
+/// timestamp, so queries can pick the latest sample of a host as a whole.
+pub async fn store_gpu_sample(
+    conn: &Arc<Mutex<Connection>>,
+    gpus: &[GpuResultInsert],
+    processes: &[GpuProcessInsert],
+) -> Result<()> {
+    let mut conn = conn.lock().await;
+    let tx = conn.transaction()?;
+    let timestamp: String = tx.query_row("SELECT CURRENT_TIMESTAMP", [], |row| row.get(0))?;
+
+    for data in gpus {
+        tx.execute(
+            r#"
+            INSERT INTO gpu_results (
+                host_id,
+                gpu_key,
+                gpu_index,
+                name,
+                memory_total_mb,
+                memory_used_mb,
+                temperature_c,
+                gpu_uuid,
+                pci_bus_id,
+                parent_uuid,
+                mig_device_index,
+                utilization_gpu,
+                utilization_memory,
+                power_draw_w,
+                power_limit_w,
+                vendor,
+                raw_output,
+                timestamp
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
+            "#,
+            params![
+                data.host_id,
+                data.gpu_key,
+                data.gpu_index as i64,
+                data.name,
+                data.memory_total_mb.map(|v| v as i64),
+                data.memory_used_mb.map(|v| v as i64),
+                data.temperature_c,
+                data.gpu_uuid,
+                data.pci_bus_id,
+                data.parent_uuid,
+                data.mig_device_index.map(|v| v as i64),
+                data.utilization_gpu,
+                data.utilization_memory,
+                data.power_draw_w,
+                data.power_limit_w,
+                data.vendor,
+                data.raw_output,
+                timestamp,
+            ],
+        )?;
+    }
+
+    for data in processes {
+        tx.execute(
+            r#"
+            INSERT INTO gpu_process_results (
+                host_id,
+                gpu_index,
+                gpu_uuid,
+                pid,
+                user,
+                process_name,
+                used_memory_mb,
+                timestamp
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#,
+            params![
+                data.host_id,
+                data.gpu_index as i64,
+                data.gpu_uuid,
+                data.pid as i64,
+                data.user,
+                data.process_name,
+                data.used_memory_mb.map(|v| v as i64),
+                timestamp,
+            ],
+        )?;
+    }
+
+    tx.commit()?;
     Ok(())
 }
@@ -6,29 +6,94 @@ use tokio::sync::Mutex;
 #[derive(Debug, Clone)]
 pub struct GpuResultRow {
     pub host_id: String,
+    pub gpu_key: String,
     pub gpu_index: u32,
+    pub gpu_uuid: Option<String>,
+    pub pci_bus_id: Option<String>,
+    pub parent_uuid: Option<String>,
+    pub mig_device_index: Option<u32>,
     pub name: String,
     pub memory_total_mb: Option<u64>,
     pub memory_used_mb: Option<u64>,
     pub temperature_c: Option<f32>,
+    pub utilization_gpu: Option<f32>,
+    pub utilization_memory: Option<f32>,
+    pub power_draw_w: Option<f32>,
+    pub power_limit_w: Option<f32>,
+    pub vendor: Option<String>,
     #[allow(dead_code)]
     pub raw_output: Option<String>,
 }
 
+#[derive(Debug, Clone)]
+pub struct GpuProcessRow {
+    pub host_id: String,
+    pub gpu_index: u32,
+    pub gpu_uuid: Option<String>,
+    pub pid: u32,
+    pub user: String,
+    pub process_name: String,
+    pub used_memory_mb: Option<u64>,
+}
+
 pub async fn fetch_latest_gpu_all(conn: &Arc<Mutex<Connection>>) -> Result<Vec<GpuResultRow>> {
     let conn = conn.lock().await;
     let mut stmt = conn.prepare(
-        "SELECT g.host_id, g.gpu_index, g.name, g.memory_total_mb, g.memory_used_mb, g.temperature_c, g.raw_output \n         FROM gpu_results g \n         JOIN (SELECT host_id, gpu_index, MAX(timestamp) AS max_ts FROM gpu_results GROUP BY host_id, gpu_index) t \n           ON g.host_id = t.host_id AND g.gpu_index = t.gpu_index AND g.timestamp = t.max_ts",
+        "SELECT g.host_id, COALESCE(g.gpu_key, 'index:' || g.gpu_index), g.gpu_index, g.gpu_uuid, g.pci_bus_id, \
+                g.parent_uuid, g.mig_device_index, g.name, g.memory_total_mb, g.memory_used_mb, g.temperature_c, \
+                g.utilization_gpu, g.utilization_memory, g.power_draw_w, g.power_limit_w, g.vendor, g.raw_output \
+         FROM gpu_results g \
+         JOIN (SELECT host_id, MAX(timestamp) AS max_ts FROM gpu_results GROUP BY host_id) t \
+           ON g.host_id = t.host_id AND g.timestamp = t.max_ts",
     )?;
     let rows = stmt.query_map([], |row| {
         Ok(GpuResultRow {
+            host_id: row.get::<_, String>(0)?,
+            gpu_key: row.get::<_, String>(1)?,
+            gpu_index: row.get::<_, i64>(2)? as u32,
+            gpu_uuid: row.get::<_, Option<String>>(3)?,
+            pci_bus_id: row.get::<_, Option<String>>(4)?,
+            parent_uuid: row.get::<_, Option<String>>(5)?,
+            mig_device_index: row.get::<_, Option<i64>>(6)?.map(|v| v as u32),
+            name: row.get::<_, String>(7)?,
+            memory_total_mb: row.get::<_, Option<i64>>(8)?.map(|v| v as u64),
+            memory_used_mb: row.get::<_, Option<i64>>(9)?.map(|v| v as u64),
+            temperature_c: row.get::<_, Option<f64>>(10)?.map(|v| v as f32),
+            utilization_gpu: row.get::<_, Option<f64>>(11)?.map(|v| v as f32),
+            utilization_memory: row.get::<_, Option<f64>>(12)?.map(|v| v as f32),
+            power_draw_w: row.get::<_, Option<f64>>(13)?.map(|v| v as f32),
+            power_limit_w: row.get::<_, Option<f64>>(14)?.map(|v| v as f32),
+            vendor: row.get::<_, Option<String>>(15)?,
+            raw_output: row.get::<_, Option<String>>(16)?,
+        })
+    })?;
+    let mut results = Vec::new();
+    for r in rows {
+        results.push(r?);
+    }
+    Ok(results)
+}
+
+pub async fn fetch_latest_gpu_processes_all(
+    conn: &Arc<Mutex<Connection>>,
+) -> Result<Vec<GpuProcessRow>> {
+    let conn = conn.lock().await;
+    let mut stmt = conn.prepare(
+        "SELECT p.host_id, p.gpu_index, p.gpu_uuid, p.pid, p.user, p.process_name, p.used_memory_mb \
+         FROM gpu_process_results p \
+         JOIN (SELECT host_id, MAX(timestamp) AS max_ts FROM gpu_results GROUP BY host_id) t \
+           ON p.host_id = t.host_id AND p.timestamp = t.max_ts \
+         ORDER BY p.host_id, p.used_memory_mb DESC",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(GpuProcessRow {
             host_id: row.get::<_, String>(0)?,
             gpu_index: row.get::<_, i64>(1)? as u32,
-            name: row.get::<_, String>(2)?,
-            memory_total_mb: row.get::<_, Option<i64>>(3)?.map(|v| v as u64),
-            memory_used_mb: row.get::<_, Option<i64>>(4)?.map(|v| v as u64),
-            temperature_c: row.get::<_, Option<f64>>(5)?.map(|v| v as f32),
-            raw_output: row.get::<_, Option<String>>(6)?,
+            gpu_uuid: row.get::<_, Option<String>>(2)?,
+            pid: row.get::<_, i64>(3)? as u32,
+            user: row.get::<_, String>(4)?,
+            process_name: row.get::<_, String>(5)?,
+            used_memory_mb: row.get::<_, Option<i64>>(6)?.map(|v| v as u64),
         })
     })?;
     let mut results = Vec::new();
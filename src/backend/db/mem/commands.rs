@@ -10,6 +10,11 @@ pub struct MemResultInsert {
     pub total_mb: u64,
     pub used_mb: u64,
     pub free_mb: u64,
+    pub available_mb: u64,
+    pub buffers_cache_mb: u64,
+    pub shared_mb: u64,
+    pub swap_total_mb: u64,
+    pub swap_used_mb: u64,
     pub used_percent: f32,
 }
 
@@ -17,14 +22,30 @@ pub async fn store_mem_result(conn: &Arc<Mutex<Connection>>, data: &MemResultIns
     let conn = conn.lock().await;
     conn.execute(
         r#"
-        INSERT INTO mem_results (host_id, total_mb, used_mb, free_mb, used_percent)
-        VALUES (?1, ?2, ?3, ?4, ?5)
+        INSERT INTO mem_results (
+            host_id,
+            total_mb,
+            used_mb,
+            free_mb,
+            available_mb,
+            buffers_cache_mb,
+            shared_mb,
+            swap_total_mb,
+            swap_used_mb,
+            used_percent
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
         "#,
         params![
             data.host_id,
             data.total_mb,
             data.used_mb,
             data.free_mb,
+            data.available_mb,
+            data.buffers_cache_mb,
+            data.shared_mb,
+            data.swap_total_mb,
+            data.swap_used_mb,
             data.used_percent,
         ],
     )?;
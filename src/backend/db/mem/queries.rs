@@ -9,13 +9,19 @@ pub struct MemResultRow {
     pub total_mb: u64,
     pub used_mb: u64,
     pub free_mb: u64,
+    pub available_mb: u64,
+    pub buffers_cache_mb: u64,
+    pub shared_mb: u64,
+    pub swap_total_mb: u64,
+    pub swap_used_mb: u64,
     pub used_percent: f32,
 }
 
 pub async fn fetch_latest_mem_all(conn: &Arc<Mutex<Connection>>) -> Result<Vec<MemResultRow>> {
     let conn = conn.lock().await;
     let mut stmt = conn.prepare(
-        "SELECT m.host_id, m.total_mb, m.used_mb, m.free_mb, m.used_percent \
+        "SELECT m.host_id, m.total_mb, m.used_mb, m.free_mb, m.available_mb, m.buffers_cache_mb, \
+                m.shared_mb, m.swap_total_mb, m.swap_used_mb, m.used_percent \
          FROM mem_results m \
          JOIN (SELECT host_id, MAX(timestamp) AS max_ts FROM mem_results GROUP BY host_id) t \
            ON m.host_id = t.host_id AND m.timestamp = t.max_ts",
@@ -26,7 +32,12 @@ pub async fn fetch_latest_mem_all(conn: &Arc<Mutex<Connection>>) -> Result<Vec<M
             total_mb: row.get::<_, i64>(1)? as u64,
             used_mb: row.get::<_, i64>(2)? as u64,
             free_mb: row.get::<_, i64>(3)? as u64,
-            used_percent: row.get::<_, f64>(4)? as f32,
+            available_mb: row.get::<_, i64>(4)? as u64,
+            buffers_cache_mb: row.get::<_, i64>(5)? as u64,
+            shared_mb: row.get::<_, i64>(6)? as u64,
+            swap_total_mb: row.get::<_, i64>(7)? as u64,
+            swap_used_mb: row.get::<_, i64>(8)? as u64,
+            used_percent: row.get::<_, f64>(9)? as f32,
         })
     })?;
 
@@ -41,24 +52,33 @@ pub async fn fetch_latest_mem_all(conn: &Arc<Mutex<Connection>>) -> Result<Vec<M
 pub struct MemTimelineRow {
     pub host_id: String,
     pub used_percent: f32,
+    pub cache_percent: f32,
+    pub swap_percent: f32,
     pub timestamp: String,
 }
 
+/// Raw samples collected at or after `since` (seconds since the epoch).
 pub async fn fetch_mem_usage_timeline(
     conn: &Arc<Mutex<Connection>>,
+    since: i64,
 ) -> Result<Vec<MemTimelineRow>> {
     let conn = conn.lock().await;
     let mut stmt = conn.prepare(
-        "SELECT host_id, used_percent, timestamp \
+        "SELECT host_id, used_percent, \
+                CASE WHEN total_mb > 0 THEN buffers_cache_mb * 100.0 / total_mb ELSE 0 END, \
+                CASE WHEN total_mb > 0 THEN swap_used_mb * 100.0 / total_mb ELSE 0 END, \
+                timestamp \
          FROM mem_results \
-         ORDER BY timestamp DESC, host_id \
-         LIMIT 1000",
+         WHERE timestamp >= datetime(?1, 'unixepoch') \
+         ORDER BY timestamp DESC, host_id",
     )?;
-    let rows = stmt.query_map([], |row| {
+    let rows = stmt.query_map([since], |row| {
         Ok(MemTimelineRow {
             host_id: row.get::<_, String>(0)?,
             used_percent: row.get::<_, f64>(1)? as f32,
-            timestamp: row.get::<_, String>(2)?,
+            cache_percent: row.get::<_, f64>(2)? as f32,
+            swap_percent: row.get::<_, f64>(3)? as f32,
+            timestamp: row.get::<_, String>(4)?,
         })
     })?;
     let mut results = vec![];
@@ -1,10 +1,19 @@
 use directories::ProjectDirs;
 use rusqlite::Connection;
 use std::path::PathBuf;
+pub mod capabilities;
 pub mod cpu;
+pub mod custom;
 pub mod disk;
+pub mod facts;
 pub mod gpu;
 pub mod mem;
+pub mod net;
+pub mod packages;
+pub mod ports;
+pub mod rollup;
+pub mod session;
+pub mod telemetry;
 
 pub fn get_default_db_path() -> PathBuf {
     let proj_dirs = ProjectDirs::from("com", "tsugumi-sys", "SshMonitor")
@@ -18,7 +27,12 @@ pub fn init_db_connection() -> Connection {
     let db_path = get_default_db_path();
 
     let conn = Connection::open(&db_path).expect("❌ Failed to open sqlite db");
+    init_schema(&conn);
+    conn
+}
 
+/// Creates missing tables and columns.
+pub fn init_schema(conn: &Connection) {
     conn.execute(
         r#"
         CREATE TABLE IF NOT EXISTS job_results (
@@ -58,13 +72,29 @@ pub fn init_db_connection() -> Connection {
             used_mb INTEGER NOT NULL,
             free_mb INTEGER NOT NULL,
             used_percent REAL NOT NULL,
-            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+            available_mb INTEGER NOT NULL DEFAULT 0,
+            buffers_cache_mb INTEGER NOT NULL DEFAULT 0,
+            shared_mb INTEGER NOT NULL DEFAULT 0,
+            swap_total_mb INTEGER NOT NULL DEFAULT 0,
+            swap_used_mb INTEGER NOT NULL DEFAULT 0
         )
         "#,
         [],
     )
     .expect("❌ Failed to create mem_results table");
 
+    for column in [
+        "available_mb",
+        "buffers_cache_mb",
+        "shared_mb",
+        "swap_total_mb",
+        "swap_used_mb",
+    ] {
+        add_column_if_missing(conn, "mem_results", column, "INTEGER NOT NULL DEFAULT 0")
+            .expect("❌ Failed to migrate mem_results table");
+    }
+
     conn.execute(
         r#"
         CREATE TABLE IF NOT EXISTS disk_results (
@@ -75,13 +105,29 @@ pub fn init_db_connection() -> Connection {
             used_mb INTEGER NOT NULL,
             available_mb INTEGER NOT NULL,
             used_percent REAL NOT NULL,
-            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+            device TEXT,
+            fs_type TEXT,
+            inodes_total INTEGER,
+            inodes_used INTEGER,
+            inodes_used_percent REAL
         )
         "#,
         [],
     )
     .expect("❌ Failed to create disk_results table");
 
+    for (column, definition) in [
+        ("device", "TEXT"),
+        ("fs_type", "TEXT"),
+        ("inodes_total", "INTEGER"),
+        ("inodes_used", "INTEGER"),
+        ("inodes_used_percent", "REAL"),
+    ] {
+        add_column_if_missing(conn, "disk_results", column, definition)
+            .expect("❌ Failed to migrate disk_results table");
+    }
+
     conn.execute(
         r#"
         CREATE TABLE IF NOT EXISTS gpu_results (
@@ -93,32 +139,223 @@ pub fn init_db_connection() -> Connection {
             memory_used_mb INTEGER,
             temperature_c REAL,
             raw_output TEXT,
-            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+            gpu_uuid TEXT,
+            utilization_gpu REAL,
+            utilization_memory REAL,
+            power_draw_w REAL,
+            power_limit_w REAL,
+            vendor TEXT,
+            gpu_key TEXT,
+            pci_bus_id TEXT,
+            parent_uuid TEXT,
+            mig_device_index INTEGER
         )
         "#,
         [],
     )
     .expect("❌ Failed to create gpu_results table");
 
+    for (column, definition) in [
+        ("gpu_uuid", "TEXT"),
+        ("utilization_gpu", "REAL"),
+        ("utilization_memory", "REAL"),
+        ("power_draw_w", "REAL"),
+        ("power_limit_w", "REAL"),
+        ("vendor", "TEXT"),
+        ("gpu_key", "TEXT"),
+        ("pci_bus_id", "TEXT"),
+        ("parent_uuid", "TEXT"),
+        ("mig_device_index", "INTEGER"),
+    ] {
+        add_column_if_missing(conn, "gpu_results", column, definition)
+            .expect("❌ Failed to migrate gpu_results table");
+    }
+
     conn.execute(
         r#"
-        DELETE FROM job_results
-        WHERE timestamp < datetime('now', '-1 hour')
+        CREATE TABLE IF NOT EXISTS gpu_process_results (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            host_id TEXT NOT NULL,
+            gpu_index INTEGER NOT NULL,
+            gpu_uuid TEXT,
+            pid INTEGER NOT NULL,
+            user TEXT NOT NULL,
+            process_name TEXT NOT NULL,
+            used_memory_mb INTEGER,
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+        )
         "#,
         [],
     )
-    .expect("❌ Failed to delete old job_results");
+    .expect("❌ Failed to create gpu_process_results table");
 
-    for table in ["cpu_results", "mem_results", "disk_results", "gpu_results"] {
-        conn.execute(
-            &format!(
-                "DELETE FROM {} WHERE timestamp < datetime('now', '-1 hour')",
-                table
-            ),
-            [],
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS session_results (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            host_id TEXT NOT NULL,
+            user_count INTEGER NOT NULL,
+            session_count INTEGER NOT NULL,
+            sessions_json TEXT NOT NULL,
+            last_logins_json TEXT NOT NULL,
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+        [],
+    )
+    .expect("❌ Failed to create session_results table");
+
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS port_results (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            host_id TEXT NOT NULL,
+            port_count INTEGER NOT NULL,
+            listeners_json TEXT NOT NULL,
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+        [],
+    )
+    .expect("❌ Failed to create port_results table");
+
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS host_facts (
+            host_id TEXT PRIMARY KEY,
+            os_name TEXT NOT NULL,
+            kernel_release TEXT NOT NULL,
+            arch TEXT NOT NULL,
+            uname TEXT NOT NULL,
+            cpu_model TEXT NOT NULL,
+            cpu_count INTEGER NOT NULL,
+            cpu_sockets INTEGER,
+            vendor TEXT,
+            product TEXT,
+            total_memory_mb INTEGER NOT NULL,
+            virtualization TEXT,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+        [],
+    )
+    .expect("❌ Failed to create host_facts table");
+
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS host_capabilities (
+            host_id TEXT PRIMARY KEY,
+            os TEXT NOT NULL,
+            shell TEXT,
+            bash INTEGER NOT NULL,
+            nvidia_smi INTEGER NOT NULL,
+            rocm_smi INTEGER NOT NULL,
+            docker INTEGER NOT NULL,
+            systemctl INTEGER NOT NULL,
+            lscpu INTEGER NOT NULL,
+            sudo INTEGER NOT NULL,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
         )
-        .expect("❌ Failed to delete old metrics");
+        "#,
+        [],
+    )
+    .expect("❌ Failed to create host_capabilities table");
+
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS package_results (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            host_id TEXT NOT NULL,
+            manager TEXT,
+            pending_count INTEGER NOT NULL,
+            security_count INTEGER,
+            reboot_required INTEGER NOT NULL,
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+        [],
+    )
+    .expect("❌ Failed to create package_results table");
+
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS net_results (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            host_id TEXT NOT NULL,
+            rx_bytes_per_sec REAL NOT NULL,
+            tx_bytes_per_sec REAL NOT NULL,
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+        [],
+    )
+    .expect("❌ Failed to create net_results table");
+
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS cycle_results (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            host_id TEXT NOT NULL,
+            status TEXT NOT NULL,
+            connect_ms INTEGER,
+            auth_ms INTEGER,
+            command_ms INTEGER,
+            parse_ms INTEGER,
+            queue_lag_ms INTEGER NOT NULL,
+            jobs_ok INTEGER NOT NULL,
+            jobs_failed INTEGER NOT NULL,
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+            clock_offset_ms REAL,
+            clock_round_trip_ms REAL
+        )
+        "#,
+        [],
+    )
+    .expect("❌ Failed to create cycle_results table");
+
+    for column in ["clock_offset_ms", "clock_round_trip_ms"] {
+        add_column_if_missing(conn, "cycle_results", column, "REAL")
+            .expect("❌ Failed to migrate cycle_results table");
     }
 
-    conn
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS metric_rollups (
+            host_id TEXT NOT NULL,
+            metric TEXT NOT NULL,
+            resolution INTEGER NOT NULL,
+            bucket INTEGER NOT NULL,
+            avg REAL NOT NULL,
+            min REAL NOT NULL,
+            max REAL NOT NULL,
+            samples INTEGER NOT NULL,
+            PRIMARY KEY (metric, resolution, bucket, host_id)
+        )
+        "#,
+        [],
+    )
+    .expect("❌ Failed to create metric_rollups table");
+}
+
+/// Adds a column to an existing table when it is missing, so databases created
+/// by older versions keep working after the schema grows.
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|name| name.ok())
+        .any(|name| name == column);
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+    Ok(())
 }
@@ -0,0 +1,28 @@
+use anyhow::Result;
+use rusqlite::{Connection, params};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Serialize)]
+pub struct NetResultInsert {
+    pub host_id: String,
+    pub rx_bytes_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+}
+
+pub async fn store_net_result(conn: &Arc<Mutex<Connection>>, data: &NetResultInsert) -> Result<()> {
+    let conn = conn.lock().await;
+    conn.execute(
+        r#"
+        INSERT INTO net_results (
+            host_id,
+            rx_bytes_per_sec,
+            tx_bytes_per_sec
+        )
+        VALUES (?1, ?2, ?3)
+        "#,
+        params![data.host_id, data.rx_bytes_per_sec, data.tx_bytes_per_sec],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use anyhow::Result;
+use rusqlite::Connection;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub struct NetResultRow {
+    pub host_id: String,
+    pub rx_bytes_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+    pub timestamp: String,
+}
+
+pub async fn fetch_latest_net_all(conn: &Arc<Mutex<Connection>>) -> Result<Vec<NetResultRow>> {
+    let conn = conn.lock().await;
+    let mut stmt = conn.prepare(
+        "SELECT n.host_id, n.rx_bytes_per_sec, n.tx_bytes_per_sec, n.timestamp \
+         FROM net_results n \
+         JOIN (SELECT host_id, MAX(id) AS max_id FROM net_results GROUP BY host_id) t \
+           ON n.id = t.max_id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(NetResultRow {
+            host_id: row.get::<_, String>(0)?,
+            rx_bytes_per_sec: row.get::<_, f64>(1)?,
+            tx_bytes_per_sec: row.get::<_, f64>(2)?,
+            timestamp: row.get::<_, String>(3)?,
+        })
+    })?;
+    let mut results = vec![];
+    for r in rows {
+        results.push(r?);
+    }
+    Ok(results)
+}
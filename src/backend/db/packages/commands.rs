@@ -0,0 +1,41 @@
+use anyhow::Result;
+use rusqlite::{Connection, params};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Serialize)]
+pub struct PackageResultInsert {
+    pub host_id: String,
+    pub manager: Option<String>,
+    pub pending_count: u32,
+    pub security_count: Option<u32>,
+    pub reboot_required: bool,
+}
+
+pub async fn store_package_result(
+    conn: &Arc<Mutex<Connection>>,
+    data: &PackageResultInsert,
+) -> Result<()> {
+    let conn = conn.lock().await;
+    conn.execute(
+        r#"
+        INSERT INTO package_results (
+            host_id,
+            manager,
+            pending_count,
+            security_count,
+            reboot_required
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        "#,
+        params![
+            data.host_id,
+            data.manager,
+            data.pending_count as i64,
+            data.security_count.map(|v| v as i64),
+            data.reboot_required,
+        ],
+    )?;
+    Ok(())
+}
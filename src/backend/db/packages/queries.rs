@@ -0,0 +1,39 @@
+use anyhow::Result;
+use rusqlite::Connection;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub struct PackageResultRow {
+    pub host_id: String,
+    pub manager: Option<String>,
+    pub pending_count: u32,
+    pub security_count: Option<u32>,
+    pub reboot_required: bool,
+}
+
+pub async fn fetch_latest_packages_all(
+    conn: &Arc<Mutex<Connection>>,
+) -> Result<Vec<PackageResultRow>> {
+    let conn = conn.lock().await;
+    let mut stmt = conn.prepare(
+        "SELECT p.host_id, p.manager, p.pending_count, p.security_count, p.reboot_required \
+         FROM package_results p \
+         JOIN (SELECT host_id, MAX(timestamp) AS max_ts FROM package_results GROUP BY host_id) t \
+           ON p.host_id = t.host_id AND p.timestamp = t.max_ts",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(PackageResultRow {
+            host_id: row.get::<_, String>(0)?,
+            manager: row.get::<_, Option<String>>(1)?,
+            pending_count: row.get::<_, i64>(2)? as u32,
+            security_count: row.get::<_, Option<i64>>(3)?.map(|v| v as u32),
+            reboot_required: row.get::<_, bool>(4)?,
+        })
+    })?;
+    let mut results = vec![];
+    for r in rows {
+        results.push(r?);
+    }
+    Ok(results)
+}
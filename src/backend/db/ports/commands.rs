@@ -0,0 +1,28 @@
+use crate::backend::jobs::ports::ListeningPort;
+use anyhow::Result;
+use rusqlite::{Connection, params};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Serialize)]
+pub struct PortResultInsert {
+    pub host_id: String,
+    pub listeners: Vec<ListeningPort>,
+}
+
+pub async fn store_port_result(
+    conn: &Arc<Mutex<Connection>>,
+    data: &PortResultInsert,
+) -> Result<()> {
+    let listeners_json = serde_json::to_string(&data.listeners)?;
+    let conn = conn.lock().await;
+    conn.execute(
+        r#"
+        INSERT INTO port_results (host_id, port_count, listeners_json)
+        VALUES (?1, ?2, ?3)
+        "#,
+        params![data.host_id, data.listeners.len() as i64, listeners_json],
+    )?;
+    Ok(())
+}
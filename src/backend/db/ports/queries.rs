@@ -0,0 +1,50 @@
+use crate::backend::jobs::ports::ListeningPort;
+use anyhow::Result;
+use rusqlite::Connection;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub struct PortResultRow {
+    pub host_id: String,
+    pub listeners: Vec<ListeningPort>,
+    pub previous_listeners: Option<Vec<ListeningPort>>,
+}
+
+/// Returns the latest listener sample of every host together with the sample
+/// before it, so callers can tell which ports appeared or disappeared.
+pub async fn fetch_latest_ports_all(conn: &Arc<Mutex<Connection>>) -> Result<Vec<PortResultRow>> {
+    let conn = conn.lock().await;
+    let mut stmt = conn.prepare(
+        "SELECT host_id, listeners_json, rn FROM ( \
+           SELECT host_id, listeners_json, \
+                  ROW_NUMBER() OVER (PARTITION BY host_id ORDER BY timestamp DESC, id DESC) AS rn \
+           FROM port_results \
+         ) \
+         WHERE rn <= 2 \
+         ORDER BY host_id, rn",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let listeners_json: String = row.get(1)?;
+        let listeners: Vec<ListeningPort> =
+            serde_json::from_str(&listeners_json).unwrap_or_default();
+        Ok((row.get::<_, String>(0)?, listeners, row.get::<_, i64>(2)?))
+    })?;
+
+    let mut results: Vec<PortResultRow> = vec![];
+    for r in rows {
+        let (host_id, listeners, rank) = r?;
+        if rank == 1 {
+            results.push(PortResultRow {
+                host_id,
+                listeners,
+                previous_listeners: None,
+            });
+        } else if let Some(latest) = results.last_mut()
+            && latest.host_id == host_id
+        {
+            latest.previous_listeners = Some(listeners);
+        }
+    }
+    Ok(results)
+}
@@ -0,0 +1,118 @@
+use anyhow::Result;
+use rusqlite::{Connection, OptionalExtension, params};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// The first bucket of `metric` at `resolution` that has not been rolled up.
+fn next_bucket(conn: &Connection, metric: &str, resolution: i64) -> Result<i64> {
+    let last: Option<i64> = conn
+        .query_row(
+            "SELECT MAX(bucket) FROM metric_rollups WHERE metric = ?1 AND resolution = ?2",
+            params![metric, resolution],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+    Ok(last.map_or(0, |bucket| bucket + resolution))
+}
+
+/// Averages `value` of the raw `table` into buckets of `resolution` seconds,
+/// for every complete bucket before `now` not rolled up yet. Returns how many
+/// buckets were written.
+pub async fn roll_up_raw(
+    conn: &Arc<Mutex<Connection>>,
+    metric: &str,
+    table: &str,
+    value: &str,
+    resolution: i64,
+    now: i64,
+) -> Result<usize> {
+    let conn = conn.lock().await;
+    let from = next_bucket(&conn, metric, resolution)?;
+    let until = now / resolution * resolution;
+    let written = conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO metric_rollups \
+                 (host_id, metric, resolution, bucket, avg, min, max, samples) \
+             SELECT host_id, ?1, ?2, \
+                    CAST(strftime('%s', timestamp) AS INTEGER) / ?2 * ?2 AS bucket, \
+                    AVG({value}), MIN({value}), MAX({value}), COUNT(*) \
+             FROM {table} \
+             WHERE timestamp >= datetime(?3, 'unixepoch') \
+               AND timestamp < datetime(?4, 'unixepoch') \
+             GROUP BY host_id, bucket"
+        ),
+        params![metric, resolution, from, until],
+    )?;
+    Ok(written)
+}
+
+/// Merges the `from_resolution` buckets of `metric` into coarser ones of
+/// `resolution` seconds, weighting each by its sample count.
+pub async fn roll_up_rollups(
+    conn: &Arc<Mutex<Connection>>,
+    metric: &str,
+    from_resolution: i64,
+    resolution: i64,
+    now: i64,
+) -> Result<usize> {
+    let conn = conn.lock().await;
+    let from = next_bucket(&conn, metric, resolution)?;
+    let until = now / resolution * resolution;
+    let written = conn.execute(
+        "INSERT OR REPLACE INTO metric_rollups \
+             (host_id, metric, resolution, bucket, avg, min, max, samples) \
+         SELECT host_id, metric, ?3, bucket / ?3 * ?3 AS coarse, \
+                SUM(avg * samples) / SUM(samples), MIN(min), MAX(max), SUM(samples) \
+         FROM metric_rollups \
+         WHERE metric = ?1 AND resolution = ?2 AND bucket >= ?4 AND bucket < ?5 \
+         GROUP BY host_id, coarse",
+        params![metric, from_resolution, resolution, from, until],
+    )?;
+    Ok(written)
+}
+
+/// Deletes the rows of a raw `table` collected before `cutoff`.
+pub async fn delete_raw_before(
+    conn: &Arc<Mutex<Connection>>,
+    table: &str,
+    cutoff: i64,
+) -> Result<usize> {
+    let conn = conn.lock().await;
+    let deleted = conn.execute(
+        &format!("DELETE FROM {table} WHERE timestamp < datetime(?1, 'unixepoch')"),
+        params![cutoff],
+    )?;
+    Ok(deleted)
+}
+
+/// Like [`delete_raw_before`], but keeps each host's newest row, for tables
+/// whose latest row is shown until the next, possibly much later, sample.
+pub async fn delete_superseded_before(
+    conn: &Arc<Mutex<Connection>>,
+    table: &str,
+    cutoff: i64,
+) -> Result<usize> {
+    let conn = conn.lock().await;
+    let deleted = conn.execute(
+        &format!(
+            "DELETE FROM {table} WHERE timestamp < datetime(?1, 'unixepoch')              AND timestamp < (SELECT MAX(timestamp) FROM {table} newest                               WHERE newest.host_id = {table}.host_id)"
+        ),
+        params![cutoff],
+    )?;
+    Ok(deleted)
+}
+
+/// Deletes the buckets of `resolution` that start before `cutoff`.
+pub async fn delete_rollups_before(
+    conn: &Arc<Mutex<Connection>>,
+    resolution: i64,
+    cutoff: i64,
+) -> Result<usize> {
+    let conn = conn.lock().await;
+    let deleted = conn.execute(
+        "DELETE FROM metric_rollups WHERE resolution = ?1 AND bucket < ?2",
+        params![resolution, cutoff],
+    )?;
+    Ok(deleted)
+}
@@ -0,0 +1,41 @@
+use anyhow::Result;
+use rusqlite::{Connection, params};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub struct RollupTimelineRow {
+    pub host_id: String,
+    pub avg: f32,
+    /// Start of the bucket, formatted like the raw tables' timestamps.
+    pub timestamp: String,
+}
+
+/// Buckets of `metric` at `resolution` starting at or after `since`, newest
+/// first like the raw timeline queries.
+pub async fn fetch_rollup_timeline(
+    conn: &Arc<Mutex<Connection>>,
+    metric: &str,
+    resolution: i64,
+    since: i64,
+) -> Result<Vec<RollupTimelineRow>> {
+    let conn = conn.lock().await;
+    let mut stmt = conn.prepare(
+        "SELECT host_id, avg, datetime(bucket, 'unixepoch') \
+         FROM metric_rollups \
+         WHERE metric = ?1 AND resolution = ?2 AND bucket >= ?3 \
+         ORDER BY bucket DESC, host_id",
+    )?;
+    let rows = stmt.query_map(params![metric, resolution, since], |row| {
+        Ok(RollupTimelineRow {
+            host_id: row.get::<_, String>(0)?,
+            avg: row.get::<_, f64>(1)? as f32,
+            timestamp: row.get::<_, String>(2)?,
+        })
+    })?;
+    let mut results = vec![];
+    for r in rows {
+        results.push(r?);
+    }
+    Ok(results)
+}
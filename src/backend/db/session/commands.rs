@@ -0,0 +1,43 @@
+use crate::backend::jobs::session::{LastLoginInfo, SessionInfo};
+use anyhow::Result;
+use rusqlite::{Connection, params};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Serialize)]
+pub struct SessionResultInsert {
+    pub host_id: String,
+    pub user_count: u32,
+    pub sessions: Vec<SessionInfo>,
+    pub last_logins: Vec<LastLoginInfo>,
+}
+
+pub async fn store_session_result(
+    conn: &Arc<Mutex<Connection>>,
+    data: &SessionResultInsert,
+) -> Result<()> {
+    let sessions_json = serde_json::to_string(&data.sessions)?;
+    let last_logins_json = serde_json::to_string(&data.last_logins)?;
+    let conn = conn.lock().await;
+    conn.execute(
+        r#"
+        INSERT INTO session_results (
+            host_id,
+            user_count,
+            session_count,
+            sessions_json,
+            last_logins_json
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        "#,
+        params![
+            data.host_id,
+            data.user_count as i64,
+            data.sessions.len() as i64,
+            sessions_json,
+            last_logins_json
+        ],
+    )?;
+    Ok(())
+}
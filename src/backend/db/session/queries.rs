@@ -0,0 +1,42 @@
+use crate::backend::jobs::session::{LastLoginInfo, SessionInfo};
+use anyhow::Result;
+use rusqlite::Connection;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub struct SessionResultRow {
+    pub host_id: String,
+    pub user_count: u32,
+    pub session_count: u32,
+    pub sessions: Vec<SessionInfo>,
+    pub last_logins: Vec<LastLoginInfo>,
+}
+
+pub async fn fetch_latest_session_all(
+    conn: &Arc<Mutex<Connection>>,
+) -> Result<Vec<SessionResultRow>> {
+    let conn = conn.lock().await;
+    let mut stmt = conn.prepare(
+        "SELECT s.host_id, s.user_count, s.session_count, s.sessions_json, s.last_logins_json \
+         FROM session_results s \
+         JOIN (SELECT host_id, MAX(timestamp) AS max_ts FROM session_results GROUP BY host_id) t \
+           ON s.host_id = t.host_id AND s.timestamp = t.max_ts",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let sessions_json: String = row.get(3)?;
+        let last_logins_json: String = row.get(4)?;
+        Ok(SessionResultRow {
+            host_id: row.get::<_, String>(0)?,
+            user_count: row.get::<_, i64>(1)? as u32,
+            session_count: row.get::<_, i64>(2)? as u32,
+            sessions: serde_json::from_str(&sessions_json).unwrap_or_default(),
+            last_logins: serde_json::from_str(&last_logins_json).unwrap_or_default(),
+        })
+    })?;
+    let mut results = vec![];
+    for r in rows {
+        results.push(r?);
+    }
+    Ok(results)
+}
@@ -0,0 +1,62 @@
+use anyhow::Result;
+use rusqlite::{Connection, params};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// One collection cycle of a host. Timings are in milliseconds and missing
+/// when the cycle failed before the phase ran.
+#[derive(Serialize)]
+pub struct CycleResultInsert {
+    pub host_id: String,
+    pub status: String,
+    pub connect_ms: Option<u64>,
+    pub auth_ms: Option<u64>,
+    pub command_ms: Option<u64>,
+    pub parse_ms: Option<u64>,
+    /// How far the host's clock is ahead of ours.
+    pub clock_offset_ms: Option<f64>,
+    pub clock_round_trip_ms: Option<f64>,
+    pub queue_lag_ms: u64,
+    pub jobs_ok: usize,
+    pub jobs_failed: usize,
+}
+
+pub async fn store_cycle_result(
+    conn: &Arc<Mutex<Connection>>,
+    data: &CycleResultInsert,
+) -> Result<()> {
+    let conn = conn.lock().await;
+    conn.execute(
+        r#"
+        INSERT INTO cycle_results (
+            host_id,
+            status,
+            connect_ms,
+            auth_ms,
+            command_ms,
+            parse_ms,
+            queue_lag_ms,
+            jobs_ok,
+            jobs_failed,
+            clock_offset_ms,
+            clock_round_trip_ms
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+        "#,
+        params![
+            data.host_id,
+            data.status,
+            data.connect_ms,
+            data.auth_ms,
+            data.command_ms,
+            data.parse_ms,
+            data.queue_lag_ms,
+            data.jobs_ok,
+            data.jobs_failed,
+            data.clock_offset_ms,
+            data.clock_round_trip_ms
+        ],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use anyhow::Result;
+use rusqlite::Connection;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub struct CycleResultRow {
+    pub host_id: String,
+    pub status: String,
+    pub connect_ms: Option<u64>,
+    pub auth_ms: Option<u64>,
+    pub command_ms: Option<u64>,
+    pub parse_ms: Option<u64>,
+    pub queue_lag_ms: u64,
+    pub jobs_ok: usize,
+    pub jobs_failed: usize,
+    pub clock_offset_ms: Option<f64>,
+    pub clock_round_trip_ms: Option<f64>,
+    pub timestamp: String,
+}
+
+pub async fn fetch_latest_cycle_all(conn: &Arc<Mutex<Connection>>) -> Result<Vec<CycleResultRow>> {
+    let conn = conn.lock().await;
+    let mut stmt = conn.prepare(
+        "SELECT c.host_id, c.status, c.connect_ms, c.auth_ms, c.command_ms, c.parse_ms, \
+                c.queue_lag_ms, c.jobs_ok, c.jobs_failed, c.clock_offset_ms, \
+                c.clock_round_trip_ms, c.timestamp \
+         FROM cycle_results c \
+         JOIN (SELECT host_id, MAX(id) AS max_id FROM cycle_results GROUP BY host_id) t \
+           ON c.id = t.max_id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(CycleResultRow {
+            host_id: row.get::<_, String>(0)?,
+            status: row.get::<_, String>(1)?,
+            connect_ms: row.get::<_, Option<u64>>(2)?,
+            auth_ms: row.get::<_, Option<u64>>(3)?,
+            command_ms: row.get::<_, Option<u64>>(4)?,
+            parse_ms: row.get::<_, Option<u64>>(5)?,
+            queue_lag_ms: row.get::<_, u64>(6)?,
+            jobs_ok: row.get::<_, usize>(7)?,
+            jobs_failed: row.get::<_, usize>(8)?,
+            clock_offset_ms: row.get::<_, Option<f64>>(9)?,
+            clock_round_trip_ms: row.get::<_, Option<f64>>(10)?,
+            timestamp: row.get::<_, String>(11)?,
+        })
+    })?;
+    let mut results = vec![];
+    for r in rows {
+        results.push(r?);
+    }
+    Ok(results)
+}
+
+/// How a host's collection went over the last hour.
+#[derive(Debug, Clone)]
+pub struct CycleHealthRow {
+    pub host_id: String,
+    pub cycles: u64,
+    pub ok: u64,
+    pub timed_out: u64,
+    /// Average connect-to-parsed time of the cycles that got that far.
+    pub avg_total_ms: Option<f64>,
+    pub avg_queue_lag_ms: f64,
+    pub max_queue_lag_ms: u64,
+}
+
+pub async fn fetch_cycle_health_all(conn: &Arc<Mutex<Connection>>) -> Result<Vec<CycleHealthRow>> {
+    let conn = conn.lock().await;
+    let mut stmt = conn.prepare(
+        "SELECT host_id, COUNT(*), \
+                SUM(status = 'ok'), SUM(status = 'timed_out'), \
+                AVG(connect_ms + auth_ms + command_ms + parse_ms), \
+                AVG(queue_lag_ms), MAX(queue_lag_ms) \
+         FROM cycle_results \
+         WHERE timestamp >= datetime('now', '-1 hour') \
+         GROUP BY host_id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(CycleHealthRow {
+            host_id: row.get::<_, String>(0)?,
+            cycles: row.get::<_, u64>(1)?,
+            ok: row.get::<_, u64>(2)?,
+            timed_out: row.get::<_, u64>(3)?,
+            avg_total_ms: row.get::<_, Option<f64>>(4)?,
+            avg_queue_lag_ms: row.get::<_, f64>(5)?,
+            max_queue_lag_ms: row.get::<_, u64>(6)?,
+        })
+    })?;
+    let mut results = vec![];
+    for r in rows {
+        results.push(r?);
+    }
+    Ok(results)
+}
@@ -0,0 +1,157 @@
+use crate::config::SchedulerConfig;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How much the backoff grows after each batch without changes.
+const BACKOFF_STEP: f64 = 1.5;
+
+/// Only collectors at most this slow are sped up while a host is focused;
+/// packages, facts and the like keep their own schedule.
+const FOCUS_MAX_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How a host's polling currently departs from its collector intervals.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum PollRate {
+    #[default]
+    Normal,
+    /// The host is selected, so its fast collectors run at the focus interval.
+    Focused,
+    /// Its samples have not changed lately; intervals are stretched by this factor.
+    BackedOff(f64),
+}
+
+/// One host's polling rate: fast while it is being looked at, slower the
+/// longer its samples stay the same.
+#[derive(Debug)]
+pub struct AdaptiveRate {
+    focus_interval: Duration,
+    max_backoff: f64,
+    focused: bool,
+    backoff: f64,
+    last_samples: HashMap<String, Value>,
+}
+
+impl AdaptiveRate {
+    pub fn new(config: &SchedulerConfig) -> Self {
+        Self {
+            focus_interval: Duration::from_secs(config.focus_interval_secs),
+            max_backoff: config.max_backoff,
+            focused: false,
+            backoff: 1.0,
+            last_samples: HashMap::new(),
+        }
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    pub fn rate(&self) -> PollRate {
+        if self.focused {
+            PollRate::Focused
+        } else if self.backoff > 1.0 {
+            PollRate::BackedOff(self.backoff)
+        } else {
+            PollRate::Normal
+        }
+    }
+
+    /// The interval a collector configured for `interval` runs at right now.
+    /// Focusing never slows a collector down.
+    pub fn interval(&self, interval: Duration) -> Duration {
+        match self.rate() {
+            PollRate::Focused if interval <= FOCUS_MAX_INTERVAL => {
+                interval.min(self.focus_interval)
+            }
+            PollRate::Focused | PollRate::Normal => interval,
+            PollRate::BackedOff(backoff) => interval.mul_f64(backoff),
+        }
+    }
+
+    /// Compares a batch's samples with the previous ones of the same jobs.
+    /// Any real change resets the backoff; a batch without changes, or without
+    /// samples at all (an unreachable host), stretches it.
+    pub fn observe(&mut self, samples: Vec<(String, Value)>) {
+        let mut changed = false;
+        for (job_name, sample) in samples {
+            changed |= self
+                .last_samples
+                .get(&job_name)
+                .is_none_or(|last| !roughly_equal(last, &sample));
+            self.last_samples.insert(job_name, sample);
+        }
+        self.backoff = if changed {
+            1.0
+        } else {
+            (self.backoff * BACKOFF_STEP).min(self.max_backoff)
+        };
+    }
+}
+
+/// Structural equality that ignores numeric noise: numbers count as equal
+/// within 2 units or 5%, so an idle CPU at 1% vs 2% is not a change.
+fn roughly_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => {
+            let (x, y) = (x.as_f64().unwrap_or(0.0), y.as_f64().unwrap_or(0.0));
+            let diff = (x - y).abs();
+            diff <= 2.0 || diff <= 0.05 * x.abs().max(y.abs())
+        }
+        (Value::Array(x), Value::Array(y)) => {
+            x.len() == y.len() && x.iter().zip(y).all(|(x, y)| roughly_equal(x, y))
+        }
+        (Value::Object(x), Value::Object(y)) => {
+            x.len() == y.len()
+                && x.iter()
+                    .all(|(key, x)| y.get(key).is_some_and(|y| roughly_equal(x, y)))
+        }
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_adaptive_rate_focus_and_backoff() {
+        let mut rate = AdaptiveRate::new(&SchedulerConfig::default());
+        let sample =
+            |usage: f64| vec![("cpu".to_string(), json!({"usage": usage, "cores": [usage]}))];
+
+        rate.observe(sample(1.0));
+        assert_eq!(rate.rate(), PollRate::Normal);
+        rate.observe(sample(2.5));
+        assert_eq!(rate.rate(), PollRate::BackedOff(1.5));
+        rate.observe(vec![]);
+        assert_eq!(rate.rate(), PollRate::BackedOff(2.25));
+        for _ in 0..10 {
+            rate.observe(sample(2.5));
+        }
+        assert_eq!(
+            rate.interval(Duration::from_secs(10)),
+            Duration::from_secs(40)
+        );
+
+        rate.set_focused(true);
+        assert_eq!(rate.rate(), PollRate::Focused);
+        assert_eq!(
+            rate.interval(Duration::from_secs(10)),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            rate.interval(Duration::from_secs(1)),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            rate.interval(Duration::from_secs(3600)),
+            Duration::from_secs(3600)
+        );
+
+        rate.set_focused(false);
+        rate.observe(sample(40.0));
+        assert_eq!(rate.rate(), PollRate::Normal);
+    }
+}
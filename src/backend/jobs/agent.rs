@@ -0,0 +1,361 @@
+use super::framing::Frame;
+use crate::backend::ssh::{run_remote, upload_file};
+use crate::config::AgentConfig;
+use crate::ssh_config::SshHostInfo;
+use anyhow::{Context, Result};
+use log::{info, warn};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+use tokio::time::Instant;
+
+pub const AGENT_PROTOCOL_VERSION: u32 = 1;
+pub const AGENT_JOBS: &[&str] = &["cpu", "mem"];
+pub const AGENT_TAG: &str = "agent";
+
+const AGENT_DIR: &str = ".cache/ssh-monitor";
+const DEPLOY_TIMEOUT: Duration = Duration::from_secs(30);
+const RETRY_AFTER: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug)]
+pub struct AgentBinary {
+    contents: Arc<Vec<u8>>,
+    sha256: String,
+    /// `None` for a configured binary, which may be cross-compiled.
+    arch: Option<&'static str>,
+}
+
+impl AgentBinary {
+    pub fn load(config: &AgentConfig) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        let (path, arch) = match &config.binary {
+            Some(path) => (path.clone(), None),
+            None => (default_binary_path()?, Some(std::env::consts::ARCH)),
+        };
+        let contents =
+            std::fs::read(&path).with_context(|| format!("reading {}", path.display()))?;
+        // A dynamically linked build fails on musl and older glibc hosts,
+        // which are the ones the agent is for.
+        if config.binary.is_none() && !is_static_elf(&contents) {
+            anyhow::bail!(
+                "{} is not a static Linux build; set agent.binary to one",
+                path.display()
+            );
+        }
+        let sha256 = format!("{:x}", Sha256::digest(&contents));
+        Ok(Some(Self {
+            contents: Arc::new(contents),
+            sha256,
+            arch,
+        }))
+    }
+
+    /// Named after the checksum, so a new build never runs an old upload.
+    fn remote_path(&self) -> String {
+        format!("{AGENT_DIR}/agent-{}", &self.sha256[..12])
+    }
+
+    fn check_host(&self, output: &str) -> Result<bool, String> {
+        let mut lines = output.lines().map(str::trim);
+        if lines.next() != Some("Linux") {
+            return Err("the agent only runs on Linux".into());
+        }
+        let host_arch = lines.next().unwrap_or_default();
+        if let Some(arch) = self.arch
+            && host_arch != arch
+        {
+            return Err(format!(
+                "the agent is built for {arch}, host is {host_arch}"
+            ));
+        }
+        Ok(lines
+            .next()
+            .is_some_and(|line| line.starts_with(&self.sha256)))
+    }
+}
+
+/// Where `cargo build --target <arch>-unknown-linux-musl` puts the agent.
+fn default_binary_path() -> Result<PathBuf> {
+    let exe = std::env::current_exe()?;
+    let (Some(profile_dir), Some(profile)) = (exe.parent(), exe.parent().and_then(Path::file_name))
+    else {
+        anyhow::bail!("cannot locate the agent next to {}", exe.display());
+    };
+    let target_dir = profile_dir.parent().unwrap_or(profile_dir);
+    Ok(target_dir
+        .join(format!("{}-unknown-linux-musl", std::env::consts::ARCH))
+        .join(profile)
+        .join("ssh-monitor-agent"))
+}
+
+/// An ELF file without `PT_INTERP` needs no dynamic loader on the host.
+fn is_static_elf(bytes: &[u8]) -> bool {
+    const PT_INTERP: u32 = 3;
+    if bytes.get(..4) != Some(b"\x7fELF".as_slice()) {
+        return false;
+    }
+    let big_endian = bytes.get(5) == Some(&2);
+    let read = |offset: usize, len: usize| -> Option<u64> {
+        let field = bytes.get(offset..offset + len)?;
+        let fold = |acc: u64, byte: &u8| acc << 8 | u64::from(*byte);
+        Some(if big_endian {
+            field.iter().fold(0, fold)
+        } else {
+            field.iter().rev().fold(0, fold)
+        })
+    };
+    let header = match bytes.get(4) {
+        Some(1) => (read(0x1c, 4), read(0x2a, 2), read(0x2c, 2)),
+        Some(2) => (read(0x20, 8), read(0x36, 2), read(0x38, 2)),
+        _ => return false,
+    };
+    let (Some(phoff), Some(phentsize), Some(phnum)) = header else {
+        return false;
+    };
+    (0..phnum).all(|i| {
+        read((phoff + i * phentsize) as usize, 4)
+            .is_some_and(|p_type| p_type != u64::from(PT_INTERP))
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum AgentState {
+    Pending,
+    Ready(String),
+    Unavailable { retry_at: Instant },
+}
+
+#[derive(Debug)]
+pub struct HostAgent {
+    binary: Arc<AgentBinary>,
+    state: AgentState,
+}
+
+impl HostAgent {
+    pub fn new(binary: Arc<AgentBinary>) -> Self {
+        Self {
+            binary,
+            state: AgentState::Pending,
+        }
+    }
+
+    pub fn ready_path(&self) -> Option<&str> {
+        match &self.state {
+            AgentState::Ready(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    pub async fn ensure_deployed(&mut self, host: &SshHostInfo, cancel: &Arc<AtomicBool>) {
+        match self.state {
+            AgentState::Ready(_) => return,
+            AgentState::Unavailable { retry_at } if Instant::now() < retry_at => return,
+            _ => {}
+        }
+        match deploy(host, &self.binary, cancel).await {
+            Ok(path) => {
+                info!("🛰️ Agent ready on '{}' at {path}", host.id);
+                self.state = AgentState::Ready(path);
+            }
+            Err(e) => self.fail(&host.id, &e),
+        }
+    }
+
+    pub fn fail(&mut self, host_id: &str, reason: &str) {
+        warn!("⚠️ Agent unavailable on '{host_id}', using shell collectors: {reason}");
+        self.state = AgentState::Unavailable {
+            retry_at: Instant::now() + RETRY_AFTER,
+        };
+    }
+}
+
+async fn exec(
+    host: &SshHostInfo,
+    command: String,
+    cancel: &Arc<AtomicBool>,
+) -> Result<String, String> {
+    let output = run_remote(host.clone(), command, DEPLOY_TIMEOUT, cancel.clone()).await?;
+    if output.exit_status != 0 {
+        return Err(format!(
+            "exit {}: {}",
+            output.exit_status,
+            output.stderr.trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+fn sha256_command(path: &str) -> String {
+    format!("(sha256sum {path} || shasum -a 256 {path}) 2>/dev/null")
+}
+
+fn host_check_command(path: &str) -> String {
+    format!("sh -c 'uname -s; uname -m; {}; true'", sha256_command(path))
+}
+
+async fn deploy(
+    host: &SshHostInfo,
+    binary: &AgentBinary,
+    cancel: &Arc<AtomicBool>,
+) -> Result<String, String> {
+    let path = binary.remote_path();
+    let check = exec(host, host_check_command(&path), cancel).await?;
+    if !binary.check_host(&check)? {
+        let tmp = format!("{path}.tmp");
+        exec(host, format!("mkdir -p {AGENT_DIR}"), cancel).await?;
+        upload_file(
+            host.clone(),
+            binary.contents.clone(),
+            tmp.clone(),
+            0o700,
+            DEPLOY_TIMEOUT,
+        )
+        .await?;
+        exec(
+            host,
+            format!(
+                "sh -c 'if {} | grep -q ^{}; then mv {tmp} {path}; \
+                 else rm -f {tmp}; echo checksum mismatch >&2; exit 1; fi'",
+                sha256_command(&tmp),
+                binary.sha256
+            ),
+            cancel,
+        )
+        .await?;
+    }
+
+    let hello = exec(host, format!("./{path} hello"), cancel).await?;
+    check_hello(&hello)?;
+    Ok(path)
+}
+
+#[derive(Debug, Deserialize)]
+struct Hello {
+    v: u32,
+    agent: String,
+}
+
+fn check_hello(output: &str) -> Result<(), String> {
+    let hello: Hello =
+        serde_json::from_str(output.trim()).map_err(|e| format!("unexpected hello: {e}"))?;
+    if hello.v != AGENT_PROTOCOL_VERSION {
+        return Err(format!(
+            "agent {} speaks protocol v{}, expected v{AGENT_PROTOCOL_VERSION}",
+            hello.agent, hello.v
+        ));
+    }
+    Ok(())
+}
+
+pub fn agent_command(path: &str, jobs: &[&str]) -> String {
+    format!("./{path} run {}", jobs.join(" "))
+}
+
+#[derive(Debug, Deserialize)]
+struct AgentFrame {
+    v: u32,
+    job: String,
+    stdout: String,
+    #[serde(default)]
+    stderr: String,
+    exit_code: i32,
+    duration_ms: Option<u64>,
+}
+
+/// Any line that is not a frame of our protocol version fails the batch.
+pub fn parse_agent_frames(output: &str) -> Result<HashMap<String, Frame>, String> {
+    let mut frames = HashMap::new();
+    for line in output.lines().filter(|line| !line.trim().is_empty()) {
+        let frame: AgentFrame =
+            serde_json::from_str(line).map_err(|e| format!("bad agent frame: {e}"))?;
+        if frame.v != AGENT_PROTOCOL_VERSION {
+            return Err(format!(
+                "agent frame v{}, expected v{AGENT_PROTOCOL_VERSION}",
+                frame.v
+            ));
+        }
+        frames.insert(
+            frame.job,
+            Frame {
+                stdout: frame.stdout,
+                stderr: frame.stderr,
+                exit_code: Some(frame.exit_code),
+                duration: frame.duration_ms.map(Duration::from_millis),
+            },
+        );
+    }
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_agent_frames_checks_version() {
+        let output = concat!(
+            r#"{"v":1,"job":"cpu","stdout":"__STAT__\ncpu 12.5\n","stderr":"","exit_code":0,"duration_ms":250}"#,
+            "\n\n",
+            r#"{"v":1,"job":"gpu","stdout":"","stderr":"gpu: not found","exit_code":127,"duration_ms":0}"#,
+            "\n"
+        );
+        let frames = parse_agent_frames(output).unwrap();
+        assert_eq!(frames["cpu"].stdout, "__STAT__\ncpu 12.5\n");
+        assert_eq!(frames["cpu"].duration, Some(Duration::from_millis(250)));
+        assert_eq!(frames["gpu"].exit_code, Some(127));
+
+        assert!(parse_agent_frames(&output.replace("\"v\":1", "\"v\":2")).is_err());
+        assert!(parse_agent_frames("sh: ./agent: Exec format error").is_err());
+        assert!(check_hello(r#"{"v":1,"agent":"0.1.5"}"#).is_ok());
+        assert!(check_hello(r#"{"v":2,"agent":"9.0.0"}"#).is_err());
+    }
+
+    #[test]
+    fn test_is_static_elf() {
+        // A 64-bit little-endian header with one program header at 0x40.
+        let elf = |p_type: u8| {
+            let mut bytes = vec![0u8; 0x40 + 0x38];
+            bytes[..6].copy_from_slice(b"\x7fELF\x02\x01");
+            bytes[0x20] = 0x40;
+            bytes[0x36] = 0x38;
+            bytes[0x38] = 1;
+            bytes[0x40] = p_type;
+            bytes
+        };
+        assert!(is_static_elf(&elf(1)));
+        assert!(!is_static_elf(&elf(3)));
+        assert!(!is_static_elf(&elf(3)[..0x42]));
+        assert!(!is_static_elf(b"\xcf\xfa\xed\xfe"));
+    }
+
+    #[test]
+    fn test_check_host_os_arch_and_checksum() {
+        let binary = AgentBinary {
+            contents: Arc::new(b"agent".to_vec()),
+            sha256: format!("{:x}", Sha256::digest(b"agent")),
+            arch: Some("x86_64"),
+        };
+        let uploaded = format!(
+            "Linux\nx86_64\n{}  {}\n",
+            binary.sha256,
+            binary.remote_path()
+        );
+
+        assert_eq!(binary.check_host(&uploaded), Ok(true));
+        assert_eq!(binary.check_host("Linux\nx86_64\n"), Ok(false));
+        assert!(binary.check_host("Linux\naarch64\n").is_err());
+        assert!(binary.check_host("Darwin\nx86_64\n").is_err());
+
+        let cross = AgentBinary {
+            arch: None,
+            ..binary
+        };
+        assert_eq!(cross.check_host("Linux\naarch64\n"), Ok(false));
+    }
+}
@@ -0,0 +1,175 @@
+use super::collector::Collector;
+use crate::backend::db::capabilities::commands::{
+    HostCapabilitiesUpsert, upsert_host_capabilities,
+};
+use crate::backend::db::capabilities::queries::{HostCapabilitiesRow, fetch_host_capabilities_all};
+use anyhow::Result;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+pub const CAPABILITIES_COMMAND: &str = r#"sh -c '
+  echo os=$(uname -s)
+  echo shell=$SHELL
+  for tool in bash nvidia-smi rocm-smi docker systemctl lscpu; do
+    if command -v $tool >/dev/null 2>&1; then echo $tool=1; else echo $tool=0; fi
+  done
+  if sudo -n true >/dev/null 2>&1; then echo sudo=1; else echo sudo=0; fi
+'"#;
+
+/// What the remote host offers, probed once per connection so collectors that
+/// cannot work there are skipped instead of failing every tick.
+#[derive(Debug, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct HostCapabilities {
+    pub os: String,
+    pub shell: Option<String>,
+    pub bash: bool,
+    pub nvidia_smi: bool,
+    pub rocm_smi: bool,
+    pub docker: bool,
+    pub systemctl: bool,
+    pub lscpu: bool,
+    pub sudo: bool,
+}
+
+impl HostCapabilities {
+    pub fn is_darwin(&self) -> bool {
+        self.os == "Darwin"
+    }
+
+    /// Names of the optional tools that were found, in probe order.
+    pub fn tools(&self) -> Vec<&'static str> {
+        [
+            ("bash", self.bash),
+            ("nvidia-smi", self.nvidia_smi),
+            ("rocm-smi", self.rocm_smi),
+            ("docker", self.docker),
+            ("systemctl", self.systemctl),
+            ("lscpu", self.lscpu),
+        ]
+        .into_iter()
+        .filter(|(_, present)| *present)
+        .map(|(name, _)| name)
+        .collect()
+    }
+}
+
+pub fn parse_capabilities(output: &str) -> Result<Option<HostCapabilities>> {
+    let values: HashMap<&str, &str> = output
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(k, v)| (k.trim(), v.trim()))
+        .collect();
+
+    let Some(os) = values.get("os").filter(|os| !os.is_empty()) else {
+        return Ok(None);
+    };
+    let flag = |key: &str| values.get(key) == Some(&"1");
+
+    Ok(Some(HostCapabilities {
+        os: os.to_string(),
+        shell: values
+            .get("shell")
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string()),
+        bash: flag("bash"),
+        nvidia_smi: flag("nvidia-smi"),
+        rocm_smi: flag("rocm-smi"),
+        docker: flag("docker"),
+        systemctl: flag("systemctl"),
+        lscpu: flag("lscpu"),
+        sudo: flag("sudo"),
+    }))
+}
+
+impl From<HostCapabilitiesRow> for HostCapabilities {
+    fn from(row: HostCapabilitiesRow) -> Self {
+        Self {
+            os: row.os,
+            shell: row.shell,
+            bash: row.bash,
+            nvidia_smi: row.nvidia_smi,
+            rocm_smi: row.rocm_smi,
+            docker: row.docker,
+            systemctl: row.systemctl,
+            lscpu: row.lscpu,
+            sudo: row.sudo,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CapabilitiesCollector;
+
+#[async_trait::async_trait]
+impl Collector for CapabilitiesCollector {
+    type Sample = HostCapabilities;
+
+    fn name(&self) -> &str {
+        "capabilities"
+    }
+
+    fn command(&self) -> String {
+        CAPABILITIES_COMMAND.to_string()
+    }
+
+    /// Re-probed this often so newly installed tools are picked up.
+    fn interval(&self) -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+
+    fn parse(&self, output: &str) -> Result<Option<HostCapabilities>> {
+        parse_capabilities(output)
+    }
+
+    async fn store(
+        &self,
+        conn: &Arc<Mutex<Connection>>,
+        host_id: &str,
+        capabilities: &HostCapabilities,
+    ) -> Result<()> {
+        let upsert = HostCapabilitiesUpsert {
+            host_id: host_id.to_string(),
+            capabilities: capabilities.clone(),
+        };
+        upsert_host_capabilities(conn, &upsert).await
+    }
+}
+
+impl CapabilitiesCollector {
+    pub async fn query(&self, conn: &Arc<Mutex<Connection>>) -> Result<Vec<HostCapabilitiesRow>> {
+        fetch_host_capabilities_all(conn).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_capabilities() {
+        let caps = parse_capabilities(
+            "os=Linux\nshell=/bin/bash\nbash=1\nnvidia-smi=1\nrocm-smi=0\ndocker=1\nsystemctl=1\nlscpu=1\nsudo=0\n",
+        )
+        .unwrap()
+        .expect("capabilities");
+        assert_eq!(caps.os, "Linux");
+        assert_eq!(caps.shell.as_deref(), Some("/bin/bash"));
+        assert!(caps.nvidia_smi && !caps.rocm_smi && !caps.sudo);
+        assert_eq!(
+            caps.tools(),
+            ["bash", "nvidia-smi", "docker", "systemctl", "lscpu"]
+        );
+
+        let busybox = parse_capabilities("os=Linux\nshell=\nbash=0\n")
+            .unwrap()
+            .expect("capabilities");
+        assert_eq!(busybox.shell, None);
+        assert!(busybox.tools().is_empty());
+
+        assert!(parse_capabilities("").unwrap().is_none());
+    }
+}
@@ -0,0 +1,60 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How far a host's clock is from ours, estimated from one exec.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClockSample {
+    /// Seconds the host's clock is ahead of ours (negative when behind).
+    pub offset: f64,
+    /// Seconds spent on the network, which bounds the error of `offset`.
+    pub round_trip: f64,
+}
+
+fn epoch_secs(time: SystemTime) -> Option<f64> {
+    time.duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs_f64())
+}
+
+/// NTP-style estimate: the host read its clock at `remote_start` and
+/// `remote_end`, between our `sent` and `received`. Assuming the network
+/// took as long each way, the offset is the mean of both differences.
+pub fn estimate(
+    sent: SystemTime,
+    received: SystemTime,
+    remote_start: f64,
+    remote_end: f64,
+) -> Option<ClockSample> {
+    let (sent, received) = (epoch_secs(sent)?, epoch_secs(received)?);
+    Some(ClockSample {
+        offset: ((remote_start - sent) + (remote_end - received)) / 2.0,
+        round_trip: ((received - sent) - (remote_end - remote_start)).max(0.0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::jobs::framing::parse_clock;
+    use std::time::Duration;
+
+    #[test]
+    fn test_estimate_offset_from_framed_output() {
+        let output = "\
+@n clock 1700000010.600000000
+@n begin cpu 1
+@n clock 1700000010.N
+@n end cpu 0 2
+@n clock 1700000011.100000000
+";
+        let (start, end) = parse_clock(output, "n").unwrap();
+        assert!((start - 1_700_000_010.6).abs() < 1e-6);
+        assert!((end - 1_700_000_011.1).abs() < 1e-6);
+
+        // 200ms each way, host 10s ahead.
+        let sent = UNIX_EPOCH + Duration::from_millis(1_700_000_000_400);
+        let received = UNIX_EPOCH + Duration::from_millis(1_700_000_001_300);
+        let sample = estimate(sent, received, start, end).unwrap();
+        assert!((sample.offset - 10.0).abs() < 1e-3);
+        assert!((sample.round_trip - 0.4).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,207 @@
+use super::capabilities::HostCapabilities;
+use super::cpu::CpuCollector;
+use super::custom::CustomCollector;
+use super::disk::DiskCollector;
+use super::facts::FactsCollector;
+use super::gpu::GpuCollector;
+use super::mem::MemCollector;
+use super::packages::PackagesCollector;
+use super::ports::PortsCollector;
+use super::session::SessionCollector;
+use crate::config::AppConfig;
+use anyhow::Result;
+use log::warn;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// A remote metric source: the shell command it runs, how its output becomes
+/// a typed sample, and how samples are written to the DB.
+#[async_trait::async_trait]
+pub trait Collector: Debug + Send + Sync + 'static {
+    type Sample: Serialize + Debug + Send + Sync + 'static;
+
+    fn name(&self) -> &str;
+
+    /// Section marker used in the combined command; must be marker-safe.
+    fn tag(&self) -> &str {
+        self.name()
+    }
+
+    fn command(&self) -> String;
+
+    /// How often the collector runs. Collectors of one host that fall due
+    /// together share a single SSH exec.
+    fn interval(&self) -> Duration {
+        Duration::from_secs(30)
+    }
+
+    /// Upper bound for connecting and running the command; a batch waits for
+    /// its slowest member.
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(5)
+    }
+
+    /// Whether the command can work on a host with these capabilities.
+    fn supports(&self, _capabilities: &HostCapabilities) -> bool {
+        true
+    }
+
+    fn parse(&self, output: &str) -> Result<Option<Self::Sample>>;
+
+    async fn store(
+        &self,
+        conn: &Arc<Mutex<Connection>>,
+        host_id: &str,
+        sample: &Self::Sample,
+    ) -> Result<()>;
+}
+
+/// Object-safe view of a [`Collector`] so collectors with different sample
+/// types can share a `JobGroup`. Implemented for every `Collector`; the
+/// sample keeps its type from `parse` to `store` and only leaves as JSON.
+#[async_trait::async_trait]
+pub trait DynCollector: Debug + Send + Sync {
+    fn name(&self) -> &str;
+    fn tag(&self) -> &str;
+    fn command(&self) -> String;
+    fn interval(&self) -> Duration;
+    fn timeout(&self) -> Duration;
+    fn supports(&self, capabilities: &HostCapabilities) -> bool;
+    /// Parses `output` and stores the sample. A failed store is logged, not
+    /// returned, since the sample itself is still good.
+    async fn parse_and_store(
+        &self,
+        output: &str,
+        conn: &Arc<Mutex<Connection>>,
+        host_id: &str,
+    ) -> Result<Option<serde_json::Value>>;
+}
+
+#[async_trait::async_trait]
+impl<C: Collector> DynCollector for C {
+    fn name(&self) -> &str {
+        Collector::name(self)
+    }
+
+    fn tag(&self) -> &str {
+        Collector::tag(self)
+    }
+
+    fn command(&self) -> String {
+        Collector::command(self)
+    }
+
+    fn interval(&self) -> Duration {
+        Collector::interval(self)
+    }
+
+    fn timeout(&self) -> Duration {
+        Collector::timeout(self)
+    }
+
+    fn supports(&self, capabilities: &HostCapabilities) -> bool {
+        Collector::supports(self, capabilities)
+    }
+
+    async fn parse_and_store(
+        &self,
+        output: &str,
+        conn: &Arc<Mutex<Connection>>,
+        host_id: &str,
+    ) -> Result<Option<serde_json::Value>> {
+        let Some(sample) = Collector::parse(self, output)? else {
+            return Ok(None);
+        };
+        if let Err(e) = Collector::store(self, conn, host_id, &sample).await {
+            warn!(
+                "❌ Failed to save {} result to DB: {e}",
+                Collector::name(self)
+            );
+        }
+        Ok(Some(serde_json::to_value(&sample)?))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CollectorRegistry {
+    collectors: HashMap<String, Arc<dyn DynCollector>>,
+}
+
+impl CollectorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Built-in collectors plus the valid `[[collectors]]` from the config.
+    pub fn with_builtins(config: &AppConfig) -> Self {
+        let mut registry = Self::new();
+        registry.register(CpuCollector);
+        registry.register(MemCollector);
+        registry.register(DiskCollector::new(config.disk.clone()));
+        registry.register(GpuCollector);
+        registry.register(SessionCollector);
+        registry.register(PortsCollector);
+        registry.register(FactsCollector);
+        registry.register(PackagesCollector);
+        for collector_config in &config.collectors {
+            match CustomCollector::new(collector_config.clone()) {
+                Ok(collector) => registry.register(collector),
+                Err(e) => warn!("❌ Skipping collector '{}': {e}", collector_config.name),
+            }
+        }
+        registry
+    }
+
+    pub fn register<C: Collector>(&mut self, collector: C) {
+        let name = Collector::name(&collector).to_string();
+        self.collectors.insert(name, Arc::new(collector));
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn DynCollector>> {
+        self.collectors.get(name).cloned()
+    }
+
+    /// Looks up several collectors, skipping names that are not registered.
+    pub fn select(&self, names: &[&str]) -> Vec<Arc<dyn DynCollector>> {
+        names.iter().filter_map(|name| self.get(name)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_registry_parses_and_stores() {
+        use crate::backend::db::init_schema;
+        use crate::backend::jobs::disk::DiskCollector;
+
+        let registry = CollectorRegistry::with_builtins(&AppConfig::default());
+        let jobs = registry.select(&["mem", "disk", "unknown"]);
+        assert_eq!(jobs.len(), 2);
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn);
+        let conn = Arc::new(Mutex::new(conn));
+        let disk = registry.get("disk").unwrap();
+        let sample = disk
+            .parse_and_store(
+                "/dev/sda1 100 40 60 40% /\n__MOUNTS__\n/dev/sda1 on / type ext4 (rw)\n",
+                &conn,
+                "host",
+            )
+            .await
+            .unwrap()
+            .expect("disk sample");
+        assert_eq!(sample[0]["mount_point"], "/");
+
+        let rows = DiskCollector::default().query(&conn).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].host_id, "host");
+    }
+}
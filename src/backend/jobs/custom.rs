@@ -0,0 +1,266 @@
+use super::collector::Collector;
+use crate::backend::db::custom::commands::{CustomResultInsert, store_custom_result};
+use crate::config::{CollectorConfig, CollectorFormat};
+use anyhow::{Result, anyhow, bail};
+use regex::Regex;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// A `[[collectors]]` entry ready to run: the regex is compiled once and the
+/// section tag is derived from the name (unique per config, see `AppConfig::validate`).
+#[derive(Debug, Clone)]
+pub struct CustomCollector {
+    pub job_name: String,
+    pub tag: String,
+    pub config: CollectorConfig,
+    regex: Option<Regex>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct CustomSample {
+    pub units: Option<String>,
+    pub values: BTreeMap<String, f64>,
+}
+
+impl CustomCollector {
+    pub fn new(config: CollectorConfig) -> Result<Self> {
+        let regex = match (&config.format, &config.pattern) {
+            (CollectorFormat::Regex, Some(pattern)) => Some(Regex::new(pattern)?),
+            (CollectorFormat::Regex, None) => {
+                bail!("collector '{}' has no pattern", config.name)
+            }
+            _ => None,
+        };
+        Ok(Self {
+            job_name: format!("custom:{}", config.name),
+            tag: config.section_tag(),
+            config,
+            regex,
+        })
+    }
+}
+
+fn parse_number(value: &str) -> Option<f64> {
+    value
+        .trim()
+        .trim_end_matches('%')
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+}
+
+fn parse_first_number(output: &str) -> BTreeMap<String, f64> {
+    output
+        .split_whitespace()
+        .find_map(parse_number)
+        .map(|v| BTreeMap::from([("value".to_string(), v)]))
+        .unwrap_or_default()
+}
+
+fn parse_key_values(output: &str) -> BTreeMap<String, f64> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once('=').or_else(|| line.split_once(':')))
+        .filter_map(|(k, v)| Some((k.trim().to_string(), parse_number(v)?)))
+        .filter(|(k, _)| !k.is_empty())
+        .collect()
+}
+
+fn flatten_json(prefix: &str, value: &serde_json::Value, out: &mut BTreeMap<String, f64>) {
+    let key = |child: &str| {
+        if prefix.is_empty() {
+            child.to_string()
+        } else {
+            format!("{}.{}", prefix, child)
+        }
+    };
+    match value {
+        serde_json::Value::Number(n) => {
+            if let Some(v) = n.as_f64() {
+                let name = if prefix.is_empty() { "value" } else { prefix };
+                out.insert(name.to_string(), v);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for (k, v) in map {
+                flatten_json(&key(k), v, out);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (i, v) in items.iter().enumerate() {
+                flatten_json(&key(&i.to_string()), v, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn parse_regex(output: &str, regex: &Regex) -> BTreeMap<String, f64> {
+    let Some(captures) = regex.captures(output) else {
+        return BTreeMap::new();
+    };
+    let names: Vec<Option<&str>> = regex.capture_names().collect();
+    if names.len() == 1 {
+        return captures
+            .get(0)
+            .and_then(|m| parse_number(m.as_str()))
+            .map(|v| BTreeMap::from([("value".to_string(), v)]))
+            .unwrap_or_default();
+    }
+    names
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter_map(|(i, name)| {
+            let value = parse_number(captures.get(i)?.as_str())?;
+            let key = name.map(str::to_string).unwrap_or_else(|| i.to_string());
+            Some((key, value))
+        })
+        .collect()
+}
+
+pub fn parse_custom(output: &str, collector: &CustomCollector) -> Result<Option<CustomSample>> {
+    let values = match collector.config.format {
+        CollectorFormat::Number => parse_first_number(output),
+        CollectorFormat::KeyValue => parse_key_values(output),
+        CollectorFormat::Json => {
+            let mut values = BTreeMap::new();
+            let json: serde_json::Value = serde_json::from_str(output)?;
+            flatten_json("", &json, &mut values);
+            values
+        }
+        CollectorFormat::Regex => {
+            let regex = collector
+                .regex
+                .as_ref()
+                .ok_or_else(|| anyhow!("collector '{}' has no pattern", collector.config.name))?;
+            parse_regex(output, regex)
+        }
+    };
+
+    if values.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(CustomSample {
+        units: collector.config.units.clone(),
+        values,
+    }))
+}
+
+#[async_trait::async_trait]
+impl Collector for CustomCollector {
+    type Sample = CustomSample;
+
+    fn name(&self) -> &str {
+        &self.job_name
+    }
+
+    fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Runs the user's command under `sh` so it behaves the same whatever the
+    /// remote login shell is.
+    fn command(&self) -> String {
+        format!("sh -c '{}'", self.config.command.replace('\'', r"'\''"))
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(self.config.interval_secs)
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(self.config.timeout_secs)
+    }
+
+    fn parse(&self, output: &str) -> Result<Option<CustomSample>> {
+        parse_custom(output, self)
+    }
+
+    async fn store(
+        &self,
+        conn: &Arc<Mutex<Connection>>,
+        host_id: &str,
+        sample: &CustomSample,
+    ) -> Result<()> {
+        let insert = CustomResultInsert {
+            host_id: host_id.to_string(),
+            job_name: self.job_name.clone(),
+            sample: sample.clone(),
+        };
+        store_custom_result(conn, &insert).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collector(format: CollectorFormat, pattern: Option<&str>) -> CustomCollector {
+        CustomCollector::new(CollectorConfig {
+            name: "queue depth".into(),
+            command: "echo 'x'".into(),
+            format,
+            pattern: pattern.map(str::to_string),
+            units: Some("jobs".into()),
+            interval_secs: 60,
+            timeout_secs: 10,
+            hosts: vec![],
+        })
+        .unwrap()
+    }
+
+    fn parse(input: &str, collector: &CustomCollector) -> Option<BTreeMap<String, f64>> {
+        parse_custom(input, collector).unwrap().map(|s| s.values)
+    }
+
+    #[test]
+    fn test_custom_collector_naming() {
+        let c = collector(CollectorFormat::Number, None);
+        assert_eq!(c.job_name, "custom:queue depth");
+        assert_eq!(c.tag, "custom_queue_depth");
+        assert_eq!(c.command(), r"sh -c 'echo '\''x'\'''");
+    }
+
+    #[test]
+    fn test_parse_custom_formats() {
+        let number = parse("  42\n", &collector(CollectorFormat::Number, None)).unwrap();
+        assert_eq!(number["value"], 42.0);
+
+        let kv = parse(
+            "active=3\nwaiting: 7\nstate=ok\n",
+            &collector(CollectorFormat::KeyValue, None),
+        )
+        .unwrap();
+        assert_eq!(kv.len(), 2);
+        assert_eq!(kv["waiting"], 7.0);
+
+        let json = parse(
+            r#"{"queue": {"ready": 5, "unacked": 1}, "workers": [2, 4], "name": "jobs"}"#,
+            &collector(CollectorFormat::Json, None),
+        )
+        .unwrap();
+        assert_eq!(json["queue.ready"], 5.0);
+        assert_eq!(json["workers.1"], 4.0);
+        assert!(!json.contains_key("name"));
+
+        let regex = collector(
+            CollectorFormat::Regex,
+            Some(r"Users of (\w+):.*?(?P<used>\d+) licenses? in use"),
+        );
+        let captured = parse(
+            "Users of matlab:  (Total of 50 licenses issued;  Total of 12 licenses in use)",
+            &regex,
+        )
+        .unwrap();
+        assert_eq!(captured.len(), 1);
+        assert_eq!(captured["used"], 12.0);
+
+        assert!(parse("no numbers here", &collector(CollectorFormat::Number, None)).is_none());
+    }
+}
@@ -1,46 +1,231 @@
-use super::job::JobResult;
+use super::collector::Collector;
+use crate::backend::db::disk::commands::{DiskResultInsert, store_disk_sample};
+use crate::backend::db::disk::queries::{DiskResultRow, fetch_latest_disk_all};
+use crate::config::DiskConfig;
 use anyhow::Result;
+use rusqlite::Connection;
 use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
 
-pub const DISK_COMMAND: &str = "df -Pm | tail -n +2";
+pub const DISK_COMMAND: &str =
+    "df -Pm | tail -n +2; echo __MOUNTS__; mount; echo __INODES__; df -Pi 2>/dev/null | tail -n +2";
 
 #[derive(Debug, Serialize, Clone)]
 pub struct DiskInfo {
+    pub device: String,
+    pub fs_type: Option<String>,
     pub mount_point: String,
     pub total_mb: u64,
     pub used_mb: u64,
     pub available_mb: u64,
     pub used_percent: f32,
+    pub inodes_total: Option<u64>,
+    pub inodes_used: Option<u64>,
+    pub inodes_used_percent: Option<f32>,
 }
 
-pub fn parse_disk(output: &str) -> Result<Option<JobResult>> {
-    let mut results = vec![];
+/// Finds the last `NN%` column that follows at least `numbers_before` numeric
+/// columns. Everything after it is the mount point, which may contain spaces,
+/// and everything before the numeric columns is the device (`map auto_home`).
+fn split_df_line(line: &str, numbers_before: usize) -> Option<(Vec<&str>, usize)> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    let index = (numbers_before + 1..parts.len().saturating_sub(1))
+        .rev()
+        .find(|&i| {
+            parts[i].ends_with('%')
+                && parts[i - numbers_before..i]
+                    .iter()
+                    .all(|p| p.parse::<u64>().is_ok())
+        })?;
+    Some((parts, index))
+}
+
+fn parse_df(output: &str) -> Vec<DiskInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (parts, i) = split_df_line(line, 3)?;
+            Some(DiskInfo {
+                device: parts[..i - 3].join(" "),
+                fs_type: None,
+                mount_point: parts[i + 1..].join(" "),
+                total_mb: parts[i - 3].parse().unwrap_or(0),
+                used_mb: parts[i - 2].parse().unwrap_or(0),
+                available_mb: parts[i - 1].parse().unwrap_or(0),
+                used_percent: parts[i].trim_end_matches('%').parse().unwrap_or(0.0),
+                inodes_total: None,
+                inodes_used: None,
+                inodes_used_percent: None,
+            })
+        })
+        .collect()
+}
+
+/// Maps mount points to filesystem types from `mount`, which prints
+/// `dev on /mnt type ext4 (rw)` on Linux and `dev on /mnt (apfs, local)` on macOS.
+fn parse_mounts(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (_, rest) = line.split_once(" on ")?;
+            if let Some((mount_point, rest)) = rest.rsplit_once(" type ") {
+                let fs_type = rest.split_whitespace().next()?;
+                Some((mount_point.to_string(), fs_type.to_string()))
+            } else {
+                let (mount_point, options) = rest.rsplit_once(" (")?;
+                let fs_type = options.split([',', ')']).next()?.trim();
+                Some((mount_point.to_string(), fs_type.to_string()))
+            }
+        })
+        .collect()
+}
+
+/// Reads `(used, free, percent)` inode counts per mount point from `df -Pi`.
+/// The used/free/percent triple ends the numeric columns on both Linux and
+/// macOS, where block counts come first.
+fn parse_inodes(output: &str) -> HashMap<String, (u64, u64, f32)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (parts, i) = split_df_line(line, 2)?;
+            let used = parts[i - 2].parse().ok()?;
+            let free = parts[i - 1].parse().ok()?;
+            let percent = parts[i].trim_end_matches('%').parse().unwrap_or(0.0);
+            Some((parts[i + 1..].join(" "), (used, free, percent)))
+        })
+        .collect()
+}
 
-    for line in output.lines() {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() < 6 {
+fn is_excluded(info: &DiskInfo, config: &DiskConfig) -> bool {
+    let fs_excluded = info
+        .fs_type
+        .as_ref()
+        .is_some_and(|fs| config.exclude_fs_types.iter().any(|t| t == fs));
+    let mount_excluded = config.exclude_mount_points.iter().any(|prefix| {
+        info.mount_point == *prefix
+            || info
+                .mount_point
+                .strip_prefix(prefix.trim_end_matches('/'))
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    });
+    fs_excluded || mount_excluded
+}
+
+/// Bind mounts and subvolumes report the same device and sizes more than once.
+/// Keep the shortest mount point for each block device so totals count it once.
+fn dedupe_devices(disks: Vec<DiskInfo>) -> Vec<DiskInfo> {
+    let mut results: Vec<DiskInfo> = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    for disk in disks {
+        if !disk.device.starts_with('/') {
+            results.push(disk);
             continue;
         }
+        match seen.get(&disk.device) {
+            Some(&i) => {
+                if disk.mount_point.len() < results[i].mount_point.len() {
+                    results[i] = disk;
+                }
+            }
+            None => {
+                seen.insert(disk.device.clone(), results.len());
+                results.push(disk);
+            }
+        }
+    }
+
+    results
+}
+
+pub fn parse_disk(output: &str, config: &DiskConfig) -> Result<Option<Vec<DiskInfo>>> {
+    let (df_output, rest) = output.split_once("__MOUNTS__").unwrap_or((output, ""));
+    let (mount_output, inode_output) = rest.split_once("__INODES__").unwrap_or((rest, ""));
 
-        let total_mb = parts[1].parse::<u64>().unwrap_or(0);
-        let used_mb = parts[2].parse::<u64>().unwrap_or(0);
-        let available_mb = parts[3].parse::<u64>().unwrap_or(0);
-        let used_percent = parts[4].trim_end_matches('%').parse::<f32>().unwrap_or(0.0);
-        let mount_point = parts[5].to_string();
-
-        results.push(DiskInfo {
-            mount_point,
-            total_mb,
-            used_mb,
-            available_mb,
-            used_percent,
-        });
+    let fs_types = parse_mounts(mount_output);
+    let inodes = parse_inodes(inode_output);
+
+    let disks: Vec<DiskInfo> = parse_df(df_output)
+        .into_iter()
+        .map(|mut disk| {
+            disk.fs_type = fs_types.get(&disk.mount_point).cloned();
+            if let Some(&(used, free, percent)) = inodes.get(&disk.mount_point) {
+                disk.inodes_total = Some(used + free);
+                disk.inodes_used = Some(used);
+                disk.inodes_used_percent = Some(percent);
+            }
+            disk
+        })
+        .filter(|disk| !is_excluded(disk, config))
+        .collect();
+
+    Ok(Some(dedupe_devices(disks)))
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DiskCollector {
+    config: DiskConfig,
+}
+
+impl DiskCollector {
+    pub fn new(config: DiskConfig) -> Self {
+        Self { config }
     }
+}
+
+#[async_trait::async_trait]
+impl Collector for DiskCollector {
+    type Sample = Vec<DiskInfo>;
 
-    Ok(Some(JobResult {
-        job_name: "disk".into(),
-        value: Box::new(results),
-    }))
+    fn name(&self) -> &str {
+        "disk"
+    }
+
+    fn command(&self) -> String {
+        DISK_COMMAND.to_string()
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(300)
+    }
+
+    fn parse(&self, output: &str) -> Result<Option<Vec<DiskInfo>>> {
+        parse_disk(output, &self.config)
+    }
+
+    async fn store(
+        &self,
+        conn: &Arc<Mutex<Connection>>,
+        host_id: &str,
+        disk_infos: &Vec<DiskInfo>,
+    ) -> Result<()> {
+        let volumes: Vec<DiskResultInsert> = disk_infos
+            .iter()
+            .map(|info| DiskResultInsert {
+                host_id: host_id.to_string(),
+                device: info.device.clone(),
+                fs_type: info.fs_type.clone(),
+                mount_point: info.mount_point.clone(),
+                total_mb: info.total_mb,
+                used_mb: info.used_mb,
+                available_mb: info.available_mb,
+                used_percent: info.used_percent,
+                inodes_total: info.inodes_total,
+                inodes_used: info.inodes_used,
+                inodes_used_percent: info.inodes_used_percent,
+            })
+            .collect();
+        store_disk_sample(conn, &volumes).await
+    }
+}
+
+impl DiskCollector {
+    pub async fn query(&self, conn: &Arc<Mutex<Connection>>) -> Result<Vec<DiskResultRow>> {
+        fetch_latest_disk_all(conn).await
+    }
 }
 
 #[cfg(test)]
@@ -67,15 +252,10 @@ map auto_home          0      0         0   100%    /System/Volumes/Data/home
 
     #[test]
     fn test_parse_disk() {
-        let result = parse_disk(SAMPLE_OUTPUT);
+        let result = parse_disk(SAMPLE_OUTPUT, &DiskConfig::default());
         assert!(result.is_ok());
 
-        let job_result = result.unwrap().expect("Job result should be present");
-
-        assert_eq!(job_result.job_name, "disk");
-
-        let disk_info_list: Vec<DiskInfo> =
-            (*job_result.value.downcast_ref::<Vec<DiskInfo>>().unwrap()).clone();
+        let disk_info_list = result.unwrap().expect("Job result should be present");
 
         assert_eq!(disk_info_list[0].mount_point, "/");
         assert_eq!(disk_info_list[0].total_mb, 471482);
@@ -92,4 +272,93 @@ map auto_home          0      0         0   100%    /System/Volumes/Data/home
         assert_eq!(disk_info_list[disk_info_list.len() - 1].available_mb, 48654);
         assert_eq!(disk_info_list[disk_info_list.len() - 1].used_percent, 24.0);
     }
+
+    #[test]
+    fn test_parse_disk_linux_filters_and_dedupes() {
+        let output = r#"/dev/nvme0n1p2      937420  412330    477390      47% /
+tmpfs                 3200       3      3198       1% /run
+/dev/nvme0n1p1         511       7       505       2% /boot/efi
+/dev/loop3              64      64         0     100% /snap/core20/2015
+overlay             937420  412330    477390      47% /var/lib/docker/overlay2/abc/merged
+/dev/nvme0n1p2      937420  412330    477390      47% /srv/data
+/dev/sdb1          1869032  934516    839574      53% /mnt/backup disk
+__MOUNTS__
+/dev/nvme0n1p2 on / type ext4 (rw,relatime)
+tmpfs on /run type tmpfs (rw,nosuid,nodev,noexec,relatime,size=3276800k,mode=755)
+/dev/nvme0n1p1 on /boot/efi type vfat (rw,relatime)
+/dev/loop3 on /snap/core20/2015 type squashfs (ro,nodev,relatime)
+overlay on /var/lib/docker/overlay2/abc/merged type overlay (rw,relatime)
+/dev/nvme0n1p2 on /srv/data type ext4 (rw,relatime)
+/dev/sdb1 on /mnt/backup disk type xfs (rw,relatime)
+__INODES__
+/dev/nvme0n1p2     61022208 1204467 59817741       2% /
+tmpfs               1021420    1301  1020119       1% /run
+/dev/nvme0n1p1            0       0        0        - /boot/efi
+/dev/loop3            12847   12847        0     100% /snap/core20/2015
+/dev/sdb1          95367104   12045 95355059       1% /mnt/backup disk
+"#;
+
+        let disks = parse_disk(output, &DiskConfig::default())
+            .unwrap()
+            .expect("disk result");
+
+        let mounts: Vec<&str> = disks.iter().map(|d| d.mount_point.as_str()).collect();
+        assert_eq!(mounts, vec!["/", "/boot/efi", "/mnt/backup disk"]);
+
+        assert_eq!(disks[0].device, "/dev/nvme0n1p2");
+        assert_eq!(disks[0].fs_type.as_deref(), Some("ext4"));
+        assert_eq!(disks[0].inodes_total, Some(61022208));
+        assert_eq!(disks[0].inodes_used, Some(1204467));
+        assert_eq!(disks[0].inodes_used_percent, Some(2.0));
+
+        assert_eq!(disks[1].fs_type.as_deref(), Some("vfat"));
+        assert_eq!(disks[1].inodes_total, None);
+
+        assert_eq!(disks[2].fs_type.as_deref(), Some("xfs"));
+        assert_eq!(disks[2].total_mb, 1869032);
+    }
+
+    #[test]
+    fn test_parse_disk_macos_mounts_and_inodes() {
+        let output = r#"/dev/disk3s1s1    471482  15125     48654    24%    /
+devfs                  0      0         0   100%    /dev
+map auto_home          0      0         0   100%    /System/Volumes/Data/home
+/dev/disk3s5      471482 381304     48654    89%    /System/Volumes/Data
+__MOUNTS__
+/dev/disk3s1s1 on / (apfs, sealed, local, read-only, journaled)
+devfs on /dev (devfs, local, nobrowse)
+/dev/disk3s5 on /System/Volumes/Data (apfs, local, journaled, nobrowse, protect)
+map auto_home on /System/Volumes/Data/home (autofs, automounted, nobrowse)
+__INODES__
+/dev/disk3s1s1   965595304  30976848  99645528    24%  356093 996456480    0%   /
+/dev/disk3s5     965595304 780929672  99645528    89% 2263518 996456480    0%   /System/Volumes/Data
+"#;
+
+        let config = DiskConfig {
+            exclude_mount_points: vec!["/System/Volumes/Data/".into()],
+            ..Default::default()
+        };
+        let disks = parse_disk(output, &config).unwrap().expect("disk result");
+
+        let mounts: Vec<&str> = disks.iter().map(|d| d.mount_point.as_str()).collect();
+        assert_eq!(mounts, vec!["/"]);
+        assert_eq!(disks[0].fs_type.as_deref(), Some("apfs"));
+        assert_eq!(disks[0].inodes_used, Some(356093));
+        assert_eq!(disks[0].inodes_total, Some(356093 + 996456480));
+    }
+
+    #[test]
+    fn test_parse_disk_busybox() {
+        let output = include_str!("fixtures/busybox/disk.txt");
+        let disks = parse_disk(output, &DiskConfig::default())
+            .unwrap()
+            .expect("disk result");
+
+        let mounts: Vec<&str> = disks.iter().map(|d| d.mount_point.as_str()).collect();
+        assert_eq!(mounts, vec!["/etc/hosts", "/var/lib/data"]);
+        assert_eq!(disks[0].fs_type.as_deref(), Some("ext4"));
+        assert_eq!(disks[1].fs_type.as_deref(), Some("xfs"));
+        assert_eq!(disks[1].total_mb, 245817);
+        assert_eq!(disks[1].inodes_used, Some(12032));
+    }
 }
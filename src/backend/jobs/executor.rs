@@ -1,36 +1,117 @@
-use crate::backend::jobs::job::{JobGroup, JobKind, JobResult};
-use crate::backend::ssh::{connect_ssh_session, run_ssh_command};
-use anyhow::Result;
+use crate::backend::jobs::agent::{
+    AGENT_JOBS, AGENT_TAG, AgentBinary, HostAgent, agent_command, parse_agent_frames,
+};
+use crate::backend::jobs::capabilities::{CapabilitiesCollector, HostCapabilities};
+use crate::backend::jobs::clock::{ClockSample, estimate};
+use crate::backend::jobs::collector::{Collector, DynCollector};
+use crate::backend::jobs::framing::{
+    Frame, build_framed_script, new_nonce, parse_clock, parse_frames,
+};
+use crate::backend::jobs::job::{JobError, JobGroup, JobOutcome};
+use crate::backend::jobs::scheduler::Scheduler;
+use crate::backend::jobs::stream::run_stream;
+use crate::backend::jobs::telemetry::{CycleStatus, CycleTelemetry};
+use crate::backend::shutdown::join_within;
+use crate::backend::ssh::{CommandOutput, SessionTimings, run_remote};
 use log::{error, info, warn};
 use rusqlite::Connection;
-use std::{collections::HashMap, sync::Arc};
-use tokio::time::{self, Duration, timeout};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::time::{self, Instant};
 use tokio::{
-    sync::{Mutex, RwLock},
-    task,
+    sync::{Mutex, RwLock, mpsc, watch},
+    task::{self, JoinHandle},
 };
 
+/// Requests a running group task accepts between runs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroupCommand {
+    Pause,
+    Resume,
+    /// Run every collector of the group now, even while paused.
+    RunNow,
+    /// Multiply every collector interval by this factor (1.0 restores them).
+    ScaleIntervals(f64),
+    /// The host was selected in the UI (or no longer is) and should be
+    /// polled at the focus interval.
+    Focus(bool),
+}
+
+#[derive(Debug)]
+struct GroupHandle {
+    commands: mpsc::UnboundedSender<GroupCommand>,
+    /// Makes the group's in-flight SSH reads give up.
+    cancel: Arc<AtomicBool>,
+    task: JoinHandle<()>,
+}
+
+/// A host streaming high-resolution samples next to its regular polling.
+#[derive(Debug)]
+struct StreamHandle {
+    stop: Arc<AtomicBool>,
+    task: JoinHandle<()>,
+}
+
 #[derive(Clone, Debug)]
 pub struct JobGroupExecutor {
     groups: Arc<RwLock<HashMap<String, JobGroup>>>,
+    handles: Arc<RwLock<HashMap<String, GroupHandle>>>,
+    streams: Arc<RwLock<HashMap<String, StreamHandle>>>,
     db: Arc<Mutex<Connection>>,
+    scheduler: Arc<Scheduler>,
+    agent: Option<Arc<AgentBinary>>,
+    shutdown: Arc<watch::Sender<bool>>,
 }
 
 impl JobGroupExecutor {
-    pub fn new(db: Arc<Mutex<Connection>>) -> Self {
+    pub fn new(db: Arc<Mutex<Connection>>, scheduler: Arc<Scheduler>) -> Self {
         Self {
             groups: Arc::new(RwLock::new(HashMap::new())),
+            handles: Arc::new(RwLock::new(HashMap::new())),
+            streams: Arc::new(RwLock::new(HashMap::new())),
             db,
+            scheduler,
+            agent: None,
+            shutdown: Arc::new(watch::channel(false).0),
         }
     }
 
+    /// Collects through `agent` on hosts that can run it.
+    pub fn with_agent(mut self, agent: Option<AgentBinary>) -> Self {
+        self.agent = agent.map(Arc::new);
+        self
+    }
+
+    /// Starts every registered group that is not running yet.
     pub async fn run_all(&self) {
         let groups = self.groups.read().await;
-        for group in groups.values().cloned() {
-            let db = self.db.clone();
-            task::spawn(async move {
-                run_group_task(group, db).await;
+        let mut handles = self.handles.write().await;
+        for group in groups.values() {
+            if handles.contains_key(&group.name) {
+                continue;
+            }
+            let (commands, receiver) = mpsc::unbounded_channel();
+            let cancel = Arc::new(AtomicBool::new(false));
+            let context = GroupContext {
+                conn: self.db.clone(),
+                scheduler: self.scheduler.clone(),
+                agent: self.agent.clone(),
+                shutdown: self.shutdown.subscribe(),
+                cancel: cancel.clone(),
+            };
+            let group = group.clone();
+            let name = group.name.clone();
+            let task = task::spawn(async move {
+                run_group_task(group, context, receiver).await;
             });
+            handles.insert(
+                name,
+                GroupHandle {
+                    commands,
+                    cancel,
+                    task,
+                },
+            );
         }
     }
 
@@ -39,116 +120,688 @@ impl JobGroupExecutor {
         groups.insert(group.name.clone(), group);
     }
 
-    #[allow(dead_code)]
-    pub async fn run(&self, name: &str) -> Result<Vec<JobResult>> {
-        let groups = self.groups.read().await;
-        if let Some(group) = groups.get(name) {
-            run_group_once(group.clone()).await
-        } else {
-            Ok(vec![])
+    /// Sends `command` to a running group. Returns false if it is not running.
+    pub async fn send(&self, name: &str, command: GroupCommand) -> bool {
+        let handles = self.handles.read().await;
+        handles
+            .get(name)
+            .is_some_and(|handle| handle.commands.send(command).is_ok())
+    }
+
+    /// Starts streaming samples from a group's host, or stops a stream that
+    /// is running. Returns whether the host is streaming now. Streams keep
+    /// their session open, so they do not take scheduler slots.
+    pub async fn toggle_stream(&self, name: &str) -> bool {
+        let mut streams = self.streams.write().await;
+        if let Some(stream) = streams.remove(name)
+            && !stream.task.is_finished()
+        {
+            stream.stop.store(true, Ordering::Relaxed);
+            return false;
+        }
+        let Some(group) = self.groups.read().await.get(name).cloned() else {
+            return false;
+        };
+        let stop = Arc::new(AtomicBool::new(false));
+        let task = task::spawn(run_stream(group.host, self.db.clone(), stop.clone()));
+        streams.insert(name.to_string(), StreamHandle { stop, task });
+        true
+    }
+
+    pub async fn is_streaming(&self, name: &str) -> bool {
+        self.streams
+            .read()
+            .await
+            .get(name)
+            .is_some_and(|stream| !stream.task.is_finished())
+    }
+
+    /// Stops a group's task and forgets the group. A run in flight is
+    /// cancelled at its next await, and its SSH session at its next read.
+    pub async fn unregister(&self, name: &str) -> bool {
+        self.groups.write().await.remove(name);
+        if let Some(stream) = self.streams.write().await.remove(name) {
+            stream.stop.store(true, Ordering::Relaxed);
+        }
+        let Some(handle) = self.handles.write().await.remove(name) else {
+            return false;
+        };
+        handle.cancel.store(true, Ordering::Relaxed);
+        handle.task.abort();
+        self.scheduler.mark_stopped(name);
+        true
+    }
+
+    /// Stops every group and stream: sleeping and queued groups exit at once,
+    /// SSH reads in flight are cancelled, and results already read are still
+    /// stored. Tasks that have not finished after `grace` are aborted.
+    pub async fn shutdown(&self, grace: Duration) {
+        self.shutdown.send_replace(true);
+        let mut tasks: Vec<_> = self
+            .handles
+            .write()
+            .await
+            .drain()
+            .map(|(_, handle)| {
+                handle.cancel.store(true, Ordering::Relaxed);
+                handle.task
+            })
+            .collect();
+        for (_, stream) in self.streams.write().await.drain() {
+            stream.stop.store(true, Ordering::Relaxed);
+            tasks.push(stream.task);
+        }
+        let aborted = join_within(tasks, grace).await;
+        if aborted > 0 {
+            warn!("⚠️ Aborted {aborted} job groups that did not stop in time");
         }
     }
 }
 
-async fn run_group_task(group: JobGroup, conn: Arc<Mutex<Connection>>) {
+/// What a group task shares with the executor.
+struct GroupContext {
+    conn: Arc<Mutex<Connection>>,
+    scheduler: Arc<Scheduler>,
+    agent: Option<Arc<AgentBinary>>,
+    shutdown: watch::Receiver<bool>,
+    cancel: Arc<AtomicBool>,
+}
+
+async fn run_group_task(
+    group: JobGroup,
+    context: GroupContext,
+    mut commands: mpsc::UnboundedReceiver<GroupCommand>,
+) {
+    let GroupContext {
+        conn,
+        scheduler,
+        agent,
+        mut shutdown,
+        cancel,
+    } = context;
+    if group.jobs.is_empty() {
+        warn!("⚠️ No jobs in group '{}'", group.name);
+        return;
+    }
+
+    // Spread first runs over the shortest interval so hosts started together
+    // do not stay in lockstep.
+    let shortest = group
+        .jobs
+        .iter()
+        .map(|j| j.interval())
+        .min()
+        .unwrap_or_default();
+    let start = Instant::now() + scheduler.start_offset(shortest);
+
+    let mut capabilities = cached_capabilities(&conn, &group.host.id).await;
+    let mut probe = ProbeSchedule::new(start, shortest);
+    let mut next_due = vec![start; group.jobs.len()];
+    let mut paused = false;
+    let mut interval_scale = 1.0;
+    let mut rate = scheduler.adaptive_rate();
+    let mut agent = agent.map(HostAgent::new);
     loop {
-        let timeout_duration = Duration::from_secs(5);
-        match timeout(timeout_duration, run_group_once(group.clone())).await {
-            Ok(Ok(results)) => {
-                for result in results {
-                    if let Some(job_kind) = group.jobs.iter().find(|j| j.name() == result.job_name)
-                    {
-                        if let Err(e) = job_kind.save(&conn, &group.host.id, &result).await {
-                            warn!("❌ Failed to save {} result to DB: {e}", result.job_name);
-                        }
-                    } else {
-                        warn!("❌ Unknown job type: {}", result.job_name);
-                    }
-                }
+        let Some(mut wake_at) = next_due.iter().min().copied() else {
+            return;
+        };
+        let effective = |interval: Duration| rate.interval(interval).mul_f64(interval_scale);
+        scheduler.set_poll_rate(&group.host.id, rate.rate(), effective(shortest));
+        if paused {
+            scheduler.set_paused(&group.host.id, true);
+        }
+        scheduler.set_next_due(&group.host.id, (!paused).then_some(wake_at));
+        let sleep = async {
+            if paused {
+                std::future::pending::<()>().await;
             }
-            Ok(Err(e)) => {
-                warn!("❌ Error running group '{:?}': {:?}", group.name, e);
+            time::sleep_until(wake_at).await;
+        };
+        tokio::select! {
+            _ = sleep => {}
+            _ = shutdown.wait_for(|stop| *stop) => return,
+            command = commands.recv() => match command {
+                None => return,
+                Some(GroupCommand::Pause | GroupCommand::Resume) => {
+                    paused = command == Some(GroupCommand::Pause);
+                    scheduler.set_paused(&group.host.id, paused);
+                    continue;
+                }
+                Some(GroupCommand::ScaleIntervals(scale)) => {
+                    interval_scale = scale;
+                    scheduler.set_interval_scale(&group.host.id, scale);
+                    pull_in(&group.jobs, &mut next_due, Instant::now(), |interval| {
+                        rate.interval(interval).mul_f64(interval_scale)
+                    });
+                    continue;
+                }
+                Some(GroupCommand::Focus(focused)) => {
+                    rate.set_focused(focused);
+                    pull_in(&group.jobs, &mut next_due, Instant::now(), |interval| {
+                        rate.interval(interval).mul_f64(interval_scale)
+                    });
+                    continue;
+                }
+                Some(GroupCommand::RunNow) => {
+                    wake_at = Instant::now();
+                    next_due.fill(wake_at);
+                }
+            },
+        }
+
+        let now = Instant::now();
+        let mut due = take_due_jobs(&group.jobs, &mut next_due, now, |interval| {
+            scheduler.jittered(effective(interval))
+        });
+        let _permit = tokio::select! {
+            permit = scheduler.acquire(&group.host, wake_at) => permit,
+            _ = shutdown.wait_for(|stop| *stop) => return,
+        };
+        scheduler.set_next_due(&group.host.id, None);
+        let queue_lag = scheduler.host(&group.host.id).last_lag;
+
+        if probe.is_due(now) {
+            let probed = probe_capabilities(&group, &conn, &cancel).await;
+            probe.record(now, probed.is_some());
+            capabilities = probed.or(capabilities);
+        }
+        if let Some(capabilities) = &capabilities {
+            due.retain(|job| job.supports(capabilities));
+        }
+        if let Some(agent) = &mut agent
+            && due.iter().any(|job| AGENT_JOBS.contains(&job.name()))
+        {
+            agent.ensure_deployed(&group.host, &cancel).await;
+        }
+        if !due.is_empty() {
+            let samples =
+                run_due_jobs(&group, &due, &conn, agent.as_mut(), queue_lag, &cancel).await;
+            rate.observe(samples);
+        }
+    }
+}
+
+/// When to probe capabilities next. A failed probe backs off from twice the
+/// shortest job interval up to the probe's own interval, so an unreachable
+/// host does not pay for an extra SSH session every cycle.
+struct ProbeSchedule {
+    due: Instant,
+    failures: u32,
+    retry_base: Duration,
+}
+
+impl ProbeSchedule {
+    fn new(start: Instant, shortest: Duration) -> Self {
+        Self {
+            due: start,
+            failures: 0,
+            retry_base: shortest * 2,
+        }
+    }
+
+    fn is_due(&self, now: Instant) -> bool {
+        self.due <= now
+    }
+
+    fn record(&mut self, now: Instant, succeeded: bool) {
+        let interval = Collector::interval(&CapabilitiesCollector);
+        if succeeded {
+            self.failures = 0;
+            self.due = now + interval;
+        } else {
+            let backoff = self.retry_base * 2u32.saturating_pow(self.failures.min(16));
+            self.failures += 1;
+            self.due = now + backoff.min(interval);
+        }
+    }
+}
+
+/// Capabilities from an earlier run, used until this run's probe succeeds.
+async fn cached_capabilities(
+    conn: &Arc<Mutex<Connection>>,
+    host_id: &str,
+) -> Option<HostCapabilities> {
+    let rows = CapabilitiesCollector.query(conn).await.ok()?;
+    rows.into_iter()
+        .find(|row| row.host_id == host_id)
+        .map(HostCapabilities::from)
+}
+
+async fn probe_capabilities(
+    group: &JobGroup,
+    conn: &Arc<Mutex<Connection>>,
+    cancel: &Arc<AtomicBool>,
+) -> Option<HostCapabilities> {
+    let probe = CapabilitiesCollector;
+    let output = run_remote_command(
+        group,
+        &Collector::command(&probe),
+        Collector::timeout(&probe),
+        cancel,
+    )
+    .await
+    .ok()?;
+    if output.exit_status != 0 {
+        warn!(
+            "⚠️ Capability probe of '{}' exited with {}: {}",
+            group.name,
+            output.exit_status,
+            output.stderr.trim()
+        );
+    }
+
+    match Collector::parse(&probe, &output.stdout) {
+        Ok(Some(capabilities)) => {
+            info!("🧭 Capabilities of '{}': {:?}", group.name, capabilities);
+            if let Err(e) = Collector::store(&probe, conn, &group.host.id, &capabilities).await {
+                warn!("❌ Failed to save capabilities to DB: {e}");
             }
-            Err(e) => {
-                warn!("❌ Timeout while running group '{}': {e}", group.name);
+            Some(capabilities)
+        }
+        Ok(None) => {
+            warn!("⚠️ No capabilities parsed for '{}'", group.name);
+            None
+        }
+        Err(err) => {
+            error!("❌ Parse error (capabilities): {:#}", err);
+            None
+        }
+    }
+}
+
+/// Returns the jobs whose time has come and pushes their next run one
+/// (jittered) interval past `now`.
+fn take_due_jobs(
+    jobs: &[Arc<dyn DynCollector>],
+    next_due: &mut [Instant],
+    now: Instant,
+    jitter: impl Fn(Duration) -> Duration,
+) -> Vec<Arc<dyn DynCollector>> {
+    let mut due = vec![];
+    for (job, due_at) in jobs.iter().zip(next_due.iter_mut()) {
+        if *due_at <= now {
+            due.push(job.clone());
+            *due_at = now + jitter(job.interval());
+        }
+    }
+    due
+}
+
+/// Brings forward runs that are due sooner once each interval is mapped
+/// through `effective`.
+fn pull_in(
+    jobs: &[Arc<dyn DynCollector>],
+    next_due: &mut [Instant],
+    now: Instant,
+    effective: impl Fn(Duration) -> Duration,
+) {
+    for (job, due_at) in jobs.iter().zip(next_due.iter_mut()) {
+        *due_at = (*due_at).min(now + effective(job.interval()));
+    }
+}
+
+/// Runs the due collectors as one SSH exec and stores whatever parsed,
+/// together with the cycle's telemetry. Returns the parsed samples by job
+/// name.
+async fn run_due_jobs(
+    group: &JobGroup,
+    due: &[Arc<dyn DynCollector>],
+    conn: &Arc<Mutex<Connection>>,
+    agent: Option<&mut HostAgent>,
+    queue_lag: Duration,
+    cancel: &Arc<AtomicBool>,
+) -> Vec<(String, serde_json::Value)> {
+    let mut samples = vec![];
+    let telemetry = match run_jobs_once(group, due, conn, agent, cancel).await {
+        Ok(batch) => {
+            let mut telemetry = CycleTelemetry {
+                status: CycleStatus::Ok,
+                timings: Some(batch.timings),
+                parse: Some(batch.parse),
+                clock: batch.clock,
+                queue_lag,
+                jobs_ok: 0,
+                jobs_failed: 0,
+            };
+            for outcome in batch.outcomes {
+                if outcome.result.is_ok() {
+                    telemetry.jobs_ok += 1;
+                } else {
+                    telemetry.jobs_failed += 1;
+                }
+                let sample = match outcome.result {
+                    Ok(Some(sample)) => sample,
+                    Ok(None) => continue,
+                    Err(err) => {
+                        warn!(
+                            "❌ Job '{}' on '{}' failed (exit {:?}, {:?}): {err}",
+                            outcome.job_name,
+                            group.name,
+                            outcome.exit_code,
+                            outcome.duration.unwrap_or_default()
+                        );
+                        if !outcome.stderr.is_empty() {
+                            info!("🖨️ Stderr of '{}':\n{}", outcome.job_name, outcome.stderr);
+                        }
+                        continue;
+                    }
+                };
+                samples.push((outcome.job_name, sample));
             }
+            telemetry
         }
-        time::sleep(group.interval).await;
+        Err(e) => CycleTelemetry::failed(&e, queue_lag, due.len()),
+    };
+    if let Err(e) = telemetry.store(conn, &group.host.id).await {
+        warn!("❌ Failed to save cycle telemetry to DB: {e}");
     }
+    samples
+}
+
+/// The outcome of one SSH exec of a batch.
+struct BatchRun {
+    outcomes: Vec<JobOutcome>,
+    timings: SessionTimings,
+    /// Parsing and storing the frames.
+    parse: Duration,
+    clock: Option<ClockSample>,
 }
 
-async fn run_group_once(group: JobGroup) -> Result<Vec<JobResult>> {
-    info!("🚀 Running group '{}'", group.name);
+/// Runs `jobs` in one SSH exec. Jobs the host's agent implements run through
+/// it as a single extra frame; the others run as shell scripts.
+async fn run_jobs_once(
+    group: &JobGroup,
+    jobs: &[Arc<dyn DynCollector>],
+    conn: &Arc<Mutex<Connection>>,
+    agent: Option<&mut HostAgent>,
+    cancel: &Arc<AtomicBool>,
+) -> Result<BatchRun, String> {
+    let names: Vec<&str> = jobs.iter().map(|j| j.name()).collect();
+    info!("🚀 Running group '{}': {}", group.name, names.join(", "));
 
-    let Some(full_cmd) = build_combined_command(&group.jobs) else {
+    let agent_path = agent.as_ref().and_then(|agent| agent.ready_path());
+    let (agent_jobs, shell_jobs): (Vec<_>, Vec<_>) = jobs
+        .iter()
+        .partition(|job| agent_path.is_some() && AGENT_JOBS.contains(&job.name()));
+    let agent_names: Vec<&str> = agent_jobs.iter().map(|j| j.name()).collect();
+    let agent_cmd = agent_path
+        .filter(|_| !agent_names.is_empty())
+        .map(|path| agent_command(path, &agent_names));
+
+    let nonce = new_nonce();
+    let commands = shell_jobs
+        .iter()
+        .map(|job| (job.tag(), job.command()))
+        .chain(agent_cmd.map(|command| (AGENT_TAG, command)));
+    let Some(full_cmd) = build_framed_script(commands, &nonce) else {
         warn!("⚠️ No jobs in group '{}'", group.name);
-        return Ok(vec![]); // Return empty results instead of raising an error
+        return Ok(BatchRun {
+            outcomes: vec![],
+            timings: SessionTimings::default(),
+            parse: Duration::ZERO,
+            clock: None,
+        });
     };
     info!("📜 Full command to execute:\n{}", full_cmd);
 
-    let session = match connect_ssh_session(&group.host) {
-        Ok(session) => session,
-        Err(e) => {
-            warn!("❌ SSH connection failed for group '{}': {e}", group.name);
-            return Ok(vec![]); // Return empty results if connection fails
-        }
-    };
+    // A batch waits for its slowest member.
+    let batch_timeout = jobs.iter().map(|j| j.timeout()).max().unwrap_or_default();
+    let output = run_remote_command(group, &full_cmd, batch_timeout, cancel).await?;
+    if !output.stderr.trim().is_empty() {
+        warn!(
+            "⚠️ Stderr outside job frames on '{}': {}",
+            group.name,
+            output.stderr.trim()
+        );
+    }
 
-    let output = match run_ssh_command(&session, &full_cmd) {
-        Ok(output) => output,
-        Err(e) => {
-            warn!("❌ SSH execution failed for group '{}': {e}", group.name);
-            return Ok(vec![]); // Return empty results if command execution fails
+    let parse_started = Instant::now();
+    let mut frames = parse_frames(&output.stdout, &nonce);
+    let clock = parse_clock(&output.stdout, &nonce)
+        .and_then(|(start, end)| estimate(output.exec_sent_at, output.stdout_done_at, start, end));
+    if let Some(agent) = agent
+        && !agent_names.is_empty()
+    {
+        let frame = frames.remove(AGENT_TAG).unwrap_or_default();
+        let agent_frames = match frame.exit_code {
+            Some(0) => parse_agent_frames(&frame.stdout),
+            code => Err(format!("exit {code:?}: {}", frame.stderr.trim())),
+        };
+        match agent_frames {
+            Ok(mut agent_frames) => {
+                for job in &agent_jobs {
+                    if let Some(frame) = agent_frames.remove(job.name()) {
+                        frames.insert(job.tag().to_string(), frame);
+                    }
+                }
+            }
+            Err(e) => agent.fail(&group.host.id, &e),
         }
-    };
-
-    info!("🖨️ SSH Output:\n{}", output);
+    }
 
-    Ok(parse_group_results(&group, &output))
+    let outcomes = parse_group_results(jobs, frames, conn, &group.host.id).await;
+    Ok(BatchRun {
+        outcomes,
+        timings: output.timings,
+        parse: parse_started.elapsed(),
+        clock,
+    })
 }
 
-fn build_combined_command(jobs: &[JobKind]) -> Option<String> {
-    if jobs.is_empty() {
-        return None;
-    }
-    let mut script = String::new();
-    for job in jobs {
-        script.push_str(&format!("echo __BEGIN_{}__\n", job.tag()));
-        script.push_str(&format!("{}\n", job.command()));
-        script.push_str(&format!("echo __END_{}__\n", job.tag()));
-    }
-    Some(script)
+async fn run_remote_command(
+    group: &JobGroup,
+    command: &str,
+    timeout: Duration,
+    cancel: &Arc<AtomicBool>,
+) -> Result<CommandOutput, String> {
+    let output = run_remote(
+        group.host.clone(),
+        command.to_string(),
+        timeout,
+        cancel.clone(),
+    )
+    .await
+    .inspect_err(|e| warn!("❌ SSH failed for group '{}': {e}", group.name))?;
+
+    info!("🖨️ SSH Output:\n{}", output.stdout);
+    Ok(output)
 }
 
-fn parse_group_results(group: &JobGroup, output: &str) -> Vec<JobResult> {
-    let mut results = vec![];
-    for job in &group.jobs {
+/// Turns each job's frame into a stored sample or a [`JobError`]. A sample
+/// wins over a non-zero exit, since scripts often end on a harmless failing
+/// test.
+async fn parse_group_results(
+    jobs: &[Arc<dyn DynCollector>],
+    mut frames: HashMap<String, Frame>,
+    conn: &Arc<Mutex<Connection>>,
+    host_id: &str,
+) -> Vec<JobOutcome> {
+    let mut outcomes = vec![];
+    for job in jobs {
         info!("🔍 Checking job '{}'", job.name());
-        if let Some(tagged_output) = extract_tagged_output(output, job.tag()) {
-            match job.parse(tagged_output) {
-                Ok(Some(mut result)) => {
-                    result.job_name = job.name().to_string();
-                    info!("✅ Parsed result for '{}'", job.name());
-                    results.push(result);
-                }
-                Ok(None) => {
+        let frame = frames.remove(job.tag()).unwrap_or_default();
+        let failure = || match frame.exit_code {
+            Some(0) => None,
+            Some(code) => Some(JobError::from_exit(code, &frame.stderr)),
+            None => Some(JobError::NoOutput),
+        };
+        let result = match job.parse_and_store(&frame.stdout, conn, host_id).await {
+            Ok(Some(sample)) => {
+                info!(
+                    "✅ Parsed result for '{}' in {:?}: {}",
+                    job.name(),
+                    frame.duration.unwrap_or_default(),
+                    sample
+                );
+                Ok(Some(sample))
+            }
+            Ok(None) => match failure() {
+                Some(err) => Err(err),
+                None => {
                     warn!("⚠️ No result parsed for job: {}", job.name());
+                    Ok(None)
                 }
-                Err(err) => {
-                    error!("❌ Parse error ({}): {:#}", job.name(), err);
-                }
-            }
-        } else {
-            warn!("⛔ No tagged output found for '{}'", job.name());
-        }
+            },
+            Err(err) => Err(failure().unwrap_or_else(|| JobError::Parse(format!("{err:#}")))),
+        };
+        outcomes.push(JobOutcome {
+            job_name: job.name().to_string(),
+            exit_code: frame.exit_code,
+            duration: frame.duration,
+            stderr: frame.stderr,
+            result,
+        });
     }
-    results
+    outcomes
 }
 
-fn extract_tagged_output<'a>(output: &'a str, tag: &str) -> Option<&'a str> {
-    let start_tag = format!("__BEGIN_{}__", tag);
-    let end_tag = format!("__END_{}__", tag);
-    let start = output.find(&start_tag)? + start_tag.len();
-    let end = output[start..].find(&end_tag)? + start;
-    Some(output[start..end].trim())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::jobs::collector::CollectorRegistry;
+    use crate::config::{AppConfig, SchedulerConfig};
+    use crate::ssh_config::SshHostInfo;
+    use std::time::Duration;
+
+    /// A group whose host refuses connections.
+    fn unreachable_group(id: &str) -> JobGroup {
+        let registry = CollectorRegistry::with_builtins(&AppConfig::default());
+        JobGroup {
+            name: id.into(),
+            host: SshHostInfo {
+                id: id.into(),
+                name: id.into(),
+                ip: "127.0.0.1".into(),
+                port: 9,
+                user: "nobody".into(),
+                identity_file: String::new(),
+                proxy_jump: None,
+            },
+            jobs: registry.select(&["cpu"]),
+        }
+    }
+
+    #[test]
+    fn test_take_due_jobs_batches_by_interval() {
+        let registry = CollectorRegistry::with_builtins(&AppConfig::default());
+        let jobs = registry.select(&["cpu", "disk", "packages"]);
+        let start = Instant::now();
+        let mut next_due = vec![start; jobs.len()];
+
+        let names = |due: Vec<Arc<dyn DynCollector>>| {
+            due.iter().map(|j| j.name().to_string()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            names(take_due_jobs(&jobs, &mut next_due, start, |i| i)),
+            ["cpu", "disk", "packages"]
+        );
+        assert!(
+            take_due_jobs(&jobs, &mut next_due, start + Duration::from_secs(5), |i| i).is_empty()
+        );
+        assert_eq!(
+            names(take_due_jobs(
+                &jobs,
+                &mut next_due,
+                start + Duration::from_secs(10),
+                |i| i
+            )),
+            ["cpu"]
+        );
+        assert_eq!(
+            names(take_due_jobs(
+                &jobs,
+                &mut next_due,
+                start + Duration::from_secs(300),
+                |i| i
+            )),
+            ["cpu", "disk"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_probe_backs_off() {
+        let db = Arc::new(Mutex::new(Connection::open_in_memory().unwrap()));
+        let group = unreachable_group("h");
+        let cancel = Arc::new(AtomicBool::new(false));
+        let shortest = Duration::from_secs(10);
+        let start = Instant::now();
+        let mut probe = ProbeSchedule::new(start, shortest);
+
+        assert!(probe.is_due(start));
+        let probed = probe_capabilities(&group, &db, &cancel).await;
+        assert!(probed.is_none());
+        probe.record(start, probed.is_some());
+
+        // The next cycle skips the probe; later retries back off further.
+        assert!(!probe.is_due(start + shortest));
+        assert!(probe.is_due(start + shortest * 2));
+        probe.record(start + shortest * 2, false);
+        assert!(!probe.is_due(start + shortest * 5));
+        assert!(probe.is_due(start + shortest * 6));
+
+        // Backoff never exceeds the probe interval, and success resets it.
+        for _ in 0..20 {
+            probe.record(start, false);
+        }
+        assert!(probe.is_due(start + Duration::from_secs(60 * 60)));
+        probe.record(start, true);
+        assert_eq!(probe.failures, 0);
+    }
+
+    #[tokio::test]
+    async fn test_group_control_pause_scale_and_unregister() {
+        use crate::backend::jobs::scheduler::ScheduleState;
+
+        let db = Arc::new(Mutex::new(Connection::open_in_memory().unwrap()));
+        let scheduler = Arc::new(Scheduler::new(SchedulerConfig::default()));
+        let executor = JobGroupExecutor::new(db, scheduler.clone());
+        executor.register_group(unreachable_group("h")).await;
+        executor.run_all().await;
+
+        let settle = || time::sleep(Duration::from_millis(50));
+        assert!(executor.send("h", GroupCommand::Pause).await);
+        assert!(executor.send("h", GroupCommand::ScaleIntervals(2.0)).await);
+        settle().await;
+        let schedule = scheduler.snapshot().hosts["h"].clone();
+        assert_eq!(schedule.state, ScheduleState::Paused);
+        assert_eq!(schedule.next_due, None);
+        assert_eq!(schedule.interval_scale, Some(2.0));
+
+        let cancel = executor.handles.read().await["h"].cancel.clone();
+        assert!(executor.unregister("h").await);
+        assert!(cancel.load(Ordering::Relaxed));
+        settle().await;
+        assert_eq!(
+            scheduler.snapshot().hosts["h"].state,
+            ScheduleState::Stopped
+        );
+        assert!(!executor.send("h", GroupCommand::RunNow).await);
+        assert!(!executor.unregister("h").await);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_stops_waiting_groups_without_grace() {
+        let db = Arc::new(Mutex::new(Connection::open_in_memory().unwrap()));
+        let scheduler = Arc::new(Scheduler::new(SchedulerConfig {
+            max_concurrent: 1,
+            ..SchedulerConfig::default()
+        }));
+        let executor = JobGroupExecutor::new(db, scheduler.clone());
+        executor.register_group(unreachable_group("sleeping")).await;
+        executor.register_group(unreachable_group("queued")).await;
+        executor.run_all().await;
+
+        // Hold the only slot so "queued" waits in the scheduler.
+        let _slot = scheduler
+            .acquire(&unreachable_group("other").host, Instant::now())
+            .await;
+        assert!(executor.send("queued", GroupCommand::RunNow).await);
+        time::sleep(Duration::from_millis(50)).await;
+
+        let started = Instant::now();
+        executor.shutdown(Duration::from_secs(5)).await;
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(!executor.send("sleeping", GroupCommand::RunNow).await);
+    }
 }
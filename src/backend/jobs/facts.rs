@@ -0,0 +1,322 @@
+use super::collector::Collector;
+use crate::backend::db::facts::commands::{HostFactsUpsert, upsert_host_facts};
+use crate::backend::db::facts::queries::{HostFactsRow, fetch_host_facts_all};
+use anyhow::Result;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+pub const FACTS_COMMAND: &str = r#"sh -c '
+  echo __UNAME__; uname -a; uname -r; uname -m;
+  if [ "$(uname -s)" = Darwin ]; then
+    echo __OS__; sw_vers;
+    echo __CPU__;
+    echo "Model name: $(sysctl -n machdep.cpu.brand_string)";
+    echo "CPU(s): $(sysctl -n hw.logicalcpu)";
+    echo "Socket(s): $(sysctl -n hw.packages)";
+    echo __DMI__; echo "Apple Inc."; sysctl -n hw.model;
+    echo __MEM__; sysctl -n hw.memsize;
+    echo __VIRT__; [ "$(sysctl -n kern.hv_vmm_present 2>/dev/null)" = 1 ] && echo vm || echo none;
+  else
+    echo __OS__; cat /etc/os-release 2>/dev/null;
+    echo __CPU__;
+    lscpu 2>/dev/null || awk -F: "/^model name/ && !m { print \"Model name:\" \$2; m = 1 } /^processor/ { n++ } END { print \"CPU(s): \" n }" /proc/cpuinfo;
+    echo __DMI__; cat /sys/class/dmi/id/sys_vendor /sys/class/dmi/id/product_name 2>/dev/null;
+    echo __MEM__; grep MemTotal /proc/meminfo;
+    echo __VIRT__; systemd-detect-virt 2>/dev/null;
+  fi
+'"#;
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct HostFacts {
+    pub os_name: String,
+    pub kernel_release: String,
+    pub arch: String,
+    pub uname: String,
+    pub cpu_model: String,
+    pub cpu_count: u32,
+    pub cpu_sockets: Option<u32>,
+    pub vendor: Option<String>,
+    pub product: Option<String>,
+    pub total_memory_mb: u64,
+    pub virtualization: Option<String>,
+}
+
+fn section<'a>(output: &'a str, name: &str) -> &'a str {
+    let marker = format!("__{}__", name);
+    output
+        .split_once(&marker)
+        .map(|(_, rest)| rest.split("\n__").next().unwrap_or(rest))
+        .unwrap_or("")
+}
+
+fn key_values(output: &str, separator: char) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once(separator))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().trim_matches('"').to_string()))
+        .collect()
+}
+
+/// `/etc/os-release` carries `PRETTY_NAME`; macOS `sw_vers` prints
+/// `ProductName:` and `ProductVersion:` instead.
+fn parse_os(output: &str) -> String {
+    let os_release = key_values(output, '=');
+    if let Some(name) = os_release.get("PRETTY_NAME") {
+        return name.clone();
+    }
+    let sw_vers = key_values(output, ':');
+    match (sw_vers.get("ProductName"), sw_vers.get("ProductVersion")) {
+        (Some(name), Some(version)) => format!("{} {}", name, version),
+        (Some(name), None) => name.clone(),
+        _ => String::new(),
+    }
+}
+
+/// `MemTotal:` from `/proc/meminfo` is in kB, `hw.memsize` in bytes.
+fn parse_memory_mb(output: &str) -> u64 {
+    let line = output
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("");
+    if let Some(rest) = line.strip_prefix("MemTotal:") {
+        let kb: u64 = rest
+            .split_whitespace()
+            .next()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+        kb / 1024
+    } else {
+        line.parse::<u64>().unwrap_or(0) / 1024 / 1024
+    }
+}
+
+fn non_empty(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+pub fn parse_facts(output: &str) -> Result<Option<HostFacts>> {
+    let uname_lines: Vec<&str> = section(output, "UNAME")
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    if uname_lines.is_empty() {
+        return Ok(None);
+    }
+
+    let cpu = key_values(section(output, "CPU"), ':');
+    let dmi: Vec<&str> = section(output, "DMI")
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    // Containers and hosts without systemd have no `systemd-detect-virt`;
+    // lscpu still reports the hypervisor in that case.
+    let virtualization = non_empty(
+        section(output, "VIRT")
+            .lines()
+            .find(|l| !l.trim().is_empty()),
+    )
+    .or_else(|| cpu.get("Hypervisor vendor").cloned());
+
+    let facts = HostFacts {
+        os_name: parse_os(section(output, "OS")),
+        uname: uname_lines[0].to_string(),
+        kernel_release: uname_lines.get(1).unwrap_or(&"").to_string(),
+        arch: uname_lines.get(2).unwrap_or(&"").to_string(),
+        cpu_model: cpu.get("Model name").cloned().unwrap_or_default(),
+        cpu_count: cpu.get("CPU(s)").and_then(|v| v.parse().ok()).unwrap_or(0),
+        cpu_sockets: cpu.get("Socket(s)").and_then(|v| v.parse().ok()),
+        vendor: non_empty(dmi.first().copied()),
+        product: non_empty(dmi.get(1).copied()),
+        total_memory_mb: parse_memory_mb(section(output, "MEM")),
+        virtualization,
+    };
+
+    Ok(Some(facts))
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FactsCollector;
+
+#[async_trait::async_trait]
+impl Collector for FactsCollector {
+    type Sample = HostFacts;
+
+    fn name(&self) -> &str {
+        "facts"
+    }
+
+    fn command(&self) -> String {
+        FACTS_COMMAND.to_string()
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn parse(&self, output: &str) -> Result<Option<HostFacts>> {
+        parse_facts(output)
+    }
+
+    async fn store(
+        &self,
+        conn: &Arc<Mutex<Connection>>,
+        host_id: &str,
+        facts: &HostFacts,
+    ) -> Result<()> {
+        let upsert = HostFactsUpsert {
+            host_id: host_id.to_string(),
+            os_name: facts.os_name.clone(),
+            kernel_release: facts.kernel_release.clone(),
+            arch: facts.arch.clone(),
+            uname: facts.uname.clone(),
+            cpu_model: facts.cpu_model.clone(),
+            cpu_count: facts.cpu_count,
+            cpu_sockets: facts.cpu_sockets,
+            vendor: facts.vendor.clone(),
+            product: facts.product.clone(),
+            total_memory_mb: facts.total_memory_mb,
+            virtualization: facts.virtualization.clone(),
+        };
+        upsert_host_facts(conn, &upsert).await
+    }
+}
+
+impl FactsCollector {
+    pub async fn query(&self, conn: &Arc<Mutex<Connection>>) -> Result<Vec<HostFactsRow>> {
+        fetch_host_facts_all(conn).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> HostFacts {
+        parse_facts(input).unwrap().expect("facts result")
+    }
+
+    #[test]
+    fn test_parse_facts_linux() {
+        let facts = parse(
+            r#"__UNAME__
+Linux gpu01 6.8.0-45-generic #45-Ubuntu SMP PREEMPT_DYNAMIC Fri Aug 30 12:02:04 UTC 2024 x86_64 x86_64 x86_64 GNU/Linux
+6.8.0-45-generic
+x86_64
+__OS__
+PRETTY_NAME="Ubuntu 24.04.1 LTS"
+NAME="Ubuntu"
+VERSION_ID="24.04"
+__CPU__
+Architecture:                         x86_64
+CPU(s):                               32
+Model name:                           AMD Ryzen 9 5950X 16-Core Processor
+Socket(s):                            1
+__DMI__
+ASUS
+System Product Name
+__MEM__
+MemTotal:       65793628 kB
+__VIRT__
+none
+"#,
+        );
+
+        assert_eq!(facts.os_name, "Ubuntu 24.04.1 LTS");
+        assert_eq!(facts.kernel_release, "6.8.0-45-generic");
+        assert_eq!(facts.arch, "x86_64");
+        assert!(facts.uname.starts_with("Linux gpu01"));
+        assert_eq!(facts.cpu_model, "AMD Ryzen 9 5950X 16-Core Processor");
+        assert_eq!(facts.cpu_count, 32);
+        assert_eq!(facts.cpu_sockets, Some(1));
+        assert_eq!(facts.vendor.as_deref(), Some("ASUS"));
+        assert_eq!(facts.product.as_deref(), Some("System Product Name"));
+        assert_eq!(facts.total_memory_mb, 64251);
+        assert_eq!(facts.virtualization.as_deref(), Some("none"));
+    }
+
+    #[test]
+    fn test_parse_facts_container_without_systemd() {
+        let facts = parse(
+            r#"__UNAME__
+Linux web-1 5.15.0-1034-aws #38-Ubuntu SMP x86_64 GNU/Linux
+5.15.0-1034-aws
+x86_64
+__OS__
+PRETTY_NAME="Debian GNU/Linux 12 (bookworm)"
+__CPU__
+CPU(s):                               4
+Model name:                           Intel(R) Xeon(R) Platinum 8259CL CPU @ 2.50GHz
+Hypervisor vendor:                    KVM
+__DMI__
+__MEM__
+MemTotal:       16202048 kB
+__VIRT__
+"#,
+        );
+
+        assert_eq!(facts.vendor, None);
+        assert_eq!(facts.virtualization.as_deref(), Some("KVM"));
+        assert_eq!(facts.total_memory_mb, 15822);
+    }
+
+    #[test]
+    fn test_parse_facts_macos() {
+        let facts = parse(
+            r#"__UNAME__
+Darwin mbp.local 24.1.0 Darwin Kernel Version 24.1.0: Thu Oct 10 21:06:23 PDT 2024; root:xnu-11215.41.3~3/RELEASE_ARM64_T6041 arm64
+24.1.0
+arm64
+__OS__
+ProductName:		macOS
+ProductVersion:		15.1
+BuildVersion:		24B83
+__CPU__
+Model name: Apple M4 Pro
+CPU(s): 14
+Socket(s): 1
+__DMI__
+Apple Inc.
+Mac16,8
+__MEM__
+51539607552
+__VIRT__
+none
+"#,
+        );
+
+        assert_eq!(facts.os_name, "macOS 15.1");
+        assert_eq!(facts.arch, "arm64");
+        assert_eq!(facts.cpu_model, "Apple M4 Pro");
+        assert_eq!(facts.cpu_count, 14);
+        assert_eq!(facts.product.as_deref(), Some("Mac16,8"));
+        assert_eq!(facts.total_memory_mb, 49152);
+    }
+
+    #[test]
+    fn test_parse_facts_alpine_without_lscpu() {
+        let facts = parse(include_str!("fixtures/busybox/facts.txt"));
+
+        assert_eq!(facts.os_name, "Alpine Linux v3.20");
+        assert_eq!(facts.cpu_model, "Intel Xeon Processor (Skylake, IBRS)");
+        assert_eq!(facts.cpu_count, 2);
+        assert_eq!(facts.cpu_sockets, None);
+        assert_eq!(facts.vendor.as_deref(), Some("QEMU"));
+        assert_eq!(facts.virtualization, None);
+        assert_eq!(facts.total_memory_mb, 3936);
+    }
+}
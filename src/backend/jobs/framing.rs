@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// What one job printed inside the combined command.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Frame {
+    pub stdout: String,
+    pub stderr: String,
+    /// `None` when the end marker never arrived (the script was cut short).
+    pub exit_code: Option<i32>,
+    /// Remote wall time; second resolution where `date` lacks `%N`.
+    pub duration: Option<Duration>,
+}
+
+/// A fresh marker for one run, so job output cannot fake a frame boundary.
+pub fn new_nonce() -> String {
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    if let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) {
+        hasher.write_u128(now.as_nanos());
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+/// Builds one POSIX sh script running every `(tag, command)` job in its own
+/// subshell. Each job prints `@<nonce> begin <tag> <ts>`, its stdout,
+/// `@<nonce> end <tag> <rc> <ts>` and then its stderr as
+/// `@<nonce> err <tag> <line>` lines. The script starts and ends with
+/// `@<nonce> clock <epoch>` to compare the host's clock with ours. It is
+/// wrapped in `sh -c` so it does not depend on the login shell.
+pub fn build_framed_script<'a>(
+    commands: impl IntoIterator<Item = (&'a str, String)>,
+    nonce: &str,
+) -> Option<String> {
+    let mut commands = commands.into_iter().peekable();
+    commands.peek()?;
+    let clock = format!("echo \"@{nonce} clock $(date +%s.%N)\"\n");
+    let mut script = clock.clone();
+    script.push_str("sm_err=$(mktemp 2>/dev/null || echo /tmp/ssh-monitor.$$)\n");
+    for (tag, command) in commands {
+        script.push_str(&format!("echo \"@{nonce} begin {tag} $(date +%s%N)\"\n"));
+        script.push_str(&format!("(\n{}\n) </dev/null 2>\"$sm_err\"\n", command));
+        script.push_str("sm_rc=$?\necho\n");
+        script.push_str(&format!(
+            "echo \"@{nonce} end {tag} $sm_rc $(date +%s%N)\"\n"
+        ));
+        script.push_str(&format!("sed \"s/^/@{nonce} err {tag} /\" \"$sm_err\"\n"));
+    }
+    script.push_str("rm -f \"$sm_err\"\n");
+    script.push_str(&clock);
+    Some(format!("sh -c '{}'", script.replace('\'', r"'\''")))
+}
+
+/// `date +%s%N` prints nanoseconds on GNU and BusyBox, and seconds followed
+/// by a literal `N` on macOS.
+fn parse_timestamp(token: &str) -> Option<Duration> {
+    let digits: String = token.chars().take_while(|c| c.is_ascii_digit()).collect();
+    let value: u64 = digits.parse().ok()?;
+    if digits.len() > 10 {
+        Some(Duration::from_nanos(value))
+    } else {
+        Some(Duration::from_secs(value))
+    }
+}
+
+/// The host's wall clock in seconds since the epoch when the script started
+/// and ended. `date` without `%N` support only gives whole seconds.
+pub fn parse_clock(output: &str, nonce: &str) -> Option<(f64, f64)> {
+    let prefix = format!("@{nonce} clock ");
+    let mut stamps = output.lines().filter_map(|line| {
+        let (secs, fraction) = line.strip_prefix(&prefix)?.trim().split_once('.')?;
+        let secs: f64 = secs.parse().ok()?;
+        let fraction = match fraction.parse::<u64>() {
+            Ok(nanos) => nanos as f64 / 10f64.powi(fraction.len() as i32),
+            Err(_) => 0.0,
+        };
+        Some(secs + fraction)
+    });
+    let started = stamps.next()?;
+    Some((started, stamps.next_back().unwrap_or(started)))
+}
+
+/// Splits the script output into frames keyed by job tag. Lines without the
+/// nonce belong to the job whose frame is open and are ignored otherwise.
+pub fn parse_frames(output: &str, nonce: &str) -> HashMap<String, Frame> {
+    let prefix = format!("@{nonce} ");
+    let mut frames: HashMap<String, Frame> = HashMap::new();
+    let mut open: Option<(String, Option<Duration>)> = None;
+
+    for line in output.lines() {
+        let Some(marker) = line.strip_prefix(&prefix) else {
+            if let Some((tag, _)) = &open {
+                let frame = frames.entry(tag.clone()).or_default();
+                frame.stdout.push_str(line);
+                frame.stdout.push('\n');
+            }
+            continue;
+        };
+
+        let mut parts = marker.splitn(3, ' ');
+        let (kind, tag, rest) = (
+            parts.next().unwrap_or(""),
+            parts.next().unwrap_or("").to_string(),
+            parts.next().unwrap_or(""),
+        );
+        match kind {
+            "begin" => {
+                frames.insert(tag.clone(), Frame::default());
+                open = Some((tag, parse_timestamp(rest)));
+            }
+            "end" => {
+                let started = open.take().and_then(|(_, started)| started);
+                let mut fields = rest.split_whitespace();
+                let frame = frames.entry(tag).or_default();
+                frame.exit_code = fields.next().and_then(|rc| rc.parse().ok());
+                let ended = fields.next().and_then(parse_timestamp);
+                frame.duration = match (started, ended) {
+                    (Some(start), Some(end)) => end.checked_sub(start),
+                    _ => None,
+                };
+            }
+            "err" => {
+                let frame = frames.entry(tag).or_default();
+                frame.stderr.push_str(rest);
+                frame.stderr.push('\n');
+            }
+            _ => {}
+        }
+    }
+
+    for frame in frames.values_mut() {
+        frame.stdout = frame.stdout.trim().to_string();
+        frame.stderr = frame.stderr.trim_end().to_string();
+    }
+    frames
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::jobs::collector::CollectorRegistry;
+    use crate::config::AppConfig;
+
+    #[test]
+    fn test_parse_frames_ignores_foreign_markers() {
+        let output = "\
+@abc begin cpu 1700000000000000000
+__STAT__
+@xyz end cpu 0 1
+cpu 12.5
+@abc end cpu 0 1700000000250000000
+@abc begin gpu 1700000000
+@abc end gpu 127 1700000001
+@abc err gpu sh: nvidia-smi: not found
+@abc begin mem 1700000001
+Linux
+";
+        let frames = parse_frames(output, "abc");
+
+        let cpu = &frames["cpu"];
+        assert_eq!(cpu.stdout, "__STAT__\n@xyz end cpu 0 1\ncpu 12.5");
+        assert_eq!(cpu.exit_code, Some(0));
+        assert_eq!(cpu.duration, Some(Duration::from_millis(250)));
+
+        let gpu = &frames["gpu"];
+        assert_eq!(gpu.stdout, "");
+        assert_eq!(gpu.exit_code, Some(127));
+        assert_eq!(gpu.stderr, "sh: nvidia-smi: not found");
+        assert_eq!(gpu.duration, Some(Duration::from_secs(1)));
+
+        let mem = &frames["mem"];
+        assert_eq!(mem.stdout, "Linux");
+        assert_eq!(mem.exit_code, None);
+    }
+
+    #[test]
+    fn test_build_framed_script() {
+        let registry = CollectorRegistry::with_builtins(&AppConfig::default());
+        assert!(build_framed_script([], "abc").is_none());
+
+        let jobs = registry.select(&["cpu"]);
+        let script =
+            build_framed_script(jobs.iter().map(|j| (j.tag(), j.command())), "abc").unwrap();
+        assert!(script.starts_with("sh -c '"));
+        assert!(script.contains("@abc begin cpu"));
+        assert!(script.contains("@abc end cpu $sm_rc"));
+        assert_ne!(new_nonce(), new_nonce());
+    }
+}
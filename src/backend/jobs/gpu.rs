@@ -1,101 +1,463 @@
-use super::job::JobResult;
+use super::capabilities::HostCapabilities;
+use super::collector::Collector;
+use crate::backend::db::gpu::commands::{GpuProcessInsert, GpuResultInsert, store_gpu_sample};
+use crate::backend::db::gpu::queries::{GpuResultRow, fetch_latest_gpu_all};
 use anyhow::Result;
+use rusqlite::Connection;
 use serde::Serialize;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
 
-pub const GPU_COMMAND: &str = r#"bash -c '
-if [[ "$(uname)" == "Darwin" ]]; then
+pub const GPU_COMMAND: &str = r#"sh -c '
+if [ "$(uname)" = Darwin ]; then
   system_profiler SPDisplaysDataType
-else
-  nvidia-smi --query-gpu=name,memory.total,memory.used,temperature.gpu --format=csv,noheader,nounits 2>&1
+elif command -v nvidia-smi >/dev/null 2>&1; then
+  nvidia-smi --query-gpu=index,uuid,pci.bus_id,name,memory.total,memory.used,temperature.gpu,utilization.gpu,utilization.memory,power.draw,power.limit --format=csv,noheader,nounits 2>&1
+  echo __GPU_APPS__
+  nvidia-smi --query-compute-apps=gpu_uuid,pid,process_name,used_memory --format=csv,noheader,nounits 2>/dev/null | while IFS=, read -r uuid pid pname pmem; do
+    echo "$uuid,$pid,$(ps -o user= -p $pid 2>/dev/null | tr -d " "),$pname,$pmem"
+  done
+  echo __GPU_MIG__
+  nvidia-smi -L 2>/dev/null
+elif command -v rocm-smi >/dev/null 2>&1; then
+  echo __ROCM__
+  rocm-smi --showuse --showmemuse --showtemp --showpower --showmaxpower --showmeminfo vram --showproductname --showuniqueid --showbus --json 2>/dev/null
 fi
 '"#;
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum GpuVendor {
+    Nvidia,
+    Amd,
+    Apple,
+    Intel,
+    #[default]
+    Unknown,
+}
+
+impl GpuVendor {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GpuVendor::Nvidia => "nvidia",
+            GpuVendor::Amd => "amd",
+            GpuVendor::Apple => "apple",
+            GpuVendor::Intel => "intel",
+            GpuVendor::Unknown => "unknown",
+        }
+    }
+
+    fn from_label(label: &str) -> Self {
+        let label = label.to_lowercase();
+        // Intel is checked before AMD and "amd" must be a whole word, since
+        // substrings like "ati" turn up inside unrelated words ("Corporation").
+        let has_word = |word: &str| {
+            label
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .any(|w| w == word)
+        };
+        if label.contains("nvidia") {
+            GpuVendor::Nvidia
+        } else if label.contains("intel") {
+            GpuVendor::Intel
+        } else if label.contains("advanced micro devices")
+            || label.contains("ati technologies")
+            || label.contains("radeon")
+            || has_word("amd")
+        {
+            GpuVendor::Amd
+        } else if label.contains("apple") {
+            GpuVendor::Apple
+        } else {
+            GpuVendor::Unknown
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
 pub struct GpuInfo {
     pub index: u32,
+    pub vendor: GpuVendor,
+    pub uuid: Option<String>,
+    pub pci_bus_id: Option<String>,
+    pub parent_uuid: Option<String>,
+    pub mig_device_index: Option<u32>,
     pub name: String,
     pub memory_total_mb: Option<u64>,
     pub memory_used_mb: Option<u64>,
     pub temperature_c: Option<f32>,
+    pub utilization_gpu_percent: Option<f32>,
+    pub utilization_memory_percent: Option<f32>,
+    pub power_draw_w: Option<f32>,
+    pub power_limit_w: Option<f32>,
+    pub processes: Vec<GpuProcessInfo>,
     pub raw_output: Option<String>,
 }
 
+impl GpuInfo {
+    /// Identity used to key per-GPU history. `index` is only a fallback because
+    /// it is renumbered when a card drops off the bus or the driver reloads.
+    pub fn key(&self) -> String {
+        self.uuid
+            .clone()
+            .or_else(|| self.pci_bus_id.clone())
+            .unwrap_or_else(|| format!("index:{}", self.index))
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GpuProcessInfo {
+    pub pid: u32,
+    pub user: String,
+    pub process_name: String,
+    pub used_memory_mb: Option<u64>,
+}
+
+/// nvidia-smi reports unavailable fields as `[N/A]` or `[Not Supported]`,
+/// which simply fail to parse and end up as `None`.
+fn parse_field<T: std::str::FromStr>(value: &str) -> Option<T> {
+    value.trim().parse::<T>().ok()
+}
+
+fn parse_gpu_processes(output: &str) -> Vec<(String, GpuProcessInfo)> {
+    let mut processes = Vec::new();
+    for line in output.lines() {
+        let parts: Vec<&str> = line.split(',').map(|p| p.trim()).collect();
+        if parts.len() < 5 {
+            continue;
+        }
+        let Some(pid) = parse_field::<u32>(parts[1]) else {
+            continue;
+        };
+        let last = parts.len() - 1;
+        processes.push((
+            parts[0].to_string(),
+            GpuProcessInfo {
+                pid,
+                user: parts[2].to_string(),
+                process_name: parts[3..last].join(","),
+                used_memory_mb: parse_field(parts[last]),
+            },
+        ));
+    }
+    processes
+}
+
+/// Parses `nvidia-smi -L`, which is the only place MIG instances show up:
+///
+/// ```text
+/// GPU 0: NVIDIA A100-SXM4-40GB (UUID: GPU-...)
+///   MIG 3g.20gb     Device  0: (UUID: MIG-...)
+/// ```
+fn parse_mig_devices(output: &str, parents: &[GpuInfo]) -> Vec<GpuInfo> {
+    let mut devices = Vec::new();
+    let mut parent: Option<&GpuInfo> = None;
+    for line in output.lines() {
+        let line = line.trim();
+        let uuid = line
+            .split_once("(UUID:")
+            .map(|(_, rest)| rest.trim_end_matches(')').trim().to_string());
+
+        if line.starts_with("GPU ") {
+            parent = parents
+                .iter()
+                .find(|gpu| gpu.uuid.is_some() && gpu.uuid == uuid);
+        } else if let Some(rest) = line.strip_prefix("MIG ")
+            && let Some(parent) = parent
+        {
+            let profile = rest.split_whitespace().next().unwrap_or_default();
+            let device_index = rest
+                .split_once("Device")
+                .and_then(|(_, tail)| tail.split(':').next())
+                .and_then(parse_field::<u32>);
+            devices.push(GpuInfo {
+                index: parent.index,
+                vendor: GpuVendor::Nvidia,
+                uuid,
+                pci_bus_id: parent.pci_bus_id.clone(),
+                parent_uuid: parent.uuid.clone(),
+                mig_device_index: device_index,
+                name: format!("MIG {}", profile),
+                ..Default::default()
+            });
+        }
+    }
+    devices
+}
+
 fn parse_gpu_linux(output: &str) -> Vec<GpuInfo> {
+    let (output, mig_part) = output.split_once("__GPU_MIG__").unwrap_or((output, ""));
+    let (gpu_part, apps_part) = output.split_once("__GPU_APPS__").unwrap_or((output, ""));
+
     let mut infos = Vec::new();
-    let lines: Vec<&str> = output.lines().collect();
-    for (idx, line) in lines.iter().enumerate() {
+    for line in gpu_part.lines() {
         let parts: Vec<&str> = line.split(',').map(|p| p.trim()).collect();
-        if parts.len() >= 4 {
-            let name = parts[0].to_string();
-            let memory_total_mb = parts[1].parse::<u64>().ok();
-            let memory_used_mb = parts[2].parse::<u64>().ok();
-            let temperature_c = parts[3].parse::<f32>().ok();
-            infos.push(GpuInfo {
-                index: idx as u32,
-                name,
-                memory_total_mb,
-                memory_used_mb,
-                temperature_c,
-                raw_output: None,
-            });
+        if parts.len() < 11 {
+            continue;
+        }
+        let Some(index) = parse_field::<u32>(parts[0]) else {
+            continue;
+        };
+        infos.push(GpuInfo {
+            index,
+            vendor: GpuVendor::Nvidia,
+            uuid: Some(parts[1].to_string()),
+            pci_bus_id: Some(parts[2].to_string()),
+            name: parts[3].to_string(),
+            memory_total_mb: parse_field(parts[4]),
+            memory_used_mb: parse_field(parts[5]),
+            temperature_c: parse_field(parts[6]),
+            utilization_gpu_percent: parse_field(parts[7]),
+            utilization_memory_percent: parse_field(parts[8]),
+            power_draw_w: parse_field(parts[9]),
+            power_limit_w: parse_field(parts[10]),
+            ..Default::default()
+        });
+    }
+
+    for (gpu_uuid, process) in parse_gpu_processes(apps_part) {
+        if let Some(info) = infos
+            .iter_mut()
+            .find(|info| info.uuid.as_deref() == Some(gpu_uuid.as_str()))
+        {
+            info.processes.push(process);
         }
     }
+
+    let mig_devices = parse_mig_devices(mig_part, &infos);
+    infos.extend(mig_devices);
+
     infos
 }
 
 fn parse_gpu_macos(output: &str) -> Vec<GpuInfo> {
-    let mut infos = Vec::new();
-    let lines: Vec<&str> = output.lines().collect();
-    for (idx, line) in lines.iter().enumerate() {
+    let mut infos: Vec<GpuInfo> = Vec::new();
+    for line in output.lines() {
         if let Some(rest) = line.split_once("Chipset Model:") {
+            let name = rest.1.trim().to_string();
             infos.push(GpuInfo {
-                index: idx as u32,
-                name: rest.1.trim().to_string(),
-                memory_total_mb: None,
-                memory_used_mb: None,
-                temperature_c: None,
-                raw_output: None,
+                index: infos.len() as u32,
+                vendor: GpuVendor::from_label(&name),
+                name,
+                ..Default::default()
             });
+        } else if let Some(rest) = line.split_once("Vendor:")
+            && let Some(info) = infos.last_mut()
+        {
+            info.vendor = GpuVendor::from_label(rest.1);
         }
     }
     infos
 }
 
-pub fn parse_gpu(output: &str) -> Result<Option<JobResult>> {
-    Ok(Some(JobResult {
-        job_name: "gpu".into(),
-        value: Box::new(if output.contains("Chipset Model:") {
-            parse_gpu_macos(output)
-        } else {
-            parse_gpu_linux(output)
-        }),
+/// rocm-smi prints every value as a string keyed by a human readable label,
+/// and the labels drift between ROCm releases, so fields are looked up by
+/// label fragments rather than exact keys.
+fn rocm_field<'a>(card: &'a Value, fragments: &[&str]) -> Option<&'a str> {
+    let object = card.as_object()?;
+    fragments.iter().find_map(|fragment| {
+        object
+            .iter()
+            .find(|(key, _)| key.contains(fragment))
+            .and_then(|(_, value)| value.as_str())
+    })
+}
+
+fn rocm_number<T: std::str::FromStr>(card: &Value, fragments: &[&str]) -> Option<T> {
+    rocm_field(card, fragments).and_then(parse_field)
+}
+
+fn parse_gpu_rocm(output: &str) -> Vec<GpuInfo> {
+    let Some(json_start) = output.find('{') else {
+        return Vec::new();
+    };
+    let Ok(Value::Object(cards)) = serde_json::from_str::<Value>(&output[json_start..]) else {
+        return Vec::new();
+    };
+
+    let mut infos: Vec<GpuInfo> = cards
+        .iter()
+        .filter_map(|(key, card)| {
+            let index = key.strip_prefix("card")?.parse::<u32>().ok()?;
+            let name = rocm_field(card, &["Card series", "Device Name", "Card model"])
+                .unwrap_or("AMD GPU")
+                .to_string();
+            let bytes_to_mb = |bytes: u64| bytes / 1024 / 1024;
+            Some(GpuInfo {
+                index,
+                vendor: GpuVendor::Amd,
+                uuid: rocm_field(card, &["Unique ID"]).map(str::to_string),
+                pci_bus_id: rocm_field(card, &["PCI Bus"]).map(str::to_string),
+                name,
+                memory_total_mb: rocm_number::<u64>(card, &["VRAM Total Memory (B)"])
+                    .map(bytes_to_mb),
+                memory_used_mb: rocm_number::<u64>(card, &["VRAM Total Used Memory (B)"])
+                    .map(bytes_to_mb),
+                temperature_c: rocm_number(card, &["(Sensor edge)", "Temperature"]),
+                utilization_gpu_percent: rocm_number(card, &["GPU use (%)"]),
+                utilization_memory_percent: rocm_number(
+                    card,
+                    &["GPU memory use (%)", "GPU Memory Allocated (VRAM%)"],
+                ),
+                power_draw_w: rocm_number(
+                    card,
+                    &[
+                        "Average Graphics Package Power (W)",
+                        "Current Socket Graphics Package Power (W)",
+                    ],
+                ),
+                power_limit_w: rocm_number(card, &["Max Graphics Package Power (W)"]),
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    infos.sort_by_key(|info| info.index);
+    infos
+}
+
+pub fn parse_gpu(output: &str) -> Result<Option<Vec<GpuInfo>>> {
+    Ok(Some(if output.contains("Chipset Model:") {
+        parse_gpu_macos(output)
+    } else if let Some((_, rocm_output)) = output.split_once("__ROCM__") {
+        parse_gpu_rocm(rocm_output)
+    } else {
+        parse_gpu_linux(output)
     }))
 }
 
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GpuCollector;
+
+#[async_trait::async_trait]
+impl Collector for GpuCollector {
+    type Sample = Vec<GpuInfo>;
+
+    fn name(&self) -> &str {
+        "gpu"
+    }
+
+    fn command(&self) -> String {
+        GPU_COMMAND.to_string()
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(15)
+    }
+
+    /// Hosts without a GPU tool have nothing to report.
+    fn supports(&self, capabilities: &HostCapabilities) -> bool {
+        capabilities.is_darwin() || capabilities.nvidia_smi || capabilities.rocm_smi
+    }
+
+    fn parse(&self, output: &str) -> Result<Option<Vec<GpuInfo>>> {
+        parse_gpu(output)
+    }
+
+    async fn store(
+        &self,
+        conn: &Arc<Mutex<Connection>>,
+        host_id: &str,
+        gpu_infos: &Vec<GpuInfo>,
+    ) -> Result<()> {
+        let gpus: Vec<GpuResultInsert> = gpu_infos
+            .iter()
+            .map(|info| GpuResultInsert {
+                host_id: host_id.to_string(),
+                gpu_key: info.key(),
+                gpu_index: info.index,
+                name: info.name.clone(),
+                memory_total_mb: info.memory_total_mb,
+                memory_used_mb: info.memory_used_mb,
+                temperature_c: info.temperature_c,
+                gpu_uuid: info.uuid.clone(),
+                pci_bus_id: info.pci_bus_id.clone(),
+                parent_uuid: info.parent_uuid.clone(),
+                mig_device_index: info.mig_device_index,
+                utilization_gpu: info.utilization_gpu_percent,
+                utilization_memory: info.utilization_memory_percent,
+                power_draw_w: info.power_draw_w,
+                power_limit_w: info.power_limit_w,
+                vendor: info.vendor.as_str().to_string(),
+                raw_output: info.raw_output.clone(),
+            })
+            .collect();
+
+        let processes: Vec<GpuProcessInsert> = gpu_infos
+            .iter()
+            .flat_map(|info| {
+                info.processes.iter().map(|process| GpuProcessInsert {
+                    host_id: host_id.to_string(),
+                    gpu_index: info.index,
+                    gpu_uuid: info.uuid.clone(),
+                    pid: process.pid,
+                    user: process.user.clone(),
+                    process_name: process.process_name.clone(),
+                    used_memory_mb: process.used_memory_mb,
+                })
+            })
+            .collect();
+
+        store_gpu_sample(conn, &gpus, &processes).await
+    }
+}
+
+impl GpuCollector {
+    pub async fn query(&self, conn: &Arc<Mutex<Connection>>) -> Result<Vec<GpuResultRow>> {
+        fetch_latest_gpu_all(conn).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_parse_gpu_linux() -> Result<()> {
-        let input = "GeForce GTX 1080 Ti, 11178, 4523, 70\nTesla K80, 11441, 0, 35";
+        let input = r#"0, GPU-5a3c0e1f-1111-2222-3333-444455556666, 00000000:01:00.0, GeForce GTX 1080 Ti, 11178, 4523, 70, 87, 41, 180.52, 250.00
+1, GPU-9b8a7c6d-aaaa-bbbb-cccc-ddddeeeeffff, 00000000:02:00.0, Tesla K80, 11441, 0, 35, 0, 0, [N/A], [N/A]
+__GPU_APPS__
+GPU-5a3c0e1f-1111-2222-3333-444455556666, 4242, alice, /usr/bin/python3, 4200
+GPU-5a3c0e1f-1111-2222-3333-444455556666, 4300, bob, ./train, 300"#;
 
         let result = parse_gpu(input)?;
 
         assert!(result.is_some());
-        let job_result = result.unwrap();
-        let infos: &Vec<GpuInfo> = job_result.value.downcast_ref::<Vec<GpuInfo>>().unwrap();
+        let infos = result.unwrap();
         assert_eq!(infos.len(), 2);
 
+        assert_eq!(infos[0].index, 0);
+        assert_eq!(infos[0].vendor, GpuVendor::Nvidia);
+        assert_eq!(
+            infos[0].uuid.as_deref(),
+            Some("GPU-5a3c0e1f-1111-2222-3333-444455556666")
+        );
+        assert_eq!(infos[0].pci_bus_id.as_deref(), Some("00000000:01:00.0"));
+        assert_eq!(infos[0].key(), "GPU-5a3c0e1f-1111-2222-3333-444455556666");
         assert_eq!(infos[0].name, "GeForce GTX 1080 Ti");
         assert_eq!(infos[0].memory_total_mb, Some(11178));
         assert_eq!(infos[0].memory_used_mb, Some(4523));
         assert_eq!(infos[0].temperature_c, Some(70.0));
+        assert_eq!(infos[0].utilization_gpu_percent, Some(87.0));
+        assert_eq!(infos[0].utilization_memory_percent, Some(41.0));
+        assert_eq!(infos[0].power_draw_w, Some(180.52));
+        assert_eq!(infos[0].power_limit_w, Some(250.0));
+        assert_eq!(infos[0].processes.len(), 2);
+        assert_eq!(infos[0].processes[0].pid, 4242);
+        assert_eq!(infos[0].processes[0].user, "alice");
+        assert_eq!(infos[0].processes[0].process_name, "/usr/bin/python3");
+        assert_eq!(infos[0].processes[0].used_memory_mb, Some(4200));
 
+        assert_eq!(infos[1].index, 1);
         assert_eq!(infos[1].name, "Tesla K80");
         assert_eq!(infos[1].memory_total_mb, Some(11441));
         assert_eq!(infos[1].memory_used_mb, Some(0));
         assert_eq!(infos[1].temperature_c, Some(35.0));
+        assert_eq!(infos[1].power_draw_w, None);
+        assert_eq!(infos[1].power_limit_w, None);
+        assert!(infos[1].processes.is_empty());
 
         Ok(())
     }
@@ -127,15 +489,109 @@ Graphics/Displays:
         let result = parse_gpu(macos_input)?;
 
         assert!(result.is_some());
-        let job_result = result.unwrap();
-        let infos: &Vec<GpuInfo> = job_result.value.downcast_ref::<Vec<GpuInfo>>().unwrap();
+        let infos = result.unwrap();
         assert_eq!(infos.len(), 1);
 
         assert_eq!(infos[0].name, "Apple M3 Pro");
+        assert_eq!(infos[0].vendor, GpuVendor::Apple);
         assert_eq!(infos[0].memory_total_mb, None);
         assert_eq!(infos[0].memory_used_mb, None);
         assert_eq!(infos[0].temperature_c, None);
 
         Ok(())
     }
+
+    #[test]
+    fn test_parse_gpu_rocm() -> Result<()> {
+        let input = r#"__ROCM__
+{"card0": {"Temperature (Sensor edge) (C)": "41.0", "Temperature (Sensor junction) (C)": "44.0", "Average Graphics Package Power (W)": "92.0", "Max Graphics Package Power (W)": "560.0", "GPU use (%)": "37", "GPU memory use (%)": "12", "VRAM Total Memory (B)": "68702699520", "VRAM Total Used Memory (B)": "8589934592", "Card series": "AMD Instinct MI210", "Card model": "0x740f", "Card vendor": "Advanced Micro Devices, Inc. [AMD/ATI]", "Unique ID": "0x3f1b6a2e19cbd7a4"}, "card1": {"Temperature (Sensor edge) (C)": "N/A", "Current Socket Graphics Package Power (W)": "N/A", "GPU use (%)": "0", "GPU memory use (%)": "0", "VRAM Total Memory (B)": "68702699520", "VRAM Total Used Memory (B)": "0", "Card series": "AMD Instinct MI210", "Unique ID": "0x9a0d5c3e74f1b286"}}"#;
+
+        let result = parse_gpu(input)?;
+
+        let infos = result.unwrap();
+        assert_eq!(infos.len(), 2);
+
+        assert_eq!(infos[0].index, 0);
+        assert_eq!(infos[0].vendor, GpuVendor::Amd);
+        assert_eq!(infos[0].name, "AMD Instinct MI210");
+        assert_eq!(infos[0].uuid.as_deref(), Some("0x3f1b6a2e19cbd7a4"));
+        assert_eq!(infos[0].memory_total_mb, Some(65520));
+        assert_eq!(infos[0].memory_used_mb, Some(8192));
+        assert_eq!(infos[0].temperature_c, Some(41.0));
+        assert_eq!(infos[0].utilization_gpu_percent, Some(37.0));
+        assert_eq!(infos[0].utilization_memory_percent, Some(12.0));
+        assert_eq!(infos[0].power_draw_w, Some(92.0));
+        assert_eq!(infos[0].power_limit_w, Some(560.0));
+        assert_eq!(infos[0].key(), "0x3f1b6a2e19cbd7a4");
+
+        assert_eq!(infos[1].index, 1);
+        assert_eq!(infos[1].temperature_c, None);
+        assert_eq!(infos[1].power_draw_w, None);
+        assert_eq!(infos[1].memory_used_mb, Some(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gpu_mig_and_reordered_cards() -> Result<()> {
+        let input = r#"1, GPU-bbbbbbbb-0000-0000-0000-000000000002, 00000000:41:00.0, NVIDIA A100-SXM4-40GB, 40960, 1024, 33, 5, 1, 61.00, 400.00
+__GPU_APPS__
+__GPU_MIG__
+GPU 1: NVIDIA A100-SXM4-40GB (UUID: GPU-bbbbbbbb-0000-0000-0000-000000000002)
+  MIG 3g.20gb     Device  0: (UUID: MIG-11111111-2222-3333-4444-555555555555)
+  MIG 1g.5gb      Device  1: (UUID: MIG-66666666-7777-8888-9999-000000000000)"#;
+
+        let result = parse_gpu(input)?;
+
+        let infos = result.unwrap();
+        assert_eq!(infos.len(), 3);
+
+        // Card 0 disappeared, but the remaining card keeps its identity.
+        assert_eq!(infos[0].index, 1);
+        assert_eq!(infos[0].key(), "GPU-bbbbbbbb-0000-0000-0000-000000000002");
+        assert_eq!(infos[0].parent_uuid, None);
+
+        assert_eq!(infos[1].name, "MIG 3g.20gb");
+        assert_eq!(infos[1].index, 1);
+        assert_eq!(infos[1].mig_device_index, Some(0));
+        assert_eq!(
+            infos[1].parent_uuid.as_deref(),
+            Some("GPU-bbbbbbbb-0000-0000-0000-000000000002")
+        );
+        assert_eq!(infos[1].key(), "MIG-11111111-2222-3333-4444-555555555555");
+
+        assert_eq!(infos[2].name, "MIG 1g.5gb");
+        assert_eq!(infos[2].mig_device_index, Some(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_gpu_vendor_from_label() {
+        assert_eq!(GpuVendor::from_label("Intel Corporation"), GpuVendor::Intel);
+        assert_eq!(
+            GpuVendor::from_label("Intel Iris Plus Graphics"),
+            GpuVendor::Intel
+        );
+        assert_eq!(
+            GpuVendor::from_label("Advanced Micro Devices, Inc. [AMD/ATI]"),
+            GpuVendor::Amd
+        );
+        assert_eq!(
+            GpuVendor::from_label("AMD Radeon Pro 5500M"),
+            GpuVendor::Amd
+        );
+        assert_eq!(GpuVendor::from_label("Apple M2"), GpuVendor::Apple);
+        assert_eq!(GpuVendor::from_label("Matrox"), GpuVendor::Unknown);
+    }
+
+    #[test]
+    fn test_parse_gpu_without_tools() -> Result<()> {
+        let result = parse_gpu("")?;
+
+        let infos = result.unwrap();
+        assert!(infos.is_empty());
+
+        Ok(())
+    }
 }
@@ -1,158 +1,104 @@
-use super::cpu::CPU_COMMAND;
-use super::disk::DISK_COMMAND;
-use super::gpu::GPU_COMMAND;
-use super::mem::MEM_COMMAND;
+use super::collector::DynCollector;
 use crate::ssh_config::SshHostInfo;
-use anyhow::Result;
-use rusqlite::Connection;
-use std::any::Any;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::Mutex;
 
+/// The collectors of one host. Each keeps its own interval; the executor
+/// runs whichever are due together.
+#[derive(Clone, Debug)]
+pub struct JobGroup {
+    pub name: String,
+    pub host: SshHostInfo,
+    pub jobs: Vec<Arc<dyn DynCollector>>,
+}
+
+/// One job's run: frame metadata plus either a sample or why there is none.
 #[derive(Debug)]
-pub struct JobResult {
+pub struct JobOutcome {
     pub job_name: String,
-    pub value: Box<dyn Any + Send + Sync>,
+    pub exit_code: Option<i32>,
+    pub duration: Option<Duration>,
+    pub stderr: String,
+    /// The stored sample as JSON.
+    pub result: Result<Option<serde_json::Value>, JobError>,
 }
 
-#[derive(Clone, Debug)]
-pub enum JobKind {
-    Cpu,
-    Mem,
-    Disk,
-    Gpu,
+/// Why a job produced no sample, derived from its exit code and stderr.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobError {
+    /// The command or one of its tools is missing (`sh: nvidia-smi: not found`).
+    CommandNotFound(String),
+    PermissionDenied(String),
+    /// Non-zero exit without a more specific cause.
+    Failed {
+        exit_code: i32,
+        stderr: String,
+    },
+    /// The output frame is missing or was cut off.
+    NoOutput,
+    Parse(String),
 }
 
-impl JobKind {
-    pub fn name(&self) -> &'static str {
-        match self {
-            JobKind::Cpu => "cpu",
-            JobKind::Mem => "mem",
-            JobKind::Disk => "disk",
-            JobKind::Gpu => "gpu",
-        }
-    }
-
-    pub fn tag(&self) -> &'static str {
-        self.name()
-    }
-
-    pub fn command(&self) -> String {
-        match self {
-            JobKind::Cpu => CPU_COMMAND.to_string(),
-            JobKind::Mem => MEM_COMMAND.to_string(),
-            JobKind::Disk => DISK_COMMAND.to_string(),
-            JobKind::Gpu => GPU_COMMAND.to_string(),
-        }
-    }
-
-    pub fn parse(&self, output: &str) -> Result<Option<JobResult>> {
-        match self {
-            JobKind::Cpu => crate::backend::jobs::cpu::parse_cpu(output),
-            JobKind::Mem => crate::backend::jobs::mem::parse_mem(output),
-            JobKind::Disk => crate::backend::jobs::disk::parse_disk(output),
-            JobKind::Gpu => crate::backend::jobs::gpu::parse_gpu(output),
+impl JobError {
+    /// Classifies a failed run. Exit codes 126/127 are what POSIX shells use
+    /// for "not executable" and "not found"; stderr covers tools that fail
+    /// inside a pipeline.
+    pub fn from_exit(exit_code: i32, stderr: &str) -> Self {
+        let line = stderr.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+        if exit_code == 127 || line.contains("not found") {
+            let command = line
+                .split(':')
+                .map(str::trim)
+                .rev()
+                .find(|part| !part.is_empty() && !part.contains("not found"))
+                .unwrap_or(line);
+            Self::CommandNotFound(command.to_string())
+        } else if exit_code == 126 || line.contains("Permission denied") {
+            Self::PermissionDenied(line.to_string())
+        } else {
+            Self::Failed {
+                exit_code,
+                stderr: line.to_string(),
+            }
         }
     }
+}
 
-    pub async fn save(
-        &self,
-        conn: &Arc<Mutex<Connection>>,
-        host_id: &str,
-        result: &JobResult,
-    ) -> Result<()> {
+impl std::fmt::Display for JobError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            JobKind::Cpu => {
-                use crate::backend::db::cpu::commands::{CpuResultInsert, store_cpu_result};
-                use crate::backend::jobs::cpu::CpuInfo;
-
-                let cpu_info = result
-                    .value
-                    .downcast_ref::<CpuInfo>()
-                    .ok_or_else(|| anyhow::anyhow!("Expected CpuInfo for JobKind::Cpu"))?;
-
-                let insert = CpuResultInsert {
-                    host_id: host_id.to_string(),
-                    model_name: cpu_info.model_name.clone(),
-                    core_count: cpu_info.core_count as u32,
-                    usage_percent: cpu_info.usage_percent,
-                    per_core: cpu_info.per_core.clone(),
-                };
-
-                store_cpu_result(conn, &insert).await
-            }
-            JobKind::Mem => {
-                use crate::backend::db::mem::commands::{MemResultInsert, store_mem_result};
-                use crate::backend::jobs::mem::MemInfo;
-                let mem_info = result
-                    .value
-                    .downcast_ref::<MemInfo>()
-                    .ok_or_else(|| anyhow::anyhow!("Expected MemInfo for JobKind::Mem"))?;
-                let insert = MemResultInsert {
-                    host_id: host_id.to_string(),
-                    total_mb: mem_info.total_mb,
-                    used_mb: mem_info.used_mb,
-                    free_mb: mem_info.free_mb,
-                    used_percent: mem_info.used_percent,
-                };
-                store_mem_result(conn, &insert).await
-            }
-            JobKind::Disk => {
-                use crate::backend::db::disk::commands::{DiskResultInsert, store_disk_result};
-                use crate::backend::jobs::disk::DiskInfo;
-
-                let disk_infos = result
-                    .value
-                    .downcast_ref::<Vec<DiskInfo>>()
-                    .ok_or_else(|| anyhow::anyhow!("Expected Vec<DiskInfo> for JobKind::Disk"))?;
-
-                for info in disk_infos {
-                    let insert = DiskResultInsert {
-                        host_id: host_id.to_string(),
-                        mount_point: info.mount_point.clone(),
-                        total_mb: info.total_mb,
-                        used_mb: info.used_mb,
-                        available_mb: info.available_mb,
-                        used_percent: info.used_percent,
-                    };
-                    store_disk_result(conn, &insert).await?;
-                }
-
-                Ok(())
-            }
-            JobKind::Gpu => {
-                use crate::backend::db::gpu::commands::{GpuResultInsert, store_gpu_result};
-                use crate::backend::jobs::gpu::GpuInfo;
-
-                let gpu_infos = result
-                    .value
-                    .downcast_ref::<Vec<GpuInfo>>()
-                    .ok_or_else(|| anyhow::anyhow!("Expected Vec<GpuInfo> for JobKind::Gpu"))?;
-
-                for info in gpu_infos {
-                    let insert = GpuResultInsert {
-                        host_id: host_id.to_string(),
-                        gpu_index: info.index,
-                        name: info.name.clone(),
-                        memory_total_mb: info.memory_total_mb,
-                        memory_used_mb: info.memory_used_mb,
-                        temperature_c: info.temperature_c,
-                        raw_output: info.raw_output.clone(),
-                    };
-                    store_gpu_result(conn, &insert).await?;
-                }
-
-                Ok(())
+            Self::CommandNotFound(command) => write!(f, "{command} not found"),
+            Self::PermissionDenied(detail) => write!(f, "permission denied: {detail}"),
+            Self::Failed { exit_code, stderr } if stderr.is_empty() => {
+                write!(f, "exited with {exit_code}")
             }
+            Self::Failed { exit_code, stderr } => write!(f, "exited with {exit_code}: {stderr}"),
+            Self::NoOutput => write!(f, "no output"),
+            Self::Parse(message) => write!(f, "parse error: {message}"),
         }
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct JobGroup {
-    pub name: String,
-    pub interval: Duration,
-    pub host: SshHostInfo,
-    pub jobs: Vec<JobKind>,
+impl std::error::Error for JobError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_error_from_exit() {
+        assert_eq!(
+            JobError::from_exit(127, "sh: 1: nvidia-smi: not found"),
+            JobError::CommandNotFound("nvidia-smi".into())
+        );
+        assert_eq!(
+            JobError::from_exit(1, "bash: line 1: lscpu: command not found\n"),
+            JobError::CommandNotFound("lscpu".into())
+        );
+        assert_eq!(
+            JobError::from_exit(1, "cat: /proc/kcore: Permission denied").to_string(),
+            "permission denied: cat: /proc/kcore: Permission denied"
+        );
+        assert_eq!(JobError::from_exit(2, "").to_string(), "exited with 2");
+    }
 }
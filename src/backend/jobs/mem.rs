@@ -1,18 +1,218 @@
-use super::job::JobResult;
+use super::collector::Collector;
+use crate::backend::db::mem::commands::{MemResultInsert, store_mem_result};
+use crate::backend::db::mem::queries::{MemResultRow, fetch_latest_mem_all};
 use anyhow::{Result, anyhow};
+use rusqlite::Connection;
 use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
 
-pub const MEM_COMMAND: &str = r#"bash -c 'uname -s && echo __MEM__ && (free -m || (echo __MAC__ && sysctl -n hw.memsize && vm_stat))'"#;
+pub const MEM_COMMAND: &str = r#"sh -c 'uname -s; echo __MEM__; if [ "$(uname -s)" = Darwin ]; then echo __MAC__; sysctl -n hw.memsize; vm_stat; echo __SWAP__; sysctl -n vm.swapusage; elif ! free -m 2>/dev/null; then echo __MEMINFO__; cat /proc/meminfo; fi'"#;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone, Default)]
 pub struct MemInfo {
     pub total_mb: u64,
     pub used_mb: u64,
     pub free_mb: u64,
+    pub available_mb: u64,
+    pub buffers_cache_mb: u64,
+    pub shared_mb: u64,
+    pub swap_total_mb: u64,
+    pub swap_used_mb: u64,
     pub used_percent: f32,
 }
 
-pub fn parse_mem(output: &str) -> Result<Option<JobResult>> {
+fn percent_of(used: u64, total: u64) -> f32 {
+    if total > 0 {
+        (used as f32 / total as f32) * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// Maps the `free -m` header onto the `Mem:` and `Swap:` rows. Older procps
+/// releases print `buffers` and `cached` instead of `buff/cache`, count them
+/// in `used`, and have no `available` column.
+fn parse_free(output: &str) -> Option<MemInfo> {
+    let header: Vec<&str> = output
+        .lines()
+        .find(|l| l.contains("total") && l.contains("used"))?
+        .split_whitespace()
+        .collect();
+
+    let row = |prefix: &str| -> HashMap<&str, u64> {
+        output
+            .lines()
+            .find(|l| l.trim_start().starts_with(prefix))
+            .map(|l| {
+                header
+                    .iter()
+                    .copied()
+                    .zip(l.split_whitespace().skip(1))
+                    .filter_map(|(name, value)| value.parse().ok().map(|v| (name, v)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mem = row("Mem:");
+    let swap = row("Swap:");
+
+    let total = *mem.get("total")?;
+    let mut used = mem.get("used").copied().unwrap_or(0);
+    let free = mem.get("free").copied().unwrap_or(0);
+    let buffers_cache = match mem.get("buff/cache") {
+        Some(&buffers_cache) => buffers_cache,
+        None => {
+            let legacy =
+                mem.get("buffers").copied().unwrap_or(0) + mem.get("cached").copied().unwrap_or(0);
+            used = used.saturating_sub(legacy);
+            legacy
+        }
+    };
+    let available = mem
+        .get("available")
+        .copied()
+        .unwrap_or(free + buffers_cache);
+
+    Some(MemInfo {
+        total_mb: total,
+        used_mb: used,
+        free_mb: free,
+        available_mb: available,
+        buffers_cache_mb: buffers_cache,
+        shared_mb: mem.get("shared").copied().unwrap_or(0),
+        swap_total_mb: swap.get("total").copied().unwrap_or(0),
+        swap_used_mb: swap.get("used").copied().unwrap_or(0),
+        used_percent: percent_of(used, total),
+    })
+}
+
+/// Parses `/proc/meminfo` (kB values), used when `free` is missing or too old
+/// for `-m`. `used` follows procps: total minus free, buffers and cache.
+pub fn parse_meminfo(output: &str) -> Option<MemInfo> {
+    let values: HashMap<&str, u64> = output
+        .lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let kb = rest.split_whitespace().next()?.parse().ok()?;
+            Some((key.trim(), kb))
+        })
+        .collect();
+    let mb = |key: &str| values.get(key).copied().unwrap_or(0) / 1024;
+
+    let total = values.get("MemTotal")? / 1024;
+    let free = mb("MemFree");
+    let buffers_cache = mb("Buffers") + mb("Cached") + mb("SReclaimable");
+    let used = total.saturating_sub(free + buffers_cache);
+    let swap_total = mb("SwapTotal");
+
+    Some(MemInfo {
+        total_mb: total,
+        used_mb: used,
+        free_mb: free,
+        available_mb: values
+            .get("MemAvailable")
+            .map(|kb| kb / 1024)
+            .unwrap_or(free + buffers_cache),
+        buffers_cache_mb: buffers_cache,
+        shared_mb: mb("Shmem"),
+        swap_total_mb: swap_total,
+        swap_used_mb: swap_total.saturating_sub(mb("SwapFree")),
+        used_percent: percent_of(used, total),
+    })
+}
+
+/// Parses `sysctl -n vm.swapusage`, e.g.
+/// `total = 2048.00M  used = 1024.50M  free = 1023.50M  (encrypted)`.
+fn parse_swapusage(output: &str) -> (u64, u64) {
+    let value = |key: &str| -> u64 {
+        output
+            .split_once(&format!("{} =", key))
+            .and_then(|(_, rest)| rest.split_whitespace().next())
+            .map(|v| {
+                let (number, factor) = match v.chars().last() {
+                    Some('G') => (&v[..v.len() - 1], 1024.0),
+                    Some('M') => (&v[..v.len() - 1], 1.0),
+                    Some('K') => (&v[..v.len() - 1], 1.0 / 1024.0),
+                    _ => (v, 1.0),
+                };
+                (number.parse::<f64>().unwrap_or(0.0) * factor) as u64
+            })
+            .unwrap_or(0)
+    };
+    (value("total"), value("used"))
+}
+
+fn parse_darwin(output: &str) -> Result<MemInfo> {
+    let (vm_output, swap_output) = output.split_once("__SWAP__").unwrap_or((output, ""));
+    let mut lines = vm_output.lines();
+
+    let total_bytes = lines
+        .find(|line| {
+            let line = line.trim();
+            !line.is_empty() && line.chars().all(|c| c.is_ascii_digit())
+        })
+        .ok_or_else(|| anyhow!("missing hw.memsize value"))?
+        .trim()
+        .parse::<u64>()
+        .unwrap_or(0);
+
+    let mut page_size = 4096u64;
+    let mut counters: HashMap<&str, u64> = HashMap::new();
+
+    for line in lines {
+        if line.contains("page size of") {
+            if let Some(v) = line
+                .split("page size of")
+                .nth(1)
+                .and_then(|s| s.split_whitespace().next())
+            {
+                page_size = v.parse().unwrap_or(4096);
+            }
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once(':') {
+            let val = value.trim().trim_end_matches('.').replace('.', "");
+            if let Ok(count) = val.parse::<u64>() {
+                counters.insert(key.trim(), count);
+            }
+        }
+    }
+
+    let pages_to_mb = |keys: &[&str]| -> u64 {
+        let pages: u64 = keys.iter().filter_map(|k| counters.get(k)).sum();
+        (pages * page_size) / 1024 / 1024
+    };
+
+    let used_mb = pages_to_mb(&[
+        "Pages active",
+        "Pages speculative",
+        "Pages occupied by compressor",
+        "Pages wired down",
+        "Pages inactive",
+    ]);
+    let total_mb = total_bytes / 1024 / 1024;
+    let free_mb = total_mb.saturating_sub(used_mb);
+    let (swap_total_mb, swap_used_mb) = parse_swapusage(swap_output);
+
+    Ok(MemInfo {
+        total_mb,
+        used_mb,
+        free_mb,
+        available_mb: pages_to_mb(&["Pages free", "Pages inactive", "Pages speculative"]),
+        buffers_cache_mb: pages_to_mb(&["File-backed pages"]),
+        shared_mb: 0,
+        swap_total_mb,
+        swap_used_mb,
+        used_percent: percent_of(used_mb, total_mb),
+    })
+}
+
+pub fn parse_mem(output: &str) -> Result<Option<MemInfo>> {
     let mut sections = output.split("__MEM__");
     let platform = sections
         .next()
@@ -24,97 +224,162 @@ pub fn parse_mem(output: &str) -> Result<Option<JobResult>> {
         .trim();
 
     let info_opt = match platform {
-        "Linux" => {
-            if let Some(line) = mem_output.lines().find(|l| l.contains("Mem:")) {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 3 {
-                    let total = parts[1].parse().unwrap_or(0);
-                    let used = parts[2].parse().unwrap_or(0);
-                    let free = parts.get(3).and_then(|v| v.parse().ok()).unwrap_or(0);
-                    let percent = if total > 0 {
-                        (used as f32 / total as f32) * 100.0
-                    } else {
-                        0.0
-                    };
-                    Some(MemInfo {
-                        total_mb: total,
-                        used_mb: used,
-                        free_mb: free,
-                        used_percent: percent,
-                    })
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        }
+        "Linux" => match mem_output.split_once("__MEMINFO__") {
+            Some((_, meminfo)) => parse_meminfo(meminfo),
+            None => parse_free(mem_output),
+        },
+        "Darwin" => Some(parse_darwin(mem_output)?),
+        _ => None,
+    };
 
-        "Darwin" => {
-            let mut lines = mem_output.lines();
-
-            let total_bytes = lines
-                .find(|line| line.trim().chars().all(|c| c.is_ascii_digit()))
-                .ok_or_else(|| anyhow!("missing hw.memsize value"))?
-                .trim()
-                .parse::<u64>()
-                .unwrap_or(0);
-
-            let mut page_size = 4096u64;
-
-            let mut counters = std::collections::HashMap::from([
-                ("Pages active", 0u64),
-                ("Pages speculative", 0u64),
-                ("Pages occupied by compressor", 0u64),
-                ("Pages wired down", 0u64),
-                ("Pages inactive", 0u64),
-            ]);
-
-            for line in lines {
-                if line.contains("page size of") {
-                    if let Some(v) = line
-                        .split("page size of")
-                        .nth(1)
-                        .and_then(|s| s.split_whitespace().next())
-                    {
-                        page_size = v.parse().unwrap_or(4096);
-                    }
-                    continue;
-                }
-
-                if let Some((key, value)) = line.split_once(':') {
-                    let val = value.trim().trim_end_matches('.').replace('.', "");
-                    if let Ok(count) = val.parse::<u64>() {
-                        if let Some(entry) = counters.get_mut(key.trim()) {
-                            *entry = count;
-                        }
-                    }
-                }
-            }
+    Ok(info_opt)
+}
 
-            let used_pages: u64 = counters.values().sum();
-            let used_mb = (used_pages * page_size) / 1024 / 1024;
-            let total_mb = total_bytes / 1024 / 1024;
-            let free_mb = total_mb.saturating_sub(used_mb);
-            let percent = if total_mb > 0 {
-                (used_mb as f32 / total_mb as f32) * 100.0
-            } else {
-                0.0
-            };
-
-            Some(MemInfo {
-                total_mb,
-                used_mb,
-                free_mb,
-                used_percent: percent,
-            })
-        }
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MemCollector;
 
-        _ => None,
-    };
+#[async_trait::async_trait]
+impl Collector for MemCollector {
+    type Sample = MemInfo;
+
+    fn name(&self) -> &str {
+        "mem"
+    }
+
+    fn command(&self) -> String {
+        MEM_COMMAND.to_string()
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn parse(&self, output: &str) -> Result<Option<MemInfo>> {
+        parse_mem(output)
+    }
+
+    async fn store(
+        &self,
+        conn: &Arc<Mutex<Connection>>,
+        host_id: &str,
+        mem_info: &MemInfo,
+    ) -> Result<()> {
+        let insert = MemResultInsert {
+            host_id: host_id.to_string(),
+            total_mb: mem_info.total_mb,
+            used_mb: mem_info.used_mb,
+            free_mb: mem_info.free_mb,
+            available_mb: mem_info.available_mb,
+            buffers_cache_mb: mem_info.buffers_cache_mb,
+            shared_mb: mem_info.shared_mb,
+            swap_total_mb: mem_info.swap_total_mb,
+            swap_used_mb: mem_info.swap_used_mb,
+            used_percent: mem_info.used_percent,
+        };
+        store_mem_result(conn, &insert).await
+    }
+}
+
+impl MemCollector {
+    pub async fn query(&self, conn: &Arc<Mutex<Connection>>) -> Result<Vec<MemResultRow>> {
+        fetch_latest_mem_all(conn).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> MemInfo {
+        parse_mem(input).unwrap().expect("mem result")
+    }
+
+    #[test]
+    fn test_parse_mem_linux() {
+        let info = parse(
+            r#"Linux
+__MEM__
+               total        used        free      shared  buff/cache   available
+Mem:           31996        9125        1482         559       21387       21823
+Swap:           2047        1210         837
+"#,
+        );
+
+        assert_eq!(info.total_mb, 31996);
+        assert_eq!(info.used_mb, 9125);
+        assert_eq!(info.free_mb, 1482);
+        assert_eq!(info.shared_mb, 559);
+        assert_eq!(info.buffers_cache_mb, 21387);
+        assert_eq!(info.available_mb, 21823);
+        assert_eq!(info.swap_total_mb, 2047);
+        assert_eq!(info.swap_used_mb, 1210);
+    }
+
+    #[test]
+    fn test_parse_mem_linux_legacy_free() {
+        let info = parse(
+            r#"Linux
+__MEM__
+             total       used       free     shared    buffers     cached
+Mem:          7983       7652        331          0        245       5263
+-/+ buffers/cache:       2143       5840
+Swap:         4095          0       4095
+"#,
+        );
+
+        assert_eq!(info.total_mb, 7983);
+        assert_eq!(info.used_mb, 7652 - 5508);
+        assert!((info.used_percent - 26.86).abs() < 0.01);
+        assert_eq!(info.buffers_cache_mb, 5508);
+        assert_eq!(info.available_mb, 5839);
+        assert_eq!(info.swap_total_mb, 4095);
+        assert_eq!(info.swap_used_mb, 0);
+    }
+
+    #[test]
+    fn test_parse_mem_darwin() {
+        let info = parse(
+            r#"Darwin
+__MEM__
+__MAC__
+17179869184
+Mach Virtual Memory Statistics: (page size of 16384 bytes)
+Pages free:                                5000.
+Pages active:                            300000.
+Pages inactive:                          290000.
+Pages speculative:                         10000.
+Pages wired down:                        150000.
+Pages occupied by compressor:             50000.
+File-backed pages:                       200000.
+__SWAP__
+total = 2048.00M  used = 1024.50M  free = 1023.50M  (encrypted)
+"#,
+        );
+
+        assert_eq!(info.total_mb, 16384);
+        assert_eq!(info.used_mb, 12500);
+        assert_eq!(info.buffers_cache_mb, 3125);
+        assert_eq!(info.available_mb, 4765);
+        assert_eq!(info.swap_total_mb, 2048);
+        assert_eq!(info.swap_used_mb, 1024);
+    }
+
+    #[test]
+    fn test_parse_mem_busybox() {
+        let info = parse(include_str!("fixtures/busybox/free.txt"));
+        assert_eq!(info.total_mb, 3936);
+        assert_eq!(info.used_mb, 412);
+        assert_eq!(info.available_mb, 3284);
 
-    Ok(info_opt.map(|info| JobResult {
-        job_name: "mem".into(),
-        value: Box::new(info),
-    }))
+        // Older BusyBox `free` has no `-m`, so the command falls back to meminfo.
+        let info = parse(include_str!("fixtures/busybox/meminfo.txt"));
+        assert_eq!(info.total_mb, 3936);
+        assert_eq!(info.free_mb, 2871);
+        assert_eq!(info.buffers_cache_mb, 22 + 580 + 50);
+        assert_eq!(info.used_mb, 3936 - 2871 - 652);
+        assert_eq!(info.available_mb, 3284);
+        assert_eq!(info.shared_mb, 9);
+        assert_eq!(info.swap_total_mb, 1023);
+        assert_eq!(info.swap_used_mb, 1023 - 823);
+    }
 }
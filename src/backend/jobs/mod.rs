@@ -1,6 +1,20 @@
+pub mod adaptive;
+pub mod agent;
+pub mod capabilities;
+pub mod clock;
+pub mod collector;
 pub mod cpu;
+pub mod custom;
 pub mod disk;
 pub mod executor;
+pub mod facts;
+pub mod framing;
 pub mod gpu;
 pub mod job;
 pub mod mem;
+pub mod packages;
+pub mod ports;
+pub mod scheduler;
+pub mod session;
+pub mod stream;
+pub mod telemetry;
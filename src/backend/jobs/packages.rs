@@ -0,0 +1,158 @@
+use super::collector::Collector;
+use crate::backend::db::packages::commands::{PackageResultInsert, store_package_result};
+use crate::backend::db::packages::queries::{PackageResultRow, fetch_latest_packages_all};
+use anyhow::Result;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+pub const PACKAGES_COMMAND: &str = r#"sh -c '
+  reboot=0
+  [ -f /var/run/reboot-required ] && reboot=1
+  [ -f /var/run/reboot-needed ] && reboot=1
+  if command -v apt-get >/dev/null 2>&1; then
+    echo manager=apt
+    updates=$(apt-get -s -o Debug::NoLocking=1 upgrade 2>/dev/null | grep "^Inst")
+    echo "pending=$(printf "%s\n" "$updates" | grep -c "^Inst")"
+    echo "security=$(printf "%s\n" "$updates" | grep -ci "security")"
+  elif command -v dnf >/dev/null 2>&1 || command -v yum >/dev/null 2>&1; then
+    pm=$(command -v dnf >/dev/null 2>&1 && echo dnf || echo yum)
+    echo manager=$pm
+    echo "pending=$($pm -q -C check-update 2>/dev/null | awk "NF==3" | wc -l)"
+    echo "security=$($pm -q -C updateinfo list --security 2>/dev/null | awk "NF>=3" | wc -l)"
+    if command -v needs-restarting >/dev/null 2>&1; then
+      needs-restarting -r >/dev/null 2>&1 || reboot=1
+    fi
+  elif command -v zypper >/dev/null 2>&1; then
+    echo manager=zypper
+    echo "pending=$(zypper -q --non-interactive --no-refresh list-updates 2>/dev/null | grep -c "^v ")"
+    echo "security=$(zypper -q --non-interactive --no-refresh list-patches --category security 2>/dev/null | grep -c "| security")"
+  elif command -v apk >/dev/null 2>&1; then
+    echo manager=apk
+    echo "pending=$(apk list -u 2>/dev/null | wc -l)"
+  else
+    for brew in brew /opt/homebrew/bin/brew /usr/local/bin/brew; do
+      if command -v $brew >/dev/null 2>&1; then
+        echo manager=brew
+        echo "pending=$(HOMEBREW_NO_AUTO_UPDATE=1 $brew outdated --quiet 2>/dev/null | wc -l)"
+        break
+      fi
+    done
+  fi
+  echo reboot_required=$reboot
+'"#;
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct PackageInfo {
+    pub manager: Option<String>,
+    pub pending_count: u32,
+    pub security_count: Option<u32>,
+    pub reboot_required: bool,
+}
+
+/// Parses the `key=value` lines printed by `PACKAGES_COMMAND`. Package
+/// managers without security metadata (apk, brew) leave `security` out.
+pub fn parse_packages(output: &str) -> Result<Option<PackageInfo>> {
+    let values: HashMap<&str, &str> = output
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(k, v)| (k.trim(), v.trim()))
+        .collect();
+
+    if !values.contains_key("reboot_required") {
+        return Ok(None);
+    }
+
+    let info = PackageInfo {
+        manager: values.get("manager").map(|m| m.to_string()),
+        pending_count: values
+            .get("pending")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0),
+        security_count: values.get("security").and_then(|v| v.parse().ok()),
+        reboot_required: values.get("reboot_required") == Some(&"1"),
+    };
+
+    Ok(Some(info))
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PackagesCollector;
+
+#[async_trait::async_trait]
+impl Collector for PackagesCollector {
+    type Sample = PackageInfo;
+
+    fn name(&self) -> &str {
+        "packages"
+    }
+
+    fn command(&self) -> String {
+        PACKAGES_COMMAND.to_string()
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(24 * 60 * 60)
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn parse(&self, output: &str) -> Result<Option<PackageInfo>> {
+        parse_packages(output)
+    }
+
+    async fn store(
+        &self,
+        conn: &Arc<Mutex<Connection>>,
+        host_id: &str,
+        info: &PackageInfo,
+    ) -> Result<()> {
+        let insert = PackageResultInsert {
+            host_id: host_id.to_string(),
+            manager: info.manager.clone(),
+            pending_count: info.pending_count,
+            security_count: info.security_count,
+            reboot_required: info.reboot_required,
+        };
+        store_package_result(conn, &insert).await
+    }
+}
+
+impl PackagesCollector {
+    pub async fn query(&self, conn: &Arc<Mutex<Connection>>) -> Result<Vec<PackageResultRow>> {
+        fetch_latest_packages_all(conn).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Option<PackageInfo> {
+        parse_packages(input).unwrap()
+    }
+
+    #[test]
+    fn test_parse_packages() {
+        let apt = parse("manager=apt\npending=23\nsecurity=7\nreboot_required=1\n").unwrap();
+        assert_eq!(apt.manager.as_deref(), Some("apt"));
+        assert_eq!(apt.pending_count, 23);
+        assert_eq!(apt.security_count, Some(7));
+        assert!(apt.reboot_required);
+
+        let brew = parse("manager=brew\npending=       4\nreboot_required=0\n").unwrap();
+        assert_eq!(brew.pending_count, 4);
+        assert_eq!(brew.security_count, None);
+        assert!(!brew.reboot_required);
+
+        let unknown = parse("reboot_required=0\n").unwrap();
+        assert_eq!(unknown.manager, None);
+
+        assert!(parse("").is_none());
+    }
+}
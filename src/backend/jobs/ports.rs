@@ -0,0 +1,291 @@
+use super::collector::Collector;
+use crate::backend::db::ports::commands::{PortResultInsert, store_port_result};
+use crate::backend::db::ports::queries::{PortResultRow, fetch_latest_ports_all};
+use anyhow::Result;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+pub const PORTS_COMMAND: &str = r#"sh -c 'if [ "$(uname -s)" = Darwin ]; then echo __LSOF__; lsof -nP -iTCP -sTCP:LISTEN 2>/dev/null; elif command -v ss >/dev/null 2>&1; then echo __SS__; ss -tulpnH 2>/dev/null; elif command -v netstat >/dev/null 2>&1; then echo __NETSTAT__; netstat -tulpn 2>/dev/null; fi'"#;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ListeningPort {
+    pub protocol: String,
+    pub address: String,
+    pub port: u16,
+    pub process: Option<String>,
+    pub pid: Option<u32>,
+}
+
+impl ListeningPort {
+    pub fn key(&self) -> (String, String, u16) {
+        (self.protocol.clone(), self.address.clone(), self.port)
+    }
+}
+
+/// Splits `0.0.0.0:22`, `[::]:80`, `:::80` and `*:22` into address and port.
+fn split_address(local: &str) -> Option<(String, u16)> {
+    let (address, port) = local.rsplit_once(':')?;
+    let address = address.trim_start_matches('[').trim_end_matches(']');
+    let address = if address.is_empty() { "::" } else { address };
+    Some((address.to_string(), port.parse().ok()?))
+}
+
+fn normalize_protocol(protocol: &str) -> String {
+    protocol.trim_end_matches(['6', '4']).to_lowercase()
+}
+
+/// Parses `ss -tulpnH`, e.g.
+/// `tcp LISTEN 0 4096 0.0.0.0:22 0.0.0.0:* users:(("sshd",pid=1034,fd=3))`.
+fn parse_ss(output: &str) -> Vec<ListeningPort> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 5 {
+                return None;
+            }
+            let (address, port) = split_address(parts[4])?;
+            let users = parts.get(6).copied().unwrap_or("");
+            let process = users
+                .split_once("((\"")
+                .and_then(|(_, rest)| rest.split_once('"'))
+                .map(|(name, _)| name.to_string());
+            let pid = users
+                .split_once("pid=")
+                .and_then(|(_, rest)| rest.split([',', ')']).next())
+                .and_then(|pid| pid.parse().ok());
+            Some(ListeningPort {
+                protocol: normalize_protocol(parts[0]),
+                address,
+                port,
+                process,
+                pid,
+            })
+        })
+        .collect()
+}
+
+/// Parses `netstat -tulpn`. UDP sockets have an empty state column, so the
+/// `PID/Program name` column is whatever follows the state. BusyBox prints the
+/// truncated command line there (`12/nginx: master pr`), so only its first
+/// word is kept.
+fn parse_netstat(output: &str) -> Vec<ListeningPort> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 6 || !(parts[0].starts_with("tcp") || parts[0].starts_with("udp")) {
+                return None;
+            }
+            if parts[0].starts_with("tcp") && !parts.contains(&"LISTEN") {
+                return None;
+            }
+            let (address, port) = split_address(parts[3])?;
+            let program = parts[5..]
+                .iter()
+                .position(|p| *p != "LISTEN")
+                .map(|i| parts[5 + i]);
+            let (pid, process) = program
+                .and_then(|p| p.split_once('/'))
+                .map(|(pid, name)| {
+                    let name = name.trim_end_matches(':');
+                    (pid.parse().ok(), Some(name.to_string()))
+                })
+                .unwrap_or((None, None));
+            Some(ListeningPort {
+                protocol: normalize_protocol(parts[0]),
+                address,
+                port,
+                process,
+                pid,
+            })
+        })
+        .collect()
+}
+
+/// Parses `lsof -nP -iTCP -sTCP:LISTEN`, e.g.
+/// `sshd 123 root 4u IPv6 0x1234 0t0 TCP *:22 (LISTEN)`.
+fn parse_lsof(output: &str) -> Vec<ListeningPort> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let proto_index = parts.iter().position(|p| *p == "TCP" || *p == "UDP")?;
+            let (address, port) = split_address(parts.get(proto_index + 1)?)?;
+            Some(ListeningPort {
+                protocol: parts[proto_index].to_lowercase(),
+                address,
+                port,
+                process: parts.first().map(|p| p.to_string()),
+                pid: parts.get(1).and_then(|p| p.parse().ok()),
+            })
+        })
+        .collect()
+}
+
+pub fn parse_ports(output: &str) -> Result<Option<Vec<ListeningPort>>> {
+    let mut ports = if let Some((_, rest)) = output.split_once("__SS__") {
+        parse_ss(rest)
+    } else if let Some((_, rest)) = output.split_once("__NETSTAT__") {
+        parse_netstat(rest)
+    } else if let Some((_, rest)) = output.split_once("__LSOF__") {
+        parse_lsof(rest)
+    } else {
+        return Ok(None);
+    };
+
+    // Forked servers list the same socket once per worker process.
+    ports.sort();
+    ports.dedup_by(|a, b| a.key() == b.key());
+
+    Ok(Some(ports))
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PortsCollector;
+
+#[async_trait::async_trait]
+impl Collector for PortsCollector {
+    type Sample = Vec<ListeningPort>;
+
+    fn name(&self) -> &str {
+        "ports"
+    }
+
+    fn command(&self) -> String {
+        PORTS_COMMAND.to_string()
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn parse(&self, output: &str) -> Result<Option<Vec<ListeningPort>>> {
+        parse_ports(output)
+    }
+
+    async fn store(
+        &self,
+        conn: &Arc<Mutex<Connection>>,
+        host_id: &str,
+        listeners: &Vec<ListeningPort>,
+    ) -> Result<()> {
+        let insert = PortResultInsert {
+            host_id: host_id.to_string(),
+            listeners: listeners.clone(),
+        };
+        store_port_result(conn, &insert).await
+    }
+}
+
+impl PortsCollector {
+    pub async fn query(&self, conn: &Arc<Mutex<Connection>>) -> Result<Vec<PortResultRow>> {
+        fetch_latest_ports_all(conn).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Vec<ListeningPort> {
+        parse_ports(input).unwrap().expect("ports result")
+    }
+
+    #[test]
+    fn test_parse_ports_ss() {
+        let ports = parse(
+            r#"__SS__
+udp   UNCONN 0      0      127.0.0.53%lo:53        0.0.0.0:*    users:(("systemd-resolve",pid=612,fd=13))
+tcp   LISTEN 0      4096   0.0.0.0:22              0.0.0.0:*    users:(("sshd",pid=1034,fd=3))
+tcp   LISTEN 0      511    [::]:80                 [::]:*       users:(("nginx",pid=1200,fd=7),("nginx",pid=1199,fd=7))
+tcp   LISTEN 0      511    [::]:80                 [::]:*       users:(("nginx",pid=1201,fd=7))
+tcp   LISTEN 0      128    127.0.0.1:5432          0.0.0.0:*
+"#,
+        );
+
+        assert_eq!(ports.len(), 4);
+        let ssh = ports.iter().find(|p| p.port == 22).unwrap();
+        assert_eq!(ssh.protocol, "tcp");
+        assert_eq!(ssh.address, "0.0.0.0");
+        assert_eq!(ssh.process.as_deref(), Some("sshd"));
+        assert_eq!(ssh.pid, Some(1034));
+
+        let http = ports.iter().find(|p| p.port == 80).unwrap();
+        assert_eq!(http.address, "::");
+        assert_eq!(http.process.as_deref(), Some("nginx"));
+
+        let dns = ports.iter().find(|p| p.port == 53).unwrap();
+        assert_eq!(dns.protocol, "udp");
+        assert_eq!(dns.address, "127.0.0.53%lo");
+
+        let postgres = ports.iter().find(|p| p.port == 5432).unwrap();
+        assert_eq!(postgres.process, None);
+    }
+
+    #[test]
+    fn test_parse_ports_netstat() {
+        let ports = parse(
+            r#"__NETSTAT__
+Active Internet connections (only servers)
+Proto Recv-Q Send-Q Local Address           Foreign Address         State       PID/Program name
+tcp        0      0 0.0.0.0:22              0.0.0.0:*               LISTEN      1034/sshd
+tcp6       0      0 :::80                   :::*                    LISTEN      -
+udp        0      0 127.0.0.53:53           0.0.0.0:*                           612/systemd-resolve
+"#,
+        );
+
+        assert_eq!(ports.len(), 3);
+        let http = ports.iter().find(|p| p.port == 80).unwrap();
+        assert_eq!(http.protocol, "tcp");
+        assert_eq!(http.address, "::");
+        assert_eq!(http.process, None);
+
+        let dns = ports.iter().find(|p| p.port == 53).unwrap();
+        assert_eq!(dns.protocol, "udp");
+        assert_eq!(dns.pid, Some(612));
+        assert_eq!(dns.process.as_deref(), Some("systemd-resolve"));
+    }
+
+    #[test]
+    fn test_parse_ports_lsof() {
+        let ports = parse(
+            r#"__LSOF__
+COMMAND     PID  USER   FD   TYPE             DEVICE SIZE/OFF NODE NAME
+sshd        123  root    4u  IPv6 0x9b3c1e2f4a5b6c7d      0t0  TCP *:22 (LISTEN)
+ControlCe   456 akira   10u  IPv4 0x1a2b3c4d5e6f7a8b      0t0  TCP 127.0.0.1:7000 (LISTEN)
+ControlCe   456 akira   11u  IPv6 0x2a2b3c4d5e6f7a8b      0t0  TCP [::1]:7000 (LISTEN)
+"#,
+        );
+
+        assert_eq!(ports.len(), 3);
+        let ssh = ports.iter().find(|p| p.port == 22).unwrap();
+        assert_eq!(ssh.address, "*");
+        assert_eq!(ssh.pid, Some(123));
+        assert_eq!(ssh.process.as_deref(), Some("sshd"));
+        assert!(ports.iter().any(|p| p.address == "::1" && p.port == 7000));
+    }
+
+    #[test]
+    fn test_parse_ports_busybox_netstat() {
+        let ports = parse(include_str!("fixtures/busybox/netstat.txt"));
+
+        assert_eq!(ports.len(), 5);
+        let http = ports
+            .iter()
+            .find(|p| p.port == 80 && p.address == "::")
+            .unwrap();
+        assert_eq!(http.pid, Some(12));
+        assert_eq!(http.process.as_deref(), Some("nginx"));
+
+        let redis = ports.iter().find(|p| p.port == 6379).unwrap();
+        assert_eq!(redis.process, None);
+
+        let dhcp = ports.iter().find(|p| p.port == 68).unwrap();
+        assert_eq!(dhcp.protocol, "udp");
+        assert_eq!(dhcp.process.as_deref(), Some("udhcpc"));
+    }
+}
@@ -0,0 +1,280 @@
+use crate::backend::jobs::adaptive::{AdaptiveRate, PollRate};
+use crate::config::SchedulerConfig;
+use crate::ssh_config::SshHostInfo;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScheduleState {
+    #[default]
+    Idle,
+    /// Due, but waiting for a global or bastion slot.
+    Queued,
+    Running,
+    Paused,
+    /// Unregistered; the host is no longer polled.
+    Stopped,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HostSchedule {
+    pub state: ScheduleState,
+    pub bastion: Option<String>,
+    /// When the batch being queued or run fell due.
+    pub due_at: Option<Instant>,
+    /// How late the last batch started.
+    pub last_lag: Duration,
+    pub next_due: Option<Instant>,
+    /// Factor applied to collector intervals; `None` keeps them as defined.
+    pub interval_scale: Option<f64>,
+    pub rate: PollRate,
+    /// The shortest collector interval with scale and rate applied.
+    pub poll_interval: Option<Duration>,
+}
+
+impl HostSchedule {
+    /// How far behind schedule the host is right now, or was on its last run.
+    pub fn lag(&self, now: Instant) -> Duration {
+        match (self.state, self.due_at) {
+            (ScheduleState::Queued, Some(due_at)) => now.saturating_duration_since(due_at),
+            _ => self.last_lag,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SchedulerSnapshot {
+    pub max_concurrent: usize,
+    pub max_per_bastion: usize,
+    pub running: usize,
+    pub queued: usize,
+    pub hosts: HashMap<String, HostSchedule>,
+}
+
+/// Hands out SSH slots so a large fleet does not open every session at once:
+/// a global limit, a smaller one per bastion, and jittered intervals.
+#[derive(Debug)]
+pub struct Scheduler {
+    config: SchedulerConfig,
+    global: Arc<Semaphore>,
+    bastions: Mutex<HashMap<String, Arc<Semaphore>>>,
+    hosts: Arc<Mutex<HashMap<String, HostSchedule>>>,
+}
+
+/// Holds a host's slots; dropping it marks the host idle again.
+#[derive(Debug)]
+pub struct SchedulerPermit {
+    host_id: String,
+    hosts: Arc<Mutex<HashMap<String, HostSchedule>>>,
+    _bastion: Option<OwnedSemaphorePermit>,
+    _global: OwnedSemaphorePermit,
+}
+
+impl Drop for SchedulerPermit {
+    fn drop(&mut self) {
+        if let Some(host) = self.hosts.lock().unwrap().get_mut(&self.host_id)
+            && host.state == ScheduleState::Running
+        {
+            host.state = ScheduleState::Idle;
+            host.due_at = None;
+        }
+    }
+}
+
+/// A value in `[0, 1)`; scheduling only needs spread, not quality.
+fn random_fraction() -> f64 {
+    let hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+impl Scheduler {
+    pub fn new(config: SchedulerConfig) -> Self {
+        Self {
+            global: Arc::new(Semaphore::new(config.max_concurrent)),
+            config,
+            bastions: Mutex::new(HashMap::new()),
+            hosts: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// A fresh polling rate for one host.
+    pub fn adaptive_rate(&self) -> AdaptiveRate {
+        AdaptiveRate::new(&self.config)
+    }
+
+    /// Random delay before a host's first run, spread over `period`.
+    pub fn start_offset(&self, period: Duration) -> Duration {
+        period.mul_f64(random_fraction())
+    }
+
+    /// `interval` stretched or shrunk by up to the configured jitter.
+    pub fn jittered(&self, interval: Duration) -> Duration {
+        interval.mul_f64(1.0 + self.config.jitter * (2.0 * random_fraction() - 1.0))
+    }
+
+    /// Waits for a bastion slot (if the host has a bastion) and a global slot.
+    /// `due_at` is when the batch should have started, to measure the lag.
+    pub async fn acquire(&self, host: &SshHostInfo, due_at: Instant) -> SchedulerPermit {
+        self.update(&host.id, |schedule| {
+            schedule.state = ScheduleState::Queued;
+            schedule.bastion = host.proxy_jump.clone();
+            schedule.due_at = Some(due_at);
+        });
+
+        let bastion = match &host.proxy_jump {
+            Some(bastion) => {
+                let semaphore = self
+                    .bastions
+                    .lock()
+                    .unwrap()
+                    .entry(bastion.clone())
+                    .or_insert_with(|| Arc::new(Semaphore::new(self.config.max_per_bastion)))
+                    .clone();
+                Some(semaphore.acquire_owned().await.expect("semaphore closed"))
+            }
+            None => None,
+        };
+        let global = self
+            .global
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("semaphore closed");
+
+        self.update(&host.id, |schedule| {
+            schedule.state = ScheduleState::Running;
+            schedule.last_lag = Instant::now().saturating_duration_since(due_at);
+        });
+        SchedulerPermit {
+            host_id: host.id.clone(),
+            hosts: self.hosts.clone(),
+            _bastion: bastion,
+            _global: global,
+        }
+    }
+
+    pub fn set_next_due(&self, host_id: &str, next_due: Option<Instant>) {
+        self.update(host_id, |schedule| schedule.next_due = next_due);
+    }
+
+    pub fn set_paused(&self, host_id: &str, paused: bool) {
+        self.update(host_id, |schedule| {
+            schedule.state = if paused {
+                ScheduleState::Paused
+            } else {
+                ScheduleState::Idle
+            };
+        });
+    }
+
+    pub fn set_interval_scale(&self, host_id: &str, scale: f64) {
+        self.update(host_id, |schedule| {
+            schedule.interval_scale = (scale != 1.0).then_some(scale);
+        });
+    }
+
+    pub fn set_poll_rate(&self, host_id: &str, rate: PollRate, poll_interval: Duration) {
+        self.update(host_id, |schedule| {
+            schedule.rate = rate;
+            schedule.poll_interval = Some(poll_interval);
+        });
+    }
+
+    pub fn mark_stopped(&self, host_id: &str) {
+        self.update(host_id, |schedule| {
+            schedule.state = ScheduleState::Stopped;
+            schedule.next_due = None;
+        });
+    }
+
+    pub fn host(&self, host_id: &str) -> HostSchedule {
+        self.hosts
+            .lock()
+            .unwrap()
+            .get(host_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn snapshot(&self) -> SchedulerSnapshot {
+        let hosts = self.hosts.lock().unwrap().clone();
+        let count = |state| hosts.values().filter(|h| h.state == state).count();
+        SchedulerSnapshot {
+            max_concurrent: self.config.max_concurrent,
+            max_per_bastion: self.config.max_per_bastion,
+            running: count(ScheduleState::Running),
+            queued: count(ScheduleState::Queued),
+            hosts,
+        }
+    }
+
+    fn update(&self, host_id: &str, f: impl FnOnce(&mut HostSchedule)) {
+        f(self
+            .hosts
+            .lock()
+            .unwrap()
+            .entry(host_id.to_string())
+            .or_default());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(id: &str, bastion: Option<&str>) -> SshHostInfo {
+        SshHostInfo {
+            id: id.into(),
+            name: id.into(),
+            ip: "127.0.0.1".into(),
+            port: 22,
+            user: "root".into(),
+            identity_file: String::new(),
+            proxy_jump: bastion.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_limits_bastion_and_tracks_queue() {
+        let scheduler = Arc::new(Scheduler::new(SchedulerConfig {
+            max_concurrent: 3,
+            max_per_bastion: 1,
+            ..SchedulerConfig::default()
+        }));
+        let now = Instant::now();
+
+        let first = scheduler.acquire(&host("a", Some("jump")), now).await;
+        let _direct = scheduler.acquire(&host("c", None), now).await;
+
+        let waiting = {
+            let scheduler = scheduler.clone();
+            tokio::spawn(async move {
+                let _permit = scheduler.acquire(&host("b", Some("jump")), now).await;
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let snapshot = scheduler.snapshot();
+        assert_eq!(snapshot.running, 2);
+        assert_eq!(snapshot.queued, 1);
+        assert_eq!(snapshot.hosts["b"].state, ScheduleState::Queued);
+        assert!(snapshot.hosts["b"].lag(Instant::now()) >= Duration::from_millis(50));
+
+        drop(first);
+        waiting.await.unwrap();
+        let snapshot = scheduler.snapshot();
+        assert_eq!(snapshot.queued, 0);
+        assert_eq!(snapshot.hosts["a"].state, ScheduleState::Idle);
+        assert!(snapshot.hosts["b"].last_lag >= Duration::from_millis(50));
+
+        for _ in 0..100 {
+            let interval = scheduler.jittered(Duration::from_secs(10));
+            assert!(interval >= Duration::from_secs(9) && interval <= Duration::from_secs(11));
+            assert!(scheduler.start_offset(Duration::from_secs(10)) < Duration::from_secs(10));
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use super::collector::Collector;
+use crate::backend::db::session::commands::{SessionResultInsert, store_session_result};
+use crate::backend::db::session::queries::{SessionResultRow, fetch_latest_session_all};
+use anyhow::Result;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+pub const SESSION_COMMAND: &str = "echo __WHO__; who -u 2>/dev/null || who 2>/dev/null; echo __LAST__; last -10 2>/dev/null || last 2>/dev/null | head -n 10";
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SessionInfo {
+    pub user: String,
+    pub tty: String,
+    pub from: Option<String>,
+    pub login_time: String,
+    pub idle: String,
+    pub idle_seconds: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct LastLoginInfo {
+    pub user: String,
+    pub tty: String,
+    pub from: Option<String>,
+    pub when: String,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct SessionSample {
+    pub sessions: Vec<SessionInfo>,
+    pub last_logins: Vec<LastLoginInfo>,
+}
+
+impl SessionSample {
+    pub fn user_count(&self) -> usize {
+        let mut users: Vec<&str> = self.sessions.iter().map(|s| s.user.as_str()).collect();
+        users.sort();
+        users.dedup();
+        users.len()
+    }
+}
+
+fn is_clock(token: &str) -> bool {
+    token.split_once(':').is_some_and(|(h, m)| {
+        (1..=2).contains(&h.len())
+            && m.len() == 2
+            && h.chars().chain(m.chars()).all(|c| c.is_ascii_digit())
+    })
+}
+
+/// `who -u` prints the idle column as `.` (active in the last minute), `HH:MM`
+/// or `old` (more than a day).
+fn parse_idle(idle: &str) -> Option<u64> {
+    match idle {
+        "." => Some(0),
+        "old" => None,
+        _ => {
+            let (h, m) = idle.split_once(':')?;
+            Some(h.parse::<u64>().ok()? * 3600 + m.parse::<u64>().ok()? * 60)
+        }
+    }
+}
+
+/// Splits a trailing `(host)` off a `who` line. Local sessions have none, and
+/// X displays show up as `(:0)`.
+fn split_from(line: &str) -> (&str, Option<String>) {
+    match line
+        .trim_end()
+        .strip_suffix(')')
+        .and_then(|l| l.rsplit_once('('))
+    {
+        Some((rest, from)) if !from.is_empty() => (rest, Some(from.to_string())),
+        _ => (line, None),
+    }
+}
+
+/// Parses BusyBox `who`, which rejects `-u` and prints a header with the idle
+/// column before the time: `root pts/0 00:03 Oct 18 10:21:05 10.0.0.5`.
+fn parse_busybox_who(output: &str) -> Vec<SessionInfo> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 6 {
+                return None;
+            }
+            let idle = parts[2];
+            Some(SessionInfo {
+                user: parts[0].to_string(),
+                tty: parts[1].to_string(),
+                from: parts.get(6).map(|host| host.to_string()),
+                login_time: parts[3..6].join(" "),
+                idle: idle.to_string(),
+                idle_seconds: parse_idle(idle),
+            })
+        })
+        .collect()
+}
+
+/// Parses `who -u`. Linux prints `user pts/0 2025-01-05 09:12 00:03 4242 (host)`
+/// while macOS prints `user ttys000 Jan  5 09:12 00:03 (host)`; the login
+/// time always ends with the first `HH:MM` column and the idle column follows.
+fn parse_who(output: &str) -> Vec<SessionInfo> {
+    let header = output.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+    if header.starts_with("USER") && header.contains("IDLE") {
+        return parse_busybox_who(output.trim_start());
+    }
+    output
+        .lines()
+        .filter_map(|line| {
+            let (rest, from) = split_from(line);
+            let parts: Vec<&str> = rest.split_whitespace().collect();
+            if parts.len() < 4 {
+                return None;
+            }
+            let time_index = (2..parts.len()).find(|&i| is_clock(parts[i]))?;
+            let idle = parts.get(time_index + 1).copied().unwrap_or("?");
+            Some(SessionInfo {
+                user: parts[0].to_string(),
+                tty: parts[1].to_string(),
+                from,
+                login_time: parts[2..=time_index].join(" "),
+                idle: idle.to_string(),
+                idle_seconds: parse_idle(idle),
+            })
+        })
+        .collect()
+}
+
+/// Parses `last -10`, skipping reboot records and the trailing `wtmp begins`
+/// line. The remote host column is absent for local logins on macOS, and
+/// BusyBox drops the weekday so its login time starts at the month.
+fn parse_last(output: &str) -> Vec<LastLoginInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 4 || matches!(parts[0], "reboot" | "shutdown" | "wtmp") {
+                return None;
+            }
+            let day_index = (2..parts.len())
+                .find(|&i| WEEKDAYS.contains(&parts[i]))
+                .or_else(|| {
+                    (2..parts.len() - 1)
+                        .find(|&i| MONTHS.contains(&parts[i]) && parts[i + 1].parse::<u8>().is_ok())
+                })?;
+            Some(LastLoginInfo {
+                user: parts[0].to_string(),
+                tty: parts[1].to_string(),
+                from: (day_index > 2).then(|| parts[2..day_index].join(" ")),
+                when: parts[day_index..].join(" "),
+            })
+        })
+        .collect()
+}
+
+pub fn parse_sessions(output: &str) -> Result<Option<SessionSample>> {
+    let (_, rest) = output.split_once("__WHO__").unwrap_or(("", output));
+    let (who_output, last_output) = rest.split_once("__LAST__").unwrap_or((rest, ""));
+
+    Ok(Some(SessionSample {
+        sessions: parse_who(who_output),
+        last_logins: parse_last(last_output),
+    }))
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SessionCollector;
+
+#[async_trait::async_trait]
+impl Collector for SessionCollector {
+    type Sample = SessionSample;
+
+    fn name(&self) -> &str {
+        "session"
+    }
+
+    fn command(&self) -> String {
+        SESSION_COMMAND.to_string()
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn parse(&self, output: &str) -> Result<Option<SessionSample>> {
+        parse_sessions(output)
+    }
+
+    async fn store(
+        &self,
+        conn: &Arc<Mutex<Connection>>,
+        host_id: &str,
+        sample: &SessionSample,
+    ) -> Result<()> {
+        let insert = SessionResultInsert {
+            host_id: host_id.to_string(),
+            user_count: sample.user_count() as u32,
+            sessions: sample.sessions.clone(),
+            last_logins: sample.last_logins.clone(),
+        };
+        store_session_result(conn, &insert).await
+    }
+}
+
+impl SessionCollector {
+    pub async fn query(&self, conn: &Arc<Mutex<Connection>>) -> Result<Vec<SessionResultRow>> {
+        fetch_latest_session_all(conn).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> SessionSample {
+        parse_sessions(input).unwrap().expect("session result")
+    }
+
+    #[test]
+    fn test_parse_sessions_linux() {
+        let sample = parse(
+            r#"__WHO__
+alice    pts/0        2025-01-05 09:12 00:03        4242 (10.0.0.5)
+bob      pts/1        2025-01-05 10:40   .          5151 (vpn.example.com)
+alice    tty1         2025-01-01 08:00  old          900
+__LAST__
+alice    pts/0        10.0.0.5         Sun Jan  5 09:12   still logged in
+bob      pts/1        vpn.example.com  Sun Jan  5 10:40   still logged in
+reboot   system boot  6.8.0-45-generic Wed Jan  1 07:59   still running
+carol    pts/2        192.168.1.20     Sat Jan  4 18:02 - 19:30  (01:28)
+
+wtmp begins Wed Jan  1 07:59:12 2025
+"#,
+        );
+
+        assert_eq!(sample.sessions.len(), 3);
+        assert_eq!(sample.user_count(), 2);
+
+        let alice = &sample.sessions[0];
+        assert_eq!(alice.user, "alice");
+        assert_eq!(alice.tty, "pts/0");
+        assert_eq!(alice.from.as_deref(), Some("10.0.0.5"));
+        assert_eq!(alice.login_time, "2025-01-05 09:12");
+        assert_eq!(alice.idle_seconds, Some(180));
+
+        assert_eq!(sample.sessions[1].idle_seconds, Some(0));
+        assert_eq!(sample.sessions[2].from, None);
+        assert_eq!(sample.sessions[2].idle, "old");
+        assert_eq!(sample.sessions[2].idle_seconds, None);
+
+        assert_eq!(sample.last_logins.len(), 3);
+        assert_eq!(sample.last_logins[2].user, "carol");
+        assert_eq!(sample.last_logins[2].from.as_deref(), Some("192.168.1.20"));
+        assert_eq!(
+            sample.last_logins[2].when,
+            "Sat Jan 4 18:02 - 19:30 (01:28)"
+        );
+    }
+
+    #[test]
+    fn test_parse_sessions_macos() {
+        let sample = parse(
+            r#"__WHO__
+akira    console  Jan  3 08:45  old
+akira    ttys000  Jan  5 09:12 00:05 (192.168.1.7)
+__LAST__
+akira     ttys000  192.168.1.7    Sun Jan  5 09:12   still logged in
+akira     console                 Fri Jan  3 08:45   still logged in
+"#,
+        );
+
+        assert_eq!(sample.sessions.len(), 2);
+        assert_eq!(sample.user_count(), 1);
+        assert_eq!(sample.sessions[0].login_time, "Jan 3 08:45");
+        assert_eq!(sample.sessions[1].from.as_deref(), Some("192.168.1.7"));
+        assert_eq!(sample.sessions[1].idle_seconds, Some(300));
+
+        assert_eq!(sample.last_logins[1].from, None);
+        assert_eq!(sample.last_logins[1].tty, "console");
+    }
+
+    #[test]
+    fn test_parse_sessions_busybox() {
+        let sample = parse(include_str!("fixtures/busybox/session.txt"));
+
+        assert_eq!(sample.sessions.len(), 3);
+        assert_eq!(sample.user_count(), 2);
+        let root = &sample.sessions[0];
+        assert_eq!(root.tty, "pts/0");
+        assert_eq!(root.from.as_deref(), Some("10.0.0.5"));
+        assert_eq!(root.login_time, "Oct 18 10:21:05");
+        assert_eq!(root.idle_seconds, Some(180));
+        assert_eq!(sample.sessions[1].idle_seconds, Some(0));
+        assert_eq!(sample.sessions[2].from, None);
+
+        assert_eq!(sample.last_logins.len(), 3);
+        assert_eq!(
+            sample.last_logins[1].from.as_deref(),
+            Some("vpn.example.com")
+        );
+        assert_eq!(sample.last_logins[1].when, "Oct 18 11:02 still logged in");
+        assert_eq!(sample.last_logins[2].from, None);
+    }
+}
@@ -0,0 +1,321 @@
+use super::collector::Collector;
+use super::cpu::{CpuCollector, CpuInfo};
+use super::framing::new_nonce;
+use super::mem::{MemCollector, MemInfo, parse_meminfo};
+use crate::backend::db::net::commands::{NetResultInsert, store_net_result};
+use crate::backend::ssh::stream_remote;
+use crate::ssh_config::SshHostInfo;
+use anyhow::Result;
+use log::{info, warn};
+use rusqlite::Connection;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use tokio::sync::{Mutex, mpsc};
+
+/// Seconds between two streamed samples.
+pub const STREAM_INTERVAL_SECS: u64 = 1;
+
+/// A remote loop printing the raw counters every second, framed by nonce
+/// markers. Linux only: the counters come straight from `/proc`.
+pub fn sampler_command(nonce: &str) -> String {
+    format!(
+        r#"sh -c '
+  if [ ! -r /proc/stat ]; then echo "streaming needs /proc" >&2; exit 1; fi
+  while :; do
+    echo "@{nonce} uptime"; cat /proc/uptime
+    echo "@{nonce} stat"; grep "^cpu" /proc/stat
+    echo "@{nonce} meminfo"; cat /proc/meminfo
+    echo "@{nonce} netdev"; cat /proc/net/dev
+    echo "@{nonce} end"
+    sleep {STREAM_INTERVAL_SECS}
+  done
+'"#
+    )
+}
+
+/// Network throughput summed over every interface except loopback.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NetRate {
+    pub rx_bytes_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+}
+
+/// One streamed sample. CPU and network are rates, so the first frame of a
+/// stream only carries memory.
+#[derive(Debug, Clone, Default)]
+pub struct StreamSample {
+    pub cpu: Option<CpuInfo>,
+    pub mem: Option<MemInfo>,
+    pub net: Option<NetRate>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    Uptime,
+    Stat,
+    Meminfo,
+    Netdev,
+}
+
+#[derive(Debug, Default)]
+struct RawFrame {
+    uptime: String,
+    stat: String,
+    meminfo: String,
+    netdev: String,
+}
+
+/// Cumulative counters of one frame.
+#[derive(Debug, Clone)]
+struct Counters {
+    uptime: f64,
+    /// `(busy, total)` jiffies: the aggregate `cpu` line first, then per core.
+    cpu: Vec<(u64, u64)>,
+    rx_bytes: u64,
+    tx_bytes: u64,
+}
+
+/// Turns the sampler's output into samples as it arrives. Chunks may end
+/// mid-line; the rest is kept until the next chunk.
+#[derive(Debug)]
+pub struct StreamParser {
+    marker: String,
+    pending: String,
+    section: Option<Section>,
+    frame: RawFrame,
+    previous: Option<Counters>,
+}
+
+impl StreamParser {
+    pub fn new(nonce: &str) -> Self {
+        Self {
+            marker: format!("@{nonce} "),
+            pending: String::new(),
+            section: None,
+            frame: RawFrame::default(),
+            previous: None,
+        }
+    }
+
+    /// Consumes a chunk of output and returns the samples it completed.
+    pub fn feed(&mut self, chunk: &str) -> Vec<StreamSample> {
+        self.pending.push_str(chunk);
+        let mut samples = vec![];
+        while let Some(end) = self.pending.find('\n') {
+            let line: String = self.pending.drain(..=end).collect();
+            if let Some(sample) = self.line(line.trim_end()) {
+                samples.push(sample);
+            }
+        }
+        samples
+    }
+
+    fn line(&mut self, line: &str) -> Option<StreamSample> {
+        let Some(tag) = line.strip_prefix(&self.marker) else {
+            let target = match self.section? {
+                Section::Uptime => &mut self.frame.uptime,
+                Section::Stat => &mut self.frame.stat,
+                Section::Meminfo => &mut self.frame.meminfo,
+                Section::Netdev => &mut self.frame.netdev,
+            };
+            target.push_str(line);
+            target.push('\n');
+            return None;
+        };
+        self.section = match tag {
+            "uptime" => {
+                self.frame = RawFrame::default();
+                Some(Section::Uptime)
+            }
+            "stat" => Some(Section::Stat),
+            "meminfo" => Some(Section::Meminfo),
+            "netdev" => Some(Section::Netdev),
+            "end" => return self.finish_frame(),
+            _ => None,
+        };
+        None
+    }
+
+    fn finish_frame(&mut self) -> Option<StreamSample> {
+        self.section = None;
+        let frame = std::mem::take(&mut self.frame);
+        let counters = parse_counters(&frame)?;
+        let mut sample = StreamSample {
+            mem: parse_meminfo(&frame.meminfo),
+            ..StreamSample::default()
+        };
+        if let Some(previous) = self.previous.replace(counters.clone()) {
+            sample.cpu = cpu_between(&previous, &counters);
+            let elapsed = counters.uptime - previous.uptime;
+            if elapsed > 0.0 {
+                sample.net = Some(NetRate {
+                    rx_bytes_per_sec: counters.rx_bytes.saturating_sub(previous.rx_bytes) as f64
+                        / elapsed,
+                    tx_bytes_per_sec: counters.tx_bytes.saturating_sub(previous.tx_bytes) as f64
+                        / elapsed,
+                });
+            }
+        }
+        Some(sample)
+    }
+}
+
+fn parse_counters(frame: &RawFrame) -> Option<Counters> {
+    let uptime = frame.uptime.split_whitespace().next()?.parse().ok()?;
+
+    // user nice system idle iowait irq softirq steal; idle and iowait are
+    // the only fields that are not busy time.
+    let cpu = frame
+        .stat
+        .lines()
+        .filter_map(|line| {
+            let values: Vec<u64> = line
+                .split_whitespace()
+                .skip(1)
+                .take(8)
+                .filter_map(|v| v.parse().ok())
+                .collect();
+            let total: u64 = values.iter().sum();
+            let idle = values.get(3).copied()? + values.get(4).copied().unwrap_or(0);
+            Some((total.saturating_sub(idle), total))
+        })
+        .collect();
+
+    let (mut rx_bytes, mut tx_bytes) = (0, 0);
+    for line in frame.netdev.lines() {
+        let Some((interface, counters)) = line.split_once(':') else {
+            continue;
+        };
+        if interface.trim() == "lo" {
+            continue;
+        }
+        let counters: Vec<u64> = counters
+            .split_whitespace()
+            .filter_map(|v| v.parse().ok())
+            .collect();
+        if counters.len() >= 9 {
+            rx_bytes += counters[0];
+            tx_bytes += counters[8];
+        }
+    }
+
+    Some(Counters {
+        uptime,
+        cpu,
+        rx_bytes,
+        tx_bytes,
+    })
+}
+
+fn cpu_between(previous: &Counters, current: &Counters) -> Option<CpuInfo> {
+    let usage = |(busy0, total0): (u64, u64), (busy1, total1): (u64, u64)| {
+        let total = total1.saturating_sub(total0);
+        if total == 0 {
+            0.0
+        } else {
+            busy1.saturating_sub(busy0) as f32 * 100.0 / total as f32
+        }
+    };
+    let (first_prev, first_cur) = (previous.cpu.first()?, current.cpu.first()?);
+    let per_core: Vec<f32> = previous
+        .cpu
+        .iter()
+        .zip(&current.cpu)
+        .skip(1)
+        .map(|(p, c)| usage(*p, *c))
+        .collect();
+    Some(CpuInfo {
+        core_count: per_core.len(),
+        usage_percent: usage(*first_prev, *first_cur),
+        per_core,
+    })
+}
+
+async fn store_sample(
+    conn: &Arc<Mutex<Connection>>,
+    host_id: &str,
+    sample: &StreamSample,
+) -> Result<()> {
+    if let Some(cpu) = &sample.cpu {
+        Collector::store(&CpuCollector, conn, host_id, cpu).await?;
+    }
+    if let Some(mem) = &sample.mem {
+        Collector::store(&MemCollector, conn, host_id, mem).await?;
+    }
+    if let Some(net) = &sample.net {
+        let insert = NetResultInsert {
+            host_id: host_id.to_string(),
+            rx_bytes_per_sec: net.rx_bytes_per_sec,
+            tx_bytes_per_sec: net.tx_bytes_per_sec,
+        };
+        store_net_result(conn, &insert).await?;
+    }
+    Ok(())
+}
+
+/// Streams samples from `host` into the DB over one long-lived session until
+/// `stop` is set or the remote loop ends. Samples already received are
+/// stored before this returns.
+pub async fn run_stream(host: SshHostInfo, conn: Arc<Mutex<Connection>>, stop: Arc<AtomicBool>) {
+    let nonce = new_nonce();
+    let command = sampler_command(&nonce);
+    let (samples, mut received) = mpsc::unbounded_channel();
+    let host_id = host.id.clone();
+    info!("📡 Streaming samples from '{host_id}'");
+
+    let reader = tokio::task::spawn_blocking(move || {
+        let mut parser = StreamParser::new(&nonce);
+        stream_remote(&host, &command, &stop, |chunk| {
+            for sample in parser.feed(chunk) {
+                let _ = samples.send(sample);
+            }
+        })
+    });
+    while let Some(sample) = received.recv().await {
+        if let Err(e) = store_sample(&conn, &host_id, &sample).await {
+            warn!("❌ Failed to save streamed sample of '{host_id}': {e}");
+        }
+    }
+    match reader.await {
+        Ok(Ok(())) => info!("📡 Stopped streaming from '{host_id}'"),
+        Ok(Err(e)) => warn!("❌ Stream from '{host_id}' failed: {e}"),
+        Err(e) => warn!("❌ Stream task for '{host_id}' failed: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(nonce: &str, uptime: f64, busy: u64, idle: u64, rx: u64) -> String {
+        format!(
+            "@{nonce} uptime\n{uptime} 100.0\n\
+             @{nonce} stat\ncpu  {busy} 0 0 {idle} 0 0 0 0 0 0\ncpu0 {busy} 0 0 {idle} 0 0 0 0 0 0\n\
+             @{nonce} meminfo\nMemTotal: 2048000 kB\nMemFree: 1024000 kB\n\
+             @{nonce} netdev\nInter-| Receive\n face |bytes\n    lo: 999 1 0 0 0 0 0 0 999 1 0 0 0 0 0 0\n  eth0: {rx} 1 0 0 0 0 0 0 {rx} 1 0 0 0 0 0 0\n\
+             @{nonce} end\n"
+        )
+    }
+
+    #[test]
+    fn test_stream_parser_handles_split_chunks() {
+        let mut parser = StreamParser::new("n");
+        let first = parser.feed(&frame("n", 10.0, 100, 100, 1000));
+        assert_eq!(first.len(), 1);
+        assert!(first[0].cpu.is_none());
+        assert_eq!(first[0].mem.as_ref().unwrap().total_mb, 2000);
+
+        // A forged marker with another nonce is plain output.
+        let output = frame("n", 12.0, 150, 250, 5000).replace("cpu0", "@x end\ncpu0");
+        let (head, tail) = output.split_at(37);
+        assert!(parser.feed(head).is_empty());
+        let samples = parser.feed(tail);
+        assert_eq!(samples.len(), 1);
+        let cpu = samples[0].cpu.as_ref().unwrap();
+        assert_eq!(cpu.usage_percent, 25.0);
+        assert_eq!(cpu.core_count, 1);
+        let net = samples[0].net.unwrap();
+        assert_eq!(net.rx_bytes_per_sec, 2000.0);
+        assert_eq!(net.tx_bytes_per_sec, 2000.0);
+    }
+}
@@ -0,0 +1,104 @@
+use super::clock::ClockSample;
+use crate::backend::db::telemetry::commands::{CycleResultInsert, store_cycle_result};
+use crate::backend::ssh::SessionTimings;
+use anyhow::Result;
+use rusqlite::Connection;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// How one collection cycle of a host ended.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CycleStatus {
+    Ok,
+    Failed,
+    TimedOut,
+}
+
+impl CycleStatus {
+    /// Classifies the error of a cycle that produced no output.
+    pub fn from_error(error: &str) -> Self {
+        if error.to_lowercase().contains("timed out") {
+            CycleStatus::TimedOut
+        } else {
+            CycleStatus::Failed
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CycleStatus::Ok => "ok",
+            CycleStatus::Failed => "failed",
+            CycleStatus::TimedOut => "timed_out",
+        }
+    }
+}
+
+/// Where the time of one collection cycle went, from waiting for a
+/// scheduler slot to parsing the output.
+#[derive(Debug, Clone)]
+pub struct CycleTelemetry {
+    pub status: CycleStatus,
+    /// `None` when the SSH exec failed.
+    pub timings: Option<SessionTimings>,
+    pub parse: Option<Duration>,
+    /// `None` when the host's clock could not be read.
+    pub clock: Option<ClockSample>,
+    pub queue_lag: Duration,
+    pub jobs_ok: usize,
+    pub jobs_failed: usize,
+}
+
+impl CycleTelemetry {
+    pub fn failed(error: &str, queue_lag: Duration, jobs: usize) -> Self {
+        Self {
+            status: CycleStatus::from_error(error),
+            timings: None,
+            parse: None,
+            clock: None,
+            queue_lag,
+            jobs_ok: 0,
+            jobs_failed: jobs,
+        }
+    }
+
+    pub async fn store(&self, conn: &Arc<Mutex<Connection>>, host_id: &str) -> Result<()> {
+        let millis = |duration: Duration| duration.as_millis() as u64;
+        let insert = CycleResultInsert {
+            host_id: host_id.to_string(),
+            status: self.status.as_str().to_string(),
+            connect_ms: self.timings.map(|t| millis(t.connect)),
+            auth_ms: self.timings.map(|t| millis(t.auth)),
+            command_ms: self.timings.map(|t| millis(t.command)),
+            parse_ms: self.parse.map(millis),
+            clock_offset_ms: self.clock.map(|c| c.offset * 1000.0),
+            clock_round_trip_ms: self.clock.map(|c| c.round_trip * 1000.0),
+            queue_lag_ms: millis(self.queue_lag),
+            jobs_ok: self.jobs_ok,
+            jobs_failed: self.jobs_failed,
+        };
+        store_cycle_result(conn, &insert).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_status_from_error() {
+        assert_eq!(
+            CycleStatus::from_error("Read error: timed out"),
+            CycleStatus::TimedOut
+        );
+        assert_eq!(
+            CycleStatus::from_error("TCP error: connection timed out (os error 110)"),
+            CycleStatus::TimedOut
+        );
+        assert_eq!(
+            CycleStatus::from_error("TCP error: Connection refused (os error 111)"),
+            CycleStatus::Failed
+        );
+        assert_eq!(CycleStatus::TimedOut.as_str(), "timed_out");
+    }
+}
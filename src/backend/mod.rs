@@ -1,3 +1,5 @@
 pub mod db;
 pub mod jobs;
+pub mod retention;
+pub mod shutdown;
 pub mod ssh;
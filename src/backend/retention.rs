@@ -0,0 +1,286 @@
+use crate::backend::db::rollup::commands::{
+    delete_raw_before, delete_rollups_before, delete_superseded_before, roll_up_raw,
+    roll_up_rollups,
+};
+use crate::config::RetentionConfig;
+use anyhow::Result;
+use log::{info, warn};
+use rusqlite::Connection;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{Mutex, watch};
+use tokio::task::JoinHandle;
+
+const MINUTE: i64 = 60;
+const HOUR: i64 = 60 * 60;
+const DAY: i64 = 24 * HOUR;
+
+/// Series kept beyond the raw retention: `(metric, table, value expression)`.
+pub const ROLLUP_SOURCES: &[(&str, &str, &str)] = &[
+    ("cpu.usage", "cpu_results", "usage_percent"),
+    ("mem.used", "mem_results", "used_percent"),
+    (
+        "mem.cache",
+        "mem_results",
+        "CASE WHEN total_mb > 0 THEN buffers_cache_mb * 100.0 / total_mb ELSE 0 END",
+    ),
+    (
+        "mem.swap",
+        "mem_results",
+        "CASE WHEN total_mb > 0 THEN swap_used_mb * 100.0 / total_mb ELSE 0 END",
+    ),
+    ("net.rx", "net_results", "rx_bytes_per_sec"),
+    ("net.tx", "net_results", "tx_bytes_per_sec"),
+];
+
+const RAW_TABLES: &[&str] = &[
+    "job_results",
+    "cpu_results",
+    "mem_results",
+    "disk_results",
+    "gpu_results",
+    "gpu_process_results",
+    "session_results",
+    "port_results",
+    "net_results",
+    "cycle_results",
+];
+
+/// Shown as each host's latest row, which may be older than `raw_hours`.
+const LATEST_TABLES: &[&str] = &["package_results"];
+
+const MAX_TIMELINE_POINTS: i64 = 1500;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolution {
+    Raw,
+    Minute,
+    Hour,
+}
+
+impl Resolution {
+    pub fn bucket_secs(self) -> Option<i64> {
+        match self {
+            Resolution::Raw => None,
+            Resolution::Minute => Some(MINUTE),
+            Resolution::Hour => Some(HOUR),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Resolution::Raw => "raw",
+            Resolution::Minute => "1m avg",
+            Resolution::Hour => "1h avg",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TimelineRange {
+    #[default]
+    Hour,
+    Day,
+    Week,
+    Month,
+    Quarter,
+}
+
+impl TimelineRange {
+    pub fn secs(self) -> i64 {
+        match self {
+            TimelineRange::Hour => HOUR,
+            TimelineRange::Day => DAY,
+            TimelineRange::Week => 7 * DAY,
+            TimelineRange::Month => 30 * DAY,
+            TimelineRange::Quarter => 90 * DAY,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TimelineRange::Hour => "1h",
+            TimelineRange::Day => "24h",
+            TimelineRange::Week => "7d",
+            TimelineRange::Month => "30d",
+            TimelineRange::Quarter => "90d",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            TimelineRange::Hour => TimelineRange::Day,
+            TimelineRange::Day => TimelineRange::Week,
+            TimelineRange::Week => TimelineRange::Month,
+            TimelineRange::Month => TimelineRange::Quarter,
+            TimelineRange::Quarter => TimelineRange::Hour,
+        }
+    }
+
+    /// Raw samples are only drawn for the last hour, and minute rollups only
+    /// while they fit in [`MAX_TIMELINE_POINTS`].
+    pub fn resolution(self, retention: &RetentionConfig) -> Resolution {
+        let range = self.secs();
+        if range <= HOUR && range <= retention.raw_hours as i64 * HOUR {
+            Resolution::Raw
+        } else if range <= retention.minute_days as i64 * DAY
+            && range / MINUTE <= MAX_TIMELINE_POINTS
+        {
+            Resolution::Minute
+        } else {
+            Resolution::Hour
+        }
+    }
+}
+
+pub fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// Rolls raw samples up into minute and hour buckets, then drops everything
+/// past its retention. Rolling up comes first so nothing expires unsummarised.
+pub async fn compact(
+    conn: &Arc<Mutex<Connection>>,
+    retention: &RetentionConfig,
+    now: i64,
+) -> Result<()> {
+    for (metric, table, value) in ROLLUP_SOURCES {
+        roll_up_raw(conn, metric, table, value, MINUTE, now).await?;
+        roll_up_rollups(conn, metric, MINUTE, HOUR, now).await?;
+    }
+
+    let mut deleted = 0;
+    let raw_cutoff = now - retention.raw_hours as i64 * HOUR;
+    for table in RAW_TABLES {
+        deleted += delete_raw_before(conn, table, raw_cutoff).await?;
+    }
+    for table in LATEST_TABLES {
+        deleted += delete_superseded_before(conn, table, raw_cutoff).await?;
+    }
+    deleted +=
+        delete_rollups_before(conn, MINUTE, now - retention.minute_days as i64 * DAY).await?;
+    deleted += delete_rollups_before(conn, HOUR, now - retention.hour_days as i64 * DAY).await?;
+    if deleted > 0 {
+        info!("🧹 Expired {deleted} rows past their retention");
+    }
+    Ok(())
+}
+
+pub struct Compactor {
+    shutdown: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+impl Compactor {
+    pub fn start(conn: Arc<Mutex<Connection>>, retention: RetentionConfig) -> Self {
+        let (shutdown, mut stop) = watch::channel(false);
+        let interval = Duration::from_secs(retention.compact_interval_secs);
+        let task = tokio::spawn(async move {
+            loop {
+                if let Err(e) = compact(&conn, &retention, now_secs()).await {
+                    warn!("❌ Failed to compact the DB: {e:#}");
+                }
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {}
+                    _ = stop.wait_for(|stop| *stop) => return,
+                }
+            }
+        });
+        Self { shutdown, task }
+    }
+
+    pub async fn shutdown(self, grace: Duration) {
+        self.shutdown.send_replace(true);
+        crate::backend::shutdown::join_within(vec![self.task], grace).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::db::init_schema;
+    use crate::backend::db::rollup::queries::fetch_rollup_timeline;
+    use rusqlite::params;
+
+    #[tokio::test]
+    async fn test_compact_rolls_up_before_expiring() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn);
+        // Two hours ago, 10 samples a minute apart, then one sample now.
+        let now = 1_700_000_000 / HOUR * HOUR + 30;
+        for (i, usage) in (0..10).zip([10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0, 80.0, 90.0, 100.0])
+        {
+            conn.execute(
+                "INSERT INTO cpu_results (host_id, model_name, core_count, usage_percent, per_core_json, timestamp) \
+                 VALUES ('a', '', 1, ?1, '[]', datetime(?2, 'unixepoch'))",
+                params![usage, now - 2 * HOUR + i * MINUTE],
+            )
+            .unwrap();
+        }
+        let conn = Arc::new(Mutex::new(conn));
+        let retention = RetentionConfig {
+            raw_hours: 1,
+            ..RetentionConfig::default()
+        };
+
+        compact(&conn, &retention, now).await.unwrap();
+        compact(&conn, &retention, now).await.unwrap();
+
+        let remaining: i64 = conn
+            .lock()
+            .await
+            .query_row("SELECT COUNT(*) FROM cpu_results", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(remaining, 0);
+        let minutes = fetch_rollup_timeline(&conn, "cpu.usage", MINUTE, 0)
+            .await
+            .unwrap();
+        assert_eq!(minutes.len(), 10);
+        assert_eq!(minutes[0].avg, 100.0);
+        let hours = fetch_rollup_timeline(&conn, "cpu.usage", HOUR, 0)
+            .await
+            .unwrap();
+        assert_eq!(hours.len(), 1);
+        assert_eq!(hours[0].avg, 55.0);
+
+        assert_eq!(TimelineRange::Hour.resolution(&retention), Resolution::Raw);
+        assert_eq!(
+            TimelineRange::Day.resolution(&retention),
+            Resolution::Minute
+        );
+        assert_eq!(TimelineRange::Week.resolution(&retention), Resolution::Hour);
+    }
+
+    #[tokio::test]
+    async fn test_compact_keeps_latest_packages() {
+        use crate::backend::db::packages::queries::fetch_latest_packages_all;
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn);
+        let now = 1_700_000_000;
+        for (pending, age) in [(5, 30 * HOUR), (3, 7 * HOUR)] {
+            conn.execute(
+                "INSERT INTO package_results (host_id, pending_count, reboot_required, timestamp)                  VALUES ('a', ?1, 0, datetime(?2, 'unixepoch'))",
+                params![pending, now - age],
+            )
+            .unwrap();
+        }
+        let conn = Arc::new(Mutex::new(conn));
+
+        compact(&conn, &RetentionConfig::default(), now)
+            .await
+            .unwrap();
+
+        let packages = fetch_latest_packages_all(&conn).await.unwrap();
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].pending_count, 3);
+        let remaining: i64 = conn
+            .lock()
+            .await
+            .query_row("SELECT COUNT(*) FROM package_results", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(remaining, 1);
+    }
+}
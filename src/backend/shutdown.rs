@@ -0,0 +1,17 @@
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, timeout_at};
+
+/// Waits for `tasks` to stop on their own and aborts whatever is still
+/// running once `grace` has passed. Returns how many had to be aborted.
+pub async fn join_within(tasks: Vec<JoinHandle<()>>, grace: Duration) -> usize {
+    let deadline = Instant::now() + grace;
+    let mut aborted = 0;
+    for mut task in tasks {
+        if timeout_at(deadline, &mut task).await.is_err() {
+            task.abort();
+            aborted += 1;
+        }
+    }
+    aborted
+}
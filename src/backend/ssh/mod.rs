@@ -1,11 +1,47 @@
 use crate::ssh_config::SshHostInfo;
 use ssh2::Session;
-use std::io::Read;
+use std::io::{Read, Write};
 use std::net::{SocketAddr, TcpStream};
-use std::path::PathBuf;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime};
 
-pub fn connect_ssh_session(info: &SshHostInfo) -> Result<Session, String> {
+/// Connecting never needs the long timeouts some collectors have.
+const MAX_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long a single read may block before deadline and cancellation are
+/// checked again.
+const READ_POLL: Duration = Duration::from_millis(250);
+/// How long a command's output loop sleeps when neither stream had data.
+const READ_IDLE: Duration = Duration::from_millis(10);
+
+fn millis(duration: Duration) -> u32 {
+    duration.as_millis().try_into().unwrap_or(u32::MAX)
+}
+
+/// Where the time of one remote command went.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SessionTimings {
+    /// TCP connect and SSH handshake.
+    pub connect: Duration,
+    pub auth: Duration,
+    /// From exec until the channel closed.
+    pub command: Duration,
+}
+
+/// Connects and authenticates. `timeout` bounds the TCP connect and every
+/// blocking libssh2 call made on the returned session.
+pub fn connect_ssh_session(info: &SshHostInfo, timeout: Duration) -> Result<Session, String> {
+    connect_timed(info, timeout).map(|(session, _)| session)
+}
+
+/// [`connect_ssh_session`], also reporting how long connecting and
+/// authenticating took.
+fn connect_timed(
+    info: &SshHostInfo,
+    timeout: Duration,
+) -> Result<(Session, SessionTimings), String> {
+    let started = Instant::now();
     let socket_addr = match info.ip.as_str() {
         "localhost" => "127.0.0.1".to_string(),
         _ => info.ip.clone(),
@@ -14,15 +50,17 @@ pub fn connect_ssh_session(info: &SshHostInfo) -> Result<Session, String> {
     let socket_addr = format!("{}:{}", socket_addr, info.port)
         .parse::<SocketAddr>()
         .map_err(|e| format!("Invalid address: {}", e))?;
-    let tcp = TcpStream::connect_timeout(&socket_addr, Duration::from_secs(1))
+    let tcp = TcpStream::connect_timeout(&socket_addr, timeout)
         .map_err(|e| format!("TCP error: {}", e))?;
 
     let mut session = Session::new().map_err(|e| format!("Session error: {}", e))?;
+    session.set_timeout(millis(timeout));
 
     session.set_tcp_stream(tcp);
     session
         .handshake()
         .map_err(|e| format!("Handshake error: {}", e))?;
+    let connected = Instant::now();
 
     let identity_path = PathBuf::from(&info.identity_file);
     if !identity_path.exists() {
@@ -43,28 +81,280 @@ pub fn connect_ssh_session(info: &SshHostInfo) -> Result<Session, String> {
 
     for identity in agent.identities().unwrap_or_default() {
         if agent.userauth(&info.user, &identity).is_ok() && session.authenticated() {
-            return Ok(session);
+            let timings = SessionTimings {
+                connect: connected - started,
+                auth: connected.elapsed(),
+                command: Duration::ZERO,
+            };
+            return Ok((session, timings));
         }
     }
 
     Err("SSH authentication failed".into())
 }
 
-pub fn run_ssh_command(session: &Session, command: &str) -> Result<String, String> {
+/// Everything a remote command left behind.
+#[derive(Debug, Clone)]
+pub struct CommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_status: i32,
+    pub timings: SessionTimings,
+    /// Wall clock when the exec request was sent, for comparing with the
+    /// host's clock.
+    pub exec_sent_at: SystemTime,
+    /// Wall clock when the last of stdout arrived.
+    pub stdout_done_at: SystemTime,
+}
+
+/// Reads a channel stream to the end, giving up once `deadline` has passed or
+/// `cancel` is set. Reads block for at most [`READ_POLL`] at a time, so either
+/// is noticed promptly even while the host prints nothing.
+fn read_until(
+    stream: &mut impl Read,
+    deadline: Instant,
+    cancel: &AtomicBool,
+) -> Result<String, String> {
+    let mut output = Vec::new();
+    let mut buf = [0u8; 8192];
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Err("Read error: cancelled".into());
+        }
+        if Instant::now() >= deadline {
+            return Err("Read error: timed out".into());
+        }
+        match stream.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => output.extend_from_slice(&buf[..n]),
+            Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {}
+            Err(e) => return Err(format!("Read error: {}", e)),
+        }
+    }
+    Ok(String::from_utf8_lossy(&output).into_owned())
+}
+
+/// Appends whatever `stream` has buffered without waiting for more. Returns
+/// `None` at the end of the stream, otherwise the number of bytes read.
+fn read_available(stream: &mut impl Read, output: &mut Vec<u8>) -> Result<Option<usize>, String> {
+    let mut buf = [0u8; 8192];
+    match stream.read(&mut buf) {
+        Ok(0) => Ok(None),
+        Ok(n) => {
+            output.extend_from_slice(&buf[..n]);
+            Ok(Some(n))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => Ok(Some(0)),
+        Err(e) => Err(format!("Read error: {}", e)),
+    }
+}
+
+/// Reads stdout and stderr of a running command in one non-blocking loop, so
+/// a command that fills its stderr window while stdout is still open cannot
+/// stall both sides. Returns both streams and when stdout ended.
+fn read_output(
+    session: &Session,
+    channel: &mut ssh2::Channel,
+    deadline: Instant,
+    cancel: &AtomicBool,
+) -> Result<(String, String, SystemTime), String> {
+    let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+    let mut pump = || {
+        let mut stdout_done_at = None;
+        let mut stderr_done = false;
+        loop {
+            if cancel.load(Ordering::Relaxed) {
+                return Err("Read error: cancelled".to_string());
+            }
+            if Instant::now() >= deadline {
+                return Err("Read error: timed out".to_string());
+            }
+            let mut read = 0;
+            if stdout_done_at.is_none() {
+                match read_available(channel, &mut stdout)? {
+                    Some(n) => read += n,
+                    None => stdout_done_at = Some(SystemTime::now()),
+                }
+            }
+            if !stderr_done {
+                match read_available(&mut channel.stderr(), &mut stderr)? {
+                    Some(n) => read += n,
+                    None => stderr_done = true,
+                }
+            }
+            if let Some(stdout_done_at) = stdout_done_at
+                && stderr_done
+            {
+                return Ok(stdout_done_at);
+            }
+            if read == 0 {
+                std::thread::sleep(READ_IDLE);
+            }
+        }
+    };
+
+    session.set_blocking(false);
+    let result = pump();
+    session.set_blocking(true);
+
+    let stdout_done_at = result?;
+    Ok((
+        String::from_utf8_lossy(&stdout).into_owned(),
+        String::from_utf8_lossy(&stderr).into_owned(),
+        stdout_done_at,
+    ))
+}
+
+pub fn run_ssh_command(
+    session: &Session,
+    command: &str,
+    deadline: Instant,
+    cancel: &AtomicBool,
+) -> Result<CommandOutput, String> {
     let mut channel = session
         .channel_session()
         .map_err(|e| format!("Channel error: {}", e))?;
+    let exec_sent_at = SystemTime::now();
     channel
         .exec(command)
         .map_err(|e| format!("Exec error: {}", e))?;
 
-    let mut output = String::new();
-    channel
-        .read_to_string(&mut output)
-        .map_err(|e| format!("Read error: {}", e))?;
+    let (stdout, stderr, stdout_done_at) = read_output(session, &mut channel, deadline, cancel)?;
+    session.set_timeout(millis(deadline.saturating_duration_since(Instant::now())).max(1));
     channel
         .wait_close()
         .map_err(|e| format!("Wait close error: {}", e))?;
+    let exit_status = channel
+        .exit_status()
+        .map_err(|e| format!("Exit status error: {}", e))?;
+
+    Ok(CommandOutput {
+        stdout,
+        stderr,
+        exit_status,
+        timings: SessionTimings::default(),
+        exec_sent_at,
+        stdout_done_at,
+    })
+}
+
+/// Connects and runs `command` on tokio's blocking pool, so libssh2's blocking
+/// I/O never occupies a runtime worker. Connecting is bounded on its own and
+/// the command then gets the full `timeout`, so a slow handshake does not eat
+/// into it. Setting `cancel` ends either early; the thread is released and
+/// the session disconnected, not just abandoned.
+pub async fn run_remote(
+    info: SshHostInfo,
+    command: String,
+    timeout: Duration,
+    cancel: Arc<AtomicBool>,
+) -> Result<CommandOutput, String> {
+    tokio::task::spawn_blocking(move || {
+        let (session, mut timings) = connect_timed(&info, timeout.min(MAX_CONNECT_TIMEOUT))?;
+        if cancel.load(Ordering::Relaxed) {
+            return Err("Cancelled".into());
+        }
+        let started = Instant::now();
+        let output = run_ssh_command(&session, &command, started + timeout, &cancel);
+        timings.command = started.elapsed();
+        let _ = session.disconnect(None, "ssh-monitor done", None);
+        output.map(|output| CommandOutput { timings, ..output })
+    })
+    .await
+    .map_err(|e| format!("SSH task failed: {}", e))?
+}
+
+/// Copies `contents` to `remote_path` over SCP with the given permissions.
+/// Relative paths are relative to the remote home directory.
+pub async fn upload_file(
+    info: SshHostInfo,
+    contents: Arc<Vec<u8>>,
+    remote_path: String,
+    mode: i32,
+    timeout: Duration,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let session = connect_ssh_session(&info, timeout.min(MAX_CONNECT_TIMEOUT))?;
+        session.set_timeout(millis(timeout));
+        let mut channel = session
+            .scp_send(Path::new(&remote_path), mode, contents.len() as u64, None)
+            .map_err(|e| format!("SCP error: {}", e))?;
+        let result = channel
+            .write_all(&contents)
+            .and_then(|_| channel.send_eof().map_err(Into::into))
+            .and_then(|_| channel.wait_eof().map_err(Into::into))
+            .and_then(|_| channel.wait_close().map_err(Into::into))
+            .map_err(|e: std::io::Error| format!("Upload error: {}", e));
+        let _ = session.disconnect(None, "ssh-monitor done", None);
+        result
+    })
+    .await
+    .map_err(|e| format!("SSH task failed: {}", e))?
+}
+
+/// Runs a long-lived `command` until it exits or `stop` is set, handing its
+/// stdout to `on_output` as it arrives. Blocks; call it on the blocking pool.
+pub fn stream_remote(
+    info: &SshHostInfo,
+    command: &str,
+    stop: &AtomicBool,
+    mut on_output: impl FnMut(&str),
+) -> Result<(), String> {
+    let session = connect_ssh_session(info, MAX_CONNECT_TIMEOUT)?;
+    let mut channel = session
+        .channel_session()
+        .map_err(|e| format!("Channel error: {}", e))?;
+    channel
+        .exec(command)
+        .map_err(|e| format!("Exec error: {}", e))?;
+
+    session.set_timeout(millis(READ_POLL));
+    let mut buf = [0u8; 8192];
+    let result = loop {
+        if stop.load(Ordering::Relaxed) {
+            break Ok(());
+        }
+        match channel.read(&mut buf) {
+            Ok(0) => {
+                let stderr = read_until(&mut channel.stderr(), Instant::now() + READ_POLL, stop)
+                    .unwrap_or_default();
+                break match stderr.trim() {
+                    "" => Ok(()),
+                    stderr => Err(stderr.to_string()),
+                };
+            }
+            Ok(n) => on_output(&String::from_utf8_lossy(&buf[..n])),
+            Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {}
+            Err(e) => break Err(format!("Read error: {}", e)),
+        }
+    };
+    let _ = session.disconnect(None, "ssh-monitor done", None);
+    result
+}
 
-    Ok(output)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_run_remote_gives_up_on_silent_host() {
+        // Accepts the TCP connection but never sends an SSH banner.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let info = SshHostInfo {
+            id: "silent".into(),
+            name: "silent".into(),
+            ip: "127.0.0.1".into(),
+            port: listener.local_addr().unwrap().port(),
+            user: "nobody".into(),
+            identity_file: String::new(),
+            proxy_jump: None,
+        };
+
+        let started = Instant::now();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let result = run_remote(info, "true".into(), Duration::from_millis(300), cancel).await;
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(3));
+    }
 }
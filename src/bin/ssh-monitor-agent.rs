@@ -0,0 +1,126 @@
+//! Collector agent that ssh-monitor uploads to hosts when `[agent] enabled`
+//! is set. It reads `/proc` directly instead of relying on the tools a distro
+//! happens to ship, and answers with one JSON frame per line:
+//!
+//! ```text
+//! ssh-monitor-agent hello          -> {"v":1,"agent":"0.1.5"}
+//! ssh-monitor-agent run cpu mem    -> {"v":1,"job":"cpu","stdout":"...","stderr":"","exit_code":0,"duration_ms":251}
+//! ```
+//!
+//! `stdout` is what the matching shell collector would print, so the client
+//! parses both the same way. Build it statically for the hosts, e.g.
+//! `cargo build --release --bin ssh-monitor-agent --target x86_64-unknown-linux-musl`.
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Bumped whenever a frame changes shape; the client refuses other versions.
+const PROTOCOL_VERSION: u32 = 1;
+
+/// Window over which CPU usage is measured.
+const CPU_WINDOW: Duration = Duration::from_millis(250);
+
+#[derive(Serialize)]
+struct Hello {
+    v: u32,
+    agent: &'static str,
+}
+
+#[derive(Serialize)]
+struct JobFrame<'a> {
+    v: u32,
+    job: &'a str,
+    stdout: String,
+    stderr: String,
+    exit_code: i32,
+    duration_ms: u64,
+}
+
+/// `(busy, total)` jiffies per `cpu` line of `/proc/stat`.
+fn cpu_times() -> std::io::Result<Vec<(String, u64, u64)>> {
+    let stat = std::fs::read_to_string("/proc/stat")?;
+    Ok(stat
+        .lines()
+        .filter(|line| line.starts_with("cpu"))
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?.to_string();
+            let values: Vec<u64> = fields.take(8).filter_map(|v| v.parse().ok()).collect();
+            let total: u64 = values.iter().sum();
+            let idle = values.get(3)? + values.get(4).unwrap_or(&0);
+            Some((name, total - idle, total))
+        })
+        .collect())
+}
+
+fn cpu() -> Result<String, String> {
+    let before = cpu_times().map_err(|e| format!("/proc/stat: {e}"))?;
+    std::thread::sleep(CPU_WINDOW);
+    let after = cpu_times().map_err(|e| format!("/proc/stat: {e}"))?;
+
+    let mut stdout = String::from("__STAT__\n");
+    for ((name, busy0, total0), (_, busy1, total1)) in before.iter().zip(&after) {
+        let total = total1.saturating_sub(*total0);
+        let usage = if total == 0 {
+            0.0
+        } else {
+            busy1.saturating_sub(*busy0) as f64 * 100.0 / total as f64
+        };
+        stdout.push_str(&format!("{name} {usage}\n"));
+    }
+    Ok(stdout)
+}
+
+fn mem() -> Result<String, String> {
+    let meminfo =
+        std::fs::read_to_string("/proc/meminfo").map_err(|e| format!("/proc/meminfo: {e}"))?;
+    Ok(format!("Linux\n__MEM__\n__MEMINFO__\n{meminfo}"))
+}
+
+fn run_job(job: &str) -> JobFrame<'_> {
+    let started = Instant::now();
+    let result = match job {
+        "cpu" => cpu(),
+        "mem" => mem(),
+        _ => Err(format!("{job}: not found")),
+    };
+    let (stdout, stderr, exit_code) = match result {
+        Ok(stdout) => (stdout, String::new(), 0),
+        Err(stderr) if stderr.ends_with("not found") => (String::new(), stderr, 127),
+        Err(stderr) => (String::new(), stderr, 1),
+    };
+    JobFrame {
+        v: PROTOCOL_VERSION,
+        job,
+        stdout,
+        stderr,
+        exit_code,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+fn print(value: &impl Serialize) {
+    match serde_json::to_string(value) {
+        Ok(line) => println!("{line}"),
+        Err(e) => eprintln!("failed to encode frame: {e}"),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("hello") => print(&Hello {
+            v: PROTOCOL_VERSION,
+            agent: env!("CARGO_PKG_VERSION"),
+        }),
+        Some("run") => {
+            for job in &args[1..] {
+                print(&run_job(job));
+            }
+        }
+        _ => {
+            eprintln!("usage: ssh-monitor-agent hello | run <job>...");
+            std::process::exit(2);
+        }
+    }
+}
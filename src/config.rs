@@ -0,0 +1,323 @@
+use directories::ProjectDirs;
+use eyre::Result;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+pub const DEFAULT_EXCLUDED_FS_TYPES: &[&str] = &[
+    "autofs",
+    "binfmt_misc",
+    "bpf",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devfs",
+    "devpts",
+    "devtmpfs",
+    "efivarfs",
+    "fuse.gvfsd-fuse",
+    "fuse.lxcfs",
+    "fuse.portal",
+    "fusectl",
+    "hugetlbfs",
+    "mqueue",
+    "nsfs",
+    "nullfs",
+    "overlay",
+    "proc",
+    "pstore",
+    "ramfs",
+    "rpc_pipefs",
+    "securityfs",
+    "squashfs",
+    "sysfs",
+    "tmpfs",
+    "tracefs",
+];
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub disk: DiskConfig,
+    pub collectors: Vec<CollectorConfig>,
+    pub scheduler: SchedulerConfig,
+    pub agent: AgentConfig,
+    pub clock: ClockConfig,
+    pub retention: RetentionConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DiskConfig {
+    pub exclude_fs_types: Vec<String>,
+    pub exclude_mount_points: Vec<String>,
+}
+
+impl Default for DiskConfig {
+    fn default() -> Self {
+        Self {
+            exclude_fs_types: DEFAULT_EXCLUDED_FS_TYPES
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            exclude_mount_points: Vec::new(),
+        }
+    }
+}
+
+/// Limits on how many hosts are polled at once, shared by all hosts.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    /// SSH sessions open at the same time across every host.
+    pub max_concurrent: usize,
+    /// Sessions at the same time among hosts behind one `ProxyJump` host.
+    pub max_per_bastion: usize,
+    /// Each interval is stretched or shrunk by up to this fraction.
+    pub jitter: f64,
+    /// Interval of the selected host's fast collectors (30s or less) while
+    /// it is selected; slower collectors keep their own intervals.
+    pub focus_interval_secs: u64,
+    /// Limit on how far the intervals of hosts whose samples stop changing
+    /// are stretched.
+    pub max_backoff: f64,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 16,
+            max_per_bastion: 4,
+            jitter: 0.1,
+            focus_interval_secs: 2,
+            max_backoff: 4.0,
+        }
+    }
+}
+
+/// Uploading the `ssh-monitor-agent` binary and collecting CPU and memory
+/// through it; the other collectors stay shell scripts.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AgentConfig {
+    pub enabled: bool,
+    /// The binary to upload; defaults to the static musl build cargo puts
+    /// beside the running executable's target directory.
+    pub binary: Option<PathBuf>,
+}
+
+/// Comparing each host's clock with the monitor's.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ClockConfig {
+    /// Hosts whose clock is further off than this are flagged.
+    pub max_skew_secs: f64,
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        Self { max_skew_secs: 2.0 }
+    }
+}
+
+/// How long samples are kept at each resolution.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RetentionConfig {
+    /// Every sample as collected.
+    pub raw_hours: u64,
+    /// Per-minute averages.
+    pub minute_days: u64,
+    /// Per-hour averages.
+    pub hour_days: u64,
+    /// How often samples are rolled up and expired.
+    pub compact_interval_secs: u64,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            raw_hours: 6,
+            minute_days: 7,
+            hour_days: 180,
+            compact_interval_secs: 300,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectorFormat {
+    /// The first number printed by the command.
+    #[default]
+    Number,
+    /// `key=value` or `key: value` lines with numeric values.
+    KeyValue,
+    /// A JSON document; numeric leaves are flattened to dotted keys.
+    Json,
+    /// Captures of `pattern`, keyed by group name or index.
+    Regex,
+}
+
+/// A user-defined collector declared as `[[collectors]]` in `config.toml`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CollectorConfig {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub format: CollectorFormat,
+    pub pattern: Option<String>,
+    pub units: Option<String>,
+    #[serde(default = "default_collector_interval_secs")]
+    pub interval_secs: u64,
+    #[serde(default = "default_collector_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Host ids to run on. Empty means every host.
+    #[serde(default)]
+    pub hosts: Vec<String>,
+}
+
+fn default_collector_interval_secs() -> u64 {
+    60
+}
+
+fn default_collector_timeout_secs() -> u64 {
+    10
+}
+
+impl CollectorConfig {
+    pub fn applies_to(&self, host_id: &str) -> bool {
+        self.hosts.is_empty() || self.hosts.iter().any(|h| h == host_id)
+    }
+
+    /// The output section tag: the name reduced to characters that are safe
+    /// inside the markers, so distinct names can map to the same tag.
+    pub fn section_tag(&self) -> String {
+        let name = self
+            .name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        format!("custom_{}", name)
+    }
+}
+
+impl AppConfig {
+    fn validate(&self) -> Result<()> {
+        if self.scheduler.max_concurrent == 0 || self.scheduler.max_per_bastion == 0 {
+            eyre::bail!("scheduler limits must be at least 1");
+        }
+        if !(0.0..1.0).contains(&self.scheduler.jitter) {
+            eyre::bail!("scheduler jitter must be in [0, 1)");
+        }
+        if self.scheduler.focus_interval_secs == 0 {
+            eyre::bail!("scheduler focus_interval_secs must be at least 1");
+        }
+        if self.scheduler.max_backoff < 1.0 {
+            eyre::bail!("scheduler max_backoff must be at least 1");
+        }
+        if self.clock.max_skew_secs <= 0.0 {
+            eyre::bail!("clock max_skew_secs must be positive");
+        }
+        let retention = &self.retention;
+        if retention.raw_hours == 0 || retention.compact_interval_secs == 0 {
+            eyre::bail!("retention raw_hours and compact_interval_secs must be at least 1");
+        }
+        if retention.minute_days * 24 < retention.raw_hours
+            || retention.hour_days < retention.minute_days
+        {
+            eyre::bail!("retention must not shrink as resolution gets coarser");
+        }
+        let mut names = std::collections::HashSet::new();
+        let mut tags = std::collections::HashMap::new();
+        for collector in &self.collectors {
+            if collector.name.trim().is_empty() {
+                eyre::bail!("collector names must not be empty");
+            }
+            if !names.insert(collector.name.as_str()) {
+                eyre::bail!("duplicate collector name '{}'", collector.name);
+            }
+            if let Some(other) = tags.insert(collector.section_tag(), collector.name.as_str()) {
+                eyre::bail!(
+                    "collector names '{}' and '{}' differ only in punctuation",
+                    other,
+                    collector.name
+                );
+            }
+            if collector.interval_secs == 0 {
+                eyre::bail!("collector '{}' has interval_secs = 0", collector.name);
+            }
+            if collector.timeout_secs == 0 {
+                eyre::bail!("collector '{}' has timeout_secs = 0", collector.name);
+            }
+            match (&collector.format, &collector.pattern) {
+                (CollectorFormat::Regex, None) => {
+                    eyre::bail!(
+                        "collector '{}' uses format = \"regex\" without a pattern",
+                        collector.name
+                    )
+                }
+                (CollectorFormat::Regex, Some(pattern)) => {
+                    regex::Regex::new(pattern).map_err(|e| {
+                        eyre::eyre!(
+                            "collector '{}' has an invalid pattern: {}",
+                            collector.name,
+                            e
+                        )
+                    })?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn get_default_config_path() -> Option<PathBuf> {
+    ProjectDirs::from("com", "tsugumi-sys", "SshMonitor")
+        .map(|dirs| dirs.config_dir().join("config.toml"))
+}
+
+/// Reads `config.toml` from the platform config directory. A missing file is
+/// not an error and yields the defaults.
+pub fn load_app_config() -> Result<AppConfig> {
+    let Some(path) = get_default_config_path() else {
+        return Ok(AppConfig::default());
+    };
+    if !path.exists() {
+        return Ok(AppConfig::default());
+    }
+    parse_app_config(&std::fs::read_to_string(&path)?)
+}
+
+fn parse_app_config(content: &str) -> Result<AppConfig> {
+    let config: AppConfig = toml::from_str(content)?;
+    config.validate()?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_app_config_rejects_bad_collectors() {
+        let collector = |name: &str, extra: &str| {
+            format!("[[collectors]]\nname = \"{name}\"\ncommand = \"true\"\n{extra}\n")
+        };
+
+        let config = parse_app_config(&(collector("a-b", "") + &collector("queue", ""))).unwrap();
+        assert_eq!(config.collectors.len(), 2);
+        assert_eq!(config.collectors[0].section_tag(), "custom_a_b");
+
+        for invalid in [
+            collector("a", "") + &collector("a", ""),
+            collector("a-b", "") + &collector("a_b", ""),
+            collector("a", "format = \"regex\""),
+            collector("a", "interval_secs = 0"),
+            collector("a", "timeout_secs = 0"),
+        ] {
+            assert!(parse_app_config(&invalid).is_err(), "{invalid}");
+        }
+    }
+}
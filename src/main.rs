@@ -1,10 +1,17 @@
 mod backend;
+mod config;
 use backend::db::init_db_connection;
-use backend::jobs::executor::JobGroupExecutor;
-use backend::jobs::job::{JobGroup, JobKind};
+use backend::jobs::agent::AgentBinary;
+use backend::jobs::collector::CollectorRegistry;
+use backend::jobs::executor::{GroupCommand, JobGroupExecutor};
+use backend::jobs::job::JobGroup;
+use backend::jobs::scheduler::Scheduler;
+use backend::jobs::stream::STREAM_INTERVAL_SECS;
+use backend::retention::Compactor;
+use config::{AppConfig, get_default_config_path, load_app_config};
 mod ssh_config;
 use color_eyre::Result;
-use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use futures::{FutureExt, StreamExt};
 use ratatui::widgets::TableState;
 use ratatui::{DefaultTerminal, Frame, widgets::ScrollbarState};
@@ -14,13 +21,17 @@ mod tui;
 use crate::tui::list_ssh::states::ListSshJobKind;
 use crate::tui::states_update::{StatesJobExecutor, StatesJobGroup};
 use std::{collections::HashMap, sync::Arc, time::Duration};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, watch};
+use tui::diagnostics::{handle_key as handle_diagnostics_key, render as render_diagnostics};
 use tui::host_details::states::{DetailsJobKind, HostDetailsState};
 use tui::host_details::{handle_key as handle_details_key, render as render_details};
 use tui::list_ssh::{
     handle_key as handle_list_key, render as render_list,
-    states::{CpuStates, DiskStates, MemStates},
+    states::{
+        CpuStates, DiskStates, HealthStates, LatencyStates, MemStates, PackageStates, SessionStates,
+    },
 };
+use tui::scheduler::{handle_key as handle_scheduler_key, render as render_scheduler};
 
 #[tokio::main]
 async fn main() -> color_eyre::Result<()> {
@@ -48,17 +59,62 @@ async fn main() -> color_eyre::Result<()> {
         }
     };
 
+    let app_config = match load_app_config() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("❌ Error reading ssh-monitor config: {}", err);
+            if let Some(path) = get_default_config_path() {
+                eprintln!("Please check {} and try again.", path.display());
+            }
+            std::process::exit(1);
+        }
+    };
+
     let terminal = ratatui::init();
-    let result = App::new_with_hosts(ssh_hosts).run(terminal).await;
+    let result = App::new_with_hosts(ssh_hosts, app_config)
+        .run(terminal)
+        .await;
     ratatui::restore();
     result
 }
 
+/// How long collectors and state updaters get to stop after quitting.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(3);
+
+/// The details view shows the focused host, so it refreshes as often as that
+/// host is polled.
+fn details_interval(config: &AppConfig) -> Duration {
+    Duration::from_secs(config.scheduler.focus_interval_secs.min(5))
+}
+
+/// Resolves on SIGINT or SIGTERM.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
+            },
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum AppMode {
     List,
     Search,
     Details,
+    Scheduler,
+    Diagnostics,
 }
 
 #[derive(Debug)]
@@ -66,15 +122,26 @@ pub struct App {
     running: bool,
     event_stream: EventStream,
     pub mode: AppMode,
+    pub config: AppConfig,
     pub db: Arc<Mutex<Connection>>,
+    pub scheduler: Arc<Scheduler>,
+    pub executor: JobGroupExecutor,
     pub ssh_hosts: SharedSshHosts,
     pub cpu_states: Arc<CpuStates>,
     pub mem_states: Arc<MemStates>,
     pub disk_states: Arc<DiskStates>,
+    pub session_states: Arc<SessionStates>,
+    pub package_states: Arc<PackageStates>,
+    pub latency_states: Arc<LatencyStates>,
+    pub health_states: Arc<HealthStates>,
     pub details_states: HostDetailsState,
     pub table_state: TableState,
     pub table_height: usize,
     pub selected_id: Option<String>,
+    /// The host the executor was last told to poll faster.
+    focused_id: Option<String>,
+    /// How often the details view reloads from the DB.
+    details_refresh: watch::Sender<Duration>,
     pub vertical_scroll_state: ScrollbarState,
     pub vertical_scroll: usize,
     pub search_query: String,
@@ -90,10 +157,11 @@ impl Default for App {
 impl App {
     pub fn new() -> Self {
         let ssh_hosts = load_ssh_configs().unwrap_or_default(); // now a HashMap
-        Self::new_with_hosts(ssh_hosts)
+        let config = load_app_config().unwrap_or_default();
+        Self::new_with_hosts(ssh_hosts, config)
     }
 
-    pub fn new_with_hosts(ssh_hosts: HashMap<String, SshHostInfo>) -> Self {
+    pub fn new_with_hosts(ssh_hosts: HashMap<String, SshHostInfo>, config: AppConfig) -> Self {
         let mut visible_hosts: Vec<(String, SshHostInfo)> = ssh_hosts
             .iter()
             .map(|(k, v)| (k.clone(), v.clone()))
@@ -104,20 +172,40 @@ impl App {
         let cpu_states = Arc::new(CpuStates::new());
         let mem_states = Arc::new(MemStates::new());
         let disk_states = Arc::new(DiskStates::new());
-        let details_states = HostDetailsState::new();
+        let session_states = Arc::new(SessionStates::new());
+        let package_states = Arc::new(PackageStates::new());
+        let latency_states = Arc::new(LatencyStates::new());
+        let health_states = Arc::new(HealthStates::new());
+        let details_states = HostDetailsState::new(&config.retention);
+        let scheduler = Arc::new(Scheduler::new(config.scheduler.clone()));
+        let agent = AgentBinary::load(&config.agent).unwrap_or_else(|e| {
+            log::warn!("⚠️ Agent disabled, using shell collectors: {e:#}");
+            None
+        });
+        let executor = JobGroupExecutor::new(db.clone(), scheduler.clone()).with_agent(agent);
+        let details_refresh = watch::channel(details_interval(&config)).0;
         Self {
             running: false,
             event_stream: EventStream::new(),
             mode: AppMode::List,
+            config,
             db,
+            scheduler,
+            executor,
             ssh_hosts: Arc::new(Mutex::new(ssh_hosts)),
             cpu_states,
             mem_states,
             disk_states,
+            session_states,
+            package_states,
+            latency_states,
+            health_states,
             details_states,
             table_height: 0,
             table_state: TableState::default().with_selected(Some(0)),
             selected_id,
+            focused_id: None,
+            details_refresh,
             vertical_scroll: 0,
             vertical_scroll_state: ScrollbarState::new(0),
             search_query: String::new(),
@@ -126,30 +214,33 @@ impl App {
     }
 
     pub async fn run(mut self, mut terminal: DefaultTerminal) -> Result<()> {
-        let executor = JobGroupExecutor::new(self.db.clone());
-
-        {
-            let hosts = self.ssh_hosts.lock().await;
-            for (host_id, host) in hosts.iter() {
-                let group = JobGroup {
-                    name: host_id.clone(),
-                    interval: std::time::Duration::from_secs(30),
-                    host: host.clone(),
-                    jobs: vec![JobKind::Cpu, JobKind::Mem, JobKind::Disk, JobKind::Gpu],
-                };
-
-                executor.register_group(group).await;
-            }
-        }
+        self.register_job_groups().await;
+        let (list_executor, details_executor) = self.register_status_update_jobs().await;
+        let compactor = Compactor::start(self.db.clone(), self.config.retention.clone());
 
-        executor.run_all().await;
-        let _status_executor = self.register_status_update_jobs().await;
+        let (signal_tx, signal_rx) = watch::channel(false);
+        tokio::spawn(async move {
+            shutdown_signal().await;
+            let _ = signal_tx.send(true);
+        });
 
         self.running = true;
         while self.running {
+            self.sync_focus().await;
+            self.sync_details_refresh().await;
             terminal.draw(|frame| self.draw(frame))?;
             self.handle_crossterm_events().await?;
+            if *signal_rx.borrow() {
+                self.running = false;
+            }
         }
+
+        tokio::join!(
+            self.executor.shutdown(SHUTDOWN_GRACE),
+            list_executor.shutdown(SHUTDOWN_GRACE),
+            details_executor.shutdown(SHUTDOWN_GRACE),
+            compactor.shutdown(SHUTDOWN_GRACE),
+        );
         Ok(())
     }
 
@@ -157,6 +248,8 @@ impl App {
         match self.mode {
             AppMode::List | AppMode::Search => render_list(self, frame),
             AppMode::Details => render_details(self, frame),
+            AppMode::Scheduler => render_scheduler(self, frame),
+            AppMode::Diagnostics => render_diagnostics(self, frame),
         }
     }
 
@@ -181,6 +274,11 @@ impl App {
     }
 
     fn on_key_event(&mut self, key: KeyEvent) {
+        // Raw mode swallows SIGINT, so Ctrl-C arrives as a key.
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.running = false;
+            return;
+        }
         match self.mode {
             AppMode::List => match key.code {
                 KeyCode::Char('/') => {
@@ -192,6 +290,12 @@ impl App {
                         self.mode = AppMode::Details;
                     }
                 }
+                KeyCode::Char('s') => {
+                    self.mode = AppMode::Scheduler;
+                }
+                KeyCode::Char('d') => {
+                    self.mode = AppMode::Diagnostics;
+                }
                 KeyCode::Char('j') | KeyCode::Down => {
                     self.vertical_scroll = self.vertical_scroll.saturating_add(1);
                     handle_list_key(self, key);
@@ -227,45 +331,102 @@ impl App {
                 _ => {}
             },
             AppMode::Details => handle_details_key(self, key),
+            AppMode::Scheduler => handle_scheduler_key(self, key),
+            AppMode::Diagnostics => handle_diagnostics_key(self, key),
         }
     }
 
+    /// Moves fast polling to the selected host.
+    async fn sync_focus(&mut self) {
+        if self.focused_id == self.selected_id {
+            return;
+        }
+        if let Some(previous) = self.focused_id.take() {
+            self.executor
+                .send(&previous, GroupCommand::Focus(false))
+                .await;
+        }
+        if let Some(selected) = &self.selected_id {
+            self.executor
+                .send(selected, GroupCommand::Focus(true))
+                .await;
+        }
+        self.focused_id = self.selected_id.clone();
+    }
+
+    /// Reloads the details view every second while the selected host streams.
+    async fn sync_details_refresh(&self) {
+        let streaming = match &self.selected_id {
+            Some(id) => self.executor.is_streaming(id).await,
+            None => false,
+        };
+        let interval = if streaming {
+            Duration::from_secs(STREAM_INTERVAL_SECS)
+        } else {
+            details_interval(&self.config)
+        };
+        self.details_refresh.send_if_modified(|current| {
+            let changed = *current != interval;
+            *current = interval;
+            changed
+        });
+    }
+
     pub fn update_selected_id_from_table(&mut self) {
-        if let Some(index) = self.table_state.selected() {
-            if index < self.visible_hosts.len() {
-                let (id, _) = &self.visible_hosts[index];
-                self.selected_id = Some(id.clone());
-            }
+        if let Some(index) = self.table_state.selected()
+            && index < self.visible_hosts.len()
+        {
+            let (id, _) = &self.visible_hosts[index];
+            self.selected_id = Some(id.clone());
         }
     }
 
     pub async fn register_job_groups(&self) {
-        let executor = JobGroupExecutor::new(self.db.clone());
+        let registry = CollectorRegistry::with_builtins(&self.config);
 
         let hosts = self.ssh_hosts.lock().await;
         for (host_id, host) in hosts.iter() {
+            let mut jobs = registry.select(&[
+                "cpu", "mem", "disk", "gpu", "session", "ports", "facts", "packages",
+            ]);
+            jobs.extend(
+                self.config
+                    .collectors
+                    .iter()
+                    .filter(|config| config.applies_to(host_id))
+                    .filter_map(|config| registry.get(&format!("custom:{}", config.name))),
+            );
+
             let group = JobGroup {
                 name: host_id.clone(),
-                interval: std::time::Duration::from_secs(60),
                 host: host.clone(),
-                jobs: vec![JobKind::Cpu, JobKind::Mem, JobKind::Disk, JobKind::Gpu],
+                jobs,
             };
 
-            executor.register_group(group).await;
+            self.executor.register_group(group).await;
         }
 
-        executor.run_all().await;
+        self.executor.run_all().await;
     }
 
-    pub async fn register_status_update_jobs(&self) {
+    pub async fn register_status_update_jobs(
+        &self,
+    ) -> (
+        StatesJobExecutor<ListSshJobKind>,
+        StatesJobExecutor<DetailsJobKind>,
+    ) {
         let list_executor = StatesJobExecutor::new(self.db.clone());
         let list_job_group = StatesJobGroup {
             name: "list_view".to_string(),
-            interval: Duration::from_secs(5),
+            interval: watch::channel(Duration::from_secs(5)).1,
             jobs: vec![
                 ListSshJobKind::Cpu(self.cpu_states.clone()),
                 ListSshJobKind::Mem(self.mem_states.clone()),
                 ListSshJobKind::Disk(self.disk_states.clone()),
+                ListSshJobKind::Session(self.session_states.clone()),
+                ListSshJobKind::Packages(self.package_states.clone()),
+                ListSshJobKind::Latency(self.latency_states.clone()),
+                ListSshJobKind::Health(self.health_states.clone()),
             ],
         };
 
@@ -275,7 +436,7 @@ impl App {
         let details_executor = StatesJobExecutor::new(self.db.clone());
         let details_job_group = StatesJobGroup {
             name: "details_view".to_string(),
-            interval: Duration::from_secs(5),
+            interval: self.details_refresh.subscribe(),
             jobs: vec![
                 DetailsJobKind::Cpu(self.details_states.cpu.clone()),
                 DetailsJobKind::CpuTimeline(self.details_states.cpu_timeline.clone()),
@@ -283,10 +444,18 @@ impl App {
                 DetailsJobKind::MemTimeline(self.details_states.mem_timeline.clone()),
                 DetailsJobKind::Disk(self.details_states.disk.clone()),
                 DetailsJobKind::Gpu(self.details_states.gpu.clone()),
+                DetailsJobKind::Session(self.details_states.session.clone()),
+                DetailsJobKind::Ports(self.details_states.ports.clone()),
+                DetailsJobKind::Facts(self.details_states.facts.clone()),
+                DetailsJobKind::Custom(self.details_states.custom.clone()),
+                DetailsJobKind::Capabilities(self.details_states.capabilities.clone()),
+                DetailsJobKind::Net(self.details_states.net.clone()),
             ],
         };
 
         details_executor.register_group(details_job_group).await;
         details_executor.run_all().await;
+
+        (list_executor, details_executor)
     }
 }
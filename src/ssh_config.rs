@@ -18,6 +18,8 @@ pub struct SshHostInfo {
     pub port: u16,
     pub user: String,
     pub identity_file: String,
+    /// First hop of `ProxyJump`, used to group hosts behind the same bastion.
+    pub proxy_jump: Option<String>,
 }
 
 pub type SharedSshHosts = Arc<Mutex<HashMap<String, SshHostInfo>>>;
@@ -30,7 +32,10 @@ pub fn load_ssh_configs() -> Result<HashMap<String, SshHostInfo>> {
         .join(".ssh/config");
 
     let mut reader = BufReader::new(File::open(path)?);
-    let config = SshConfig::default().parse(&mut reader, ParseRule::STRICT)?;
+    let config = SshConfig::default().parse(
+        &mut reader,
+        ParseRule::STRICT | ParseRule::ALLOW_UNSUPPORTED_FIELDS,
+    )?;
 
     let hosts = config
         .get_hosts()
@@ -61,6 +66,15 @@ pub fn load_ssh_configs() -> Result<HashMap<String, SshHostInfo>> {
                 .map(|pathbuf| pathbuf.to_string_lossy().into_owned())
                 .unwrap_or_else(|| PLACEHOLDER_IDENTITY_FILE.into());
 
+            let proxy_jump = host
+                .params
+                .unsupported_fields
+                .get("proxyjump")
+                .and_then(|args| args.first())
+                .and_then(|hops| hops.split(',').next())
+                .filter(|hop| !hop.eq_ignore_ascii_case("none"))
+                .map(str::to_string);
+
             let hash_input = format!("{}:{}:{}", name, ip, port);
             let id = format!("{:x}", md5::compute(hash_input));
 
@@ -73,6 +87,7 @@ pub fn load_ssh_configs() -> Result<HashMap<String, SshHostInfo>> {
                     port,
                     user,
                     identity_file,
+                    proxy_jump,
                 },
             ))
         })
@@ -0,0 +1,240 @@
+use crate::App;
+use crate::tui::list_ssh::states::{HealthSnapshot, LatencySnapshot};
+use crate::tui::list_ssh::themed_table::TableColors;
+use futures::executor::block_on;
+use ratatui::prelude::*;
+use ratatui::text::Line;
+use ratatui::widgets::*;
+
+pub fn format_millis(ms: u64) -> String {
+    if ms >= 1000 {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    } else {
+        format!("{}ms", ms)
+    }
+}
+
+fn success_rate(health: &HealthSnapshot) -> Option<f64> {
+    (health.cycles > 0).then(|| health.ok as f64 * 100.0 / health.cycles as f64)
+}
+
+fn rate_color(rate: Option<f64>) -> Color {
+    match rate {
+        None => Color::Gray,
+        Some(rate) if rate >= 99.0 => Color::Green,
+        Some(rate) if rate >= 90.0 => Color::Yellow,
+        Some(_) => Color::Red,
+    }
+}
+
+/// Every host's last hour folded into one, weighting averages by cycles.
+fn fleet_health<'a>(hosts: impl IntoIterator<Item = &'a HealthSnapshot>) -> HealthSnapshot {
+    let mut fleet = HealthSnapshot::default();
+    let (mut latency_sum, mut latency_cycles) = (0.0, 0);
+    for host in hosts {
+        fleet.cycles += host.cycles;
+        fleet.ok += host.ok;
+        fleet.timed_out += host.timed_out;
+        fleet.avg_queue_lag_ms += host.avg_queue_lag_ms * host.cycles as f64;
+        fleet.max_queue_lag_ms = fleet.max_queue_lag_ms.max(host.max_queue_lag_ms);
+        if let Some(avg) = host.avg_total_ms {
+            latency_sum += avg * host.ok as f64;
+            latency_cycles += host.ok;
+        }
+    }
+    if fleet.cycles > 0 {
+        fleet.avg_queue_lag_ms /= fleet.cycles as f64;
+    }
+    fleet.avg_total_ms = (latency_cycles > 0).then(|| latency_sum / latency_cycles as f64);
+    fleet
+}
+
+fn clock_cell(latency: Option<&LatencySnapshot>, max_skew_secs: f64) -> Cell<'static> {
+    let Some((latency, offset_ms)) = latency.and_then(|l| Some((l, l.clock_offset_ms?))) else {
+        return Cell::from("-");
+    };
+    let text = format!("{:+.2}s", offset_ms / 1000.0);
+    match latency.clock_skew(max_skew_secs) {
+        Some(_) => Cell::from(format!("{text} ⚠")).style(Style::default().fg(Color::Red)),
+        None => Cell::from(text),
+    }
+}
+
+fn last_cycle_cell(latency: Option<&LatencySnapshot>) -> Cell<'static> {
+    let Some(latency) = latency else {
+        return Cell::from("-");
+    };
+    match (latency.status.as_str(), latency.total_ms()) {
+        ("ok", Some(total)) => {
+            let text = if latency.jobs_failed > 0 {
+                format!("{} ({} failed)", format_millis(total), latency.jobs_failed)
+            } else {
+                format_millis(total)
+            };
+            Cell::from(text).style(Style::default().fg(if latency.jobs_failed > 0 {
+                Color::Yellow
+            } else {
+                Color::Green
+            }))
+        }
+        ("timed_out", _) => Cell::from("timed out").style(Style::default().fg(Color::Red)),
+        _ => Cell::from("failed").style(Style::default().fg(Color::Red)),
+    }
+}
+
+pub fn render(app: &mut App, frame: &mut Frame) {
+    let colors = TableColors::default();
+    let health = block_on(app.health_states.snapshot_map());
+    let latency = block_on(app.latency_states.snapshot_map());
+    let scheduler = app.scheduler.snapshot();
+    let max_skew_secs = app.config.clock.max_skew_secs;
+    let skewed = latency
+        .values()
+        .filter(|l| l.clock_skew(max_skew_secs).is_some())
+        .count();
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Length(3), // Summary
+            Constraint::Min(0),    // Table
+            Constraint::Length(3), // Footer
+        ])
+        .split(frame.area());
+
+    let title = Paragraph::new("Diagnostics (last hour)")
+        .style(Style::default().add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center);
+    frame.render_widget(title, chunks[0]);
+
+    let fleet = fleet_health(health.values());
+    let rate = success_rate(&fleet);
+    let summary = Line::from(vec![
+        Span::raw(format!("Cycles: {} • Success: ", fleet.cycles)),
+        Span::styled(
+            rate.map_or("-".to_string(), |rate| format!("{rate:.1}%")),
+            Style::default().fg(rate_color(rate)),
+        ),
+        Span::raw(" • Timeouts: "),
+        Span::styled(
+            fleet.timed_out.to_string(),
+            Style::default().fg(if fleet.timed_out > 0 {
+                Color::Yellow
+            } else {
+                Color::Green
+            }),
+        ),
+        Span::raw(format!(
+            " • Avg latency: {} • Queue lag: avg {}, max {} • Running: {}/{} • Queued: {}",
+            fleet
+                .avg_total_ms
+                .map_or("-".to_string(), |ms| format_millis(ms as u64)),
+            format_millis(fleet.avg_queue_lag_ms as u64),
+            format_millis(fleet.max_queue_lag_ms),
+            scheduler.running,
+            scheduler.max_concurrent,
+            scheduler.queued
+        )),
+        Span::raw(" • Skewed clocks: "),
+        Span::styled(
+            skewed.to_string(),
+            Style::default().fg(if skewed > 0 { Color::Red } else { Color::Green }),
+        ),
+    ]);
+    frame.render_widget(Paragraph::new(summary), chunks[1]);
+
+    let mut entries: Vec<(String, Option<HealthSnapshot>, Option<LatencySnapshot>)> = app
+        .visible_hosts
+        .iter()
+        .map(|(id, info)| {
+            (
+                info.name.clone(),
+                health.get(id).cloned(),
+                latency.get(id).cloned(),
+            )
+        })
+        .collect();
+    // Least healthy hosts first.
+    entries.sort_by(|(a_name, a, _), (b_name, b, _)| {
+        let rate = |h: &Option<HealthSnapshot>| h.as_ref().and_then(success_rate).unwrap_or(100.0);
+        rate(a).total_cmp(&rate(b)).then_with(|| a_name.cmp(b_name))
+    });
+
+    let rows = entries.iter().map(|(name, health, latency)| {
+        let health = health.clone().unwrap_or_default();
+        let rate = success_rate(&health);
+        Row::new(vec![
+            Cell::from(name.clone()),
+            last_cycle_cell(latency.as_ref()),
+            Cell::from(health.cycles.to_string()),
+            Cell::from(rate.map_or("-".to_string(), |rate| format!("{rate:.1}%")))
+                .style(Style::default().fg(rate_color(rate))),
+            Cell::from(health.timed_out.to_string()),
+            Cell::from(
+                health
+                    .avg_total_ms
+                    .map_or("-".to_string(), |ms| format_millis(ms as u64)),
+            ),
+            Cell::from(format_millis(health.avg_queue_lag_ms as u64)),
+            Cell::from(format_millis(health.max_queue_lag_ms)),
+            clock_cell(latency.as_ref(), max_skew_secs),
+        ])
+        .style(
+            Style::default()
+                .fg(colors.row_fg)
+                .bg(colors.normal_row_color),
+        )
+    });
+
+    let header = Row::new(vec![
+        "Name",
+        "Last cycle",
+        "Cycles",
+        "Success",
+        "Timeouts",
+        "Avg latency",
+        "Avg lag",
+        "Max lag",
+        "Clock",
+    ])
+    .style(
+        Style::default()
+            .fg(colors.header_fg)
+            .bg(colors.header_bg)
+            .add_modifier(Modifier::BOLD),
+    );
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(24),
+            Constraint::Length(20),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(12),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(12),
+        ],
+    )
+    .header(header)
+    .block(Block::default().borders(Borders::ALL).title("Hosts"));
+    frame.render_widget(table, chunks[2]);
+
+    let footer = Paragraph::new(vec![Line::from("ESC/d: Back")])
+        .alignment(Alignment::Center)
+        .style(
+            Style::default()
+                .fg(colors.row_fg)
+                .bg(colors.normal_row_color),
+        )
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Controls")
+                .border_style(Style::default().fg(colors.footer_border_color)),
+        );
+    frame.render_widget(footer, chunks[3]);
+}
@@ -0,0 +1,38 @@
+use crate::App;
+use crate::backend::jobs::executor::GroupCommand;
+use crate::backend::jobs::scheduler::ScheduleState;
+use crossterm::event::{KeyCode, KeyEvent};
+use futures::executor::block_on;
+
+const MIN_INTERVAL_SCALE: f64 = 0.25;
+const MAX_INTERVAL_SCALE: f64 = 8.0;
+
+/// Polling controls for the selected host, shared by the list and details
+/// views. Returns true if the key was handled.
+pub fn handle_key(app: &mut App, key: KeyEvent) -> bool {
+    let Some(host_id) = app.selected_id.clone() else {
+        return false;
+    };
+    let schedule = app
+        .scheduler
+        .snapshot()
+        .hosts
+        .remove(&host_id)
+        .unwrap_or_default();
+    let scale = schedule.interval_scale.unwrap_or(1.0);
+
+    let command = match key.code {
+        KeyCode::Char('r') => GroupCommand::RunNow,
+        KeyCode::Char('p') if schedule.state == ScheduleState::Paused => GroupCommand::Resume,
+        KeyCode::Char('p') => GroupCommand::Pause,
+        KeyCode::Char('+') => GroupCommand::ScaleIntervals((scale * 2.0).min(MAX_INTERVAL_SCALE)),
+        KeyCode::Char('-') => GroupCommand::ScaleIntervals((scale / 2.0).max(MIN_INTERVAL_SCALE)),
+        KeyCode::Char('X') => {
+            block_on(app.executor.unregister(&host_id));
+            return true;
+        }
+        _ => return false,
+    };
+    block_on(app.executor.send(&host_id, command));
+    true
+}
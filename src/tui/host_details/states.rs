@@ -1,6 +1,17 @@
 use crate::backend::db::cpu::queries as cpu_queries;
+use crate::backend::db::custom::queries as custom_queries;
 use crate::backend::db::disk::queries as disk_queries;
-use crate::backend::db::mem::queries as mem_queries;
+use crate::backend::db::net::queries as net_queries;
+use crate::backend::db::rollup::queries::{RollupTimelineRow, fetch_rollup_timeline};
+use crate::backend::jobs::capabilities::{CapabilitiesCollector, HostCapabilities};
+use crate::backend::jobs::disk::DiskCollector;
+use crate::backend::jobs::facts::FactsCollector;
+use crate::backend::jobs::gpu::GpuCollector;
+use crate::backend::jobs::mem::MemCollector;
+use crate::backend::jobs::ports::{ListeningPort, PortsCollector};
+use crate::backend::jobs::session::{LastLoginInfo, SessionCollector, SessionInfo};
+use crate::backend::retention::{Resolution, TimelineRange, now_secs};
+use crate::config::RetentionConfig;
 use crate::tui::states_update::StateJob;
 use anyhow::Result;
 use rusqlite::Connection;
@@ -16,6 +27,34 @@ pub struct CpuDetailSnapshot {
     pub per_core: Vec<f32>,
 }
 
+/// How far back the CPU and memory timelines reach, switched from the
+/// details view.
+#[derive(Debug, Clone, Default)]
+pub struct TimelineWindow {
+    range: Arc<RwLock<TimelineRange>>,
+    retention: RetentionConfig,
+}
+
+impl TimelineWindow {
+    pub fn new(retention: RetentionConfig) -> Self {
+        Self {
+            range: Arc::new(RwLock::new(TimelineRange::default())),
+            retention,
+        }
+    }
+
+    pub async fn get(&self) -> (TimelineRange, Resolution) {
+        let range = *self.range.read().await;
+        (range, range.resolution(&self.retention))
+    }
+
+    /// Switches to the next longer range, wrapping around to the shortest.
+    pub async fn cycle(&self) {
+        let mut range = self.range.write().await;
+        *range = range.next();
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct CpuTimelineSnapshot {
     pub timeline_data: Vec<(String, f32, String)>, // (host_id, usage_percent, timestamp)
@@ -24,28 +63,33 @@ pub struct CpuTimelineSnapshot {
 #[derive(Debug, Clone)]
 pub struct CpuTimelineStates {
     data: Arc<RwLock<CpuTimelineSnapshot>>,
+    window: TimelineWindow,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct MemTimelineSnapshot {
     pub timeline_data: Vec<(String, f32, String)>, // (host_id, used_percent, timestamp)
+    pub stacked_cache_data: Vec<(String, f32, String)>, // (host_id, used + cache percent, timestamp)
+    pub stacked_swap_data: Vec<(String, f32, String)>, // (host_id, used + cache + swap percent, timestamp)
 }
 
 #[derive(Debug, Clone)]
 pub struct MemTimelineStates {
     data: Arc<RwLock<MemTimelineSnapshot>>,
+    window: TimelineWindow,
 }
 
 impl Default for MemTimelineStates {
     fn default() -> Self {
-        Self::new()
+        Self::new(TimelineWindow::default())
     }
 }
 
 impl MemTimelineStates {
-    pub fn new() -> Self {
+    pub fn new(window: TimelineWindow) -> Self {
         Self {
             data: Arc::new(RwLock::new(MemTimelineSnapshot::default())),
+            window,
         }
     }
 
@@ -57,30 +101,75 @@ impl MemTimelineStates {
         &self,
         conn: &Arc<tokio::sync::Mutex<rusqlite::Connection>>,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        use crate::backend::db::mem::queries::fetch_mem_usage_timeline;
-
-        let timeline_rows = fetch_mem_usage_timeline(conn).await?;
-        let timeline_data = timeline_rows
-            .into_iter()
-            .map(|row| (row.host_id, row.used_percent, row.timestamp))
-            .collect();
+        use crate::backend::db::mem::queries::{MemTimelineRow, fetch_mem_usage_timeline};
+
+        let (range, resolution) = self.window.get().await;
+        let since = now_secs() - range.secs();
+        let timeline_rows = match resolution.bucket_secs() {
+            None => fetch_mem_usage_timeline(conn, since).await?,
+            Some(bucket) => {
+                let cache =
+                    by_bucket(fetch_rollup_timeline(conn, "mem.cache", bucket, since).await?);
+                let swap = by_bucket(fetch_rollup_timeline(conn, "mem.swap", bucket, since).await?);
+                fetch_rollup_timeline(conn, "mem.used", bucket, since)
+                    .await?
+                    .into_iter()
+                    .map(|row| {
+                        let key = (row.host_id, row.timestamp);
+                        MemTimelineRow {
+                            used_percent: row.avg,
+                            cache_percent: cache.get(&key).copied().unwrap_or(0.0),
+                            swap_percent: swap.get(&key).copied().unwrap_or(0.0),
+                            host_id: key.0,
+                            timestamp: key.1,
+                        }
+                    })
+                    .collect()
+            }
+        };
+        let mut snapshot = MemTimelineSnapshot::default();
+        for row in timeline_rows {
+            let with_cache = row.used_percent + row.cache_percent;
+            snapshot.timeline_data.push((
+                row.host_id.clone(),
+                row.used_percent,
+                row.timestamp.clone(),
+            ));
+            snapshot.stacked_cache_data.push((
+                row.host_id.clone(),
+                with_cache,
+                row.timestamp.clone(),
+            ));
+            snapshot.stacked_swap_data.push((
+                row.host_id,
+                with_cache + row.swap_percent,
+                row.timestamp,
+            ));
+        }
 
-        let snapshot = MemTimelineSnapshot { timeline_data };
         *self.data.write().await = snapshot;
         Ok(())
     }
 }
 
+/// Rollup values keyed by host and bucket, to line series up with each other.
+fn by_bucket(rows: Vec<RollupTimelineRow>) -> HashMap<(String, String), f32> {
+    rows.into_iter()
+        .map(|row| ((row.host_id, row.timestamp), row.avg))
+        .collect()
+}
+
 impl Default for CpuTimelineStates {
     fn default() -> Self {
-        Self::new()
+        Self::new(TimelineWindow::default())
     }
 }
 
 impl CpuTimelineStates {
-    pub fn new() -> Self {
+    pub fn new(window: TimelineWindow) -> Self {
         Self {
             data: Arc::new(RwLock::new(CpuTimelineSnapshot::default())),
+            window,
         }
     }
 
@@ -94,11 +183,20 @@ impl CpuTimelineStates {
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         use crate::backend::db::cpu::queries::fetch_cpu_usage_timeline;
 
-        let timeline_rows = fetch_cpu_usage_timeline(conn).await?;
-        let timeline_data = timeline_rows
-            .into_iter()
-            .map(|row| (row.host_id, row.usage_percent, row.timestamp))
-            .collect();
+        let (range, resolution) = self.window.get().await;
+        let since = now_secs() - range.secs();
+        let timeline_data = match resolution.bucket_secs() {
+            None => fetch_cpu_usage_timeline(conn, since)
+                .await?
+                .into_iter()
+                .map(|row| (row.host_id, row.usage_percent, row.timestamp))
+                .collect(),
+            Some(bucket) => fetch_rollup_timeline(conn, "cpu.usage", bucket, since)
+                .await?
+                .into_iter()
+                .map(|row| (row.host_id, row.avg, row.timestamp))
+                .collect(),
+        };
 
         let snapshot = CpuTimelineSnapshot { timeline_data };
         *self.data.write().await = snapshot;
@@ -111,6 +209,11 @@ pub struct MemDetailSnapshot {
     pub total_mb: u64,
     pub used_mb: u64,
     pub free_mb: u64,
+    pub available_mb: u64,
+    pub buffers_cache_mb: u64,
+    pub shared_mb: u64,
+    pub swap_total_mb: u64,
+    pub swap_used_mb: u64,
     pub used_percent: f32,
 }
 
@@ -123,20 +226,44 @@ pub struct DiskDetailSnapshot {
 
 #[derive(Debug, Clone, Default)]
 pub struct GpuDetailSnapshot {
+    pub key: String,
     pub index: u32,
+    pub vendor: Option<String>,
+    pub uuid: Option<String>,
+    pub pci_bus_id: Option<String>,
+    pub parent_uuid: Option<String>,
+    pub mig_device_index: Option<u32>,
     pub name: String,
     pub memory_total_mb: Option<u64>,
     pub memory_used_mb: Option<u64>,
     pub temperature_c: Option<f32>,
+    pub utilization_gpu: Option<f32>,
+    pub utilization_memory: Option<f32>,
+    pub power_draw_w: Option<f32>,
+    pub power_limit_w: Option<f32>,
+    pub processes: Vec<GpuProcessSnapshot>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GpuProcessSnapshot {
+    pub pid: u32,
+    pub user: String,
+    pub process_name: String,
+    pub used_memory_mb: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
 pub struct DiskVolumeSnapshot {
+    pub device: Option<String>,
+    pub fs_type: Option<String>,
     pub mount_point: String,
     pub total_mb: u64,
     pub used_mb: u64,
     pub available_mb: u64,
     pub used_percent: f32,
+    pub inodes_total: Option<u64>,
+    pub inodes_used: Option<u64>,
+    pub inodes_used_percent: Option<f32>,
 }
 
 #[derive(Debug, Clone)]
@@ -217,7 +344,7 @@ impl MemDetailStates {
     }
 
     pub async fn update_from_db(&self, conn: &Arc<Mutex<Connection>>) -> Result<()> {
-        let rows = mem_queries::fetch_latest_mem_all(conn).await?;
+        let rows = MemCollector.query(conn).await?;
         let mut map = self.data.write().await;
         map.clear();
         for row in rows {
@@ -227,6 +354,11 @@ impl MemDetailStates {
                     total_mb: row.total_mb,
                     used_mb: row.used_mb,
                     free_mb: row.free_mb,
+                    available_mb: row.available_mb,
+                    buffers_cache_mb: row.buffers_cache_mb,
+                    shared_mb: row.shared_mb,
+                    swap_total_mb: row.swap_total_mb,
+                    swap_used_mb: row.swap_used_mb,
                     used_percent: row.used_percent,
                 },
             );
@@ -269,18 +401,23 @@ impl DiskDetailStates {
         let volumes: Vec<DiskVolumeSnapshot> = rows
             .into_iter()
             .map(|row| DiskVolumeSnapshot {
+                device: row.device,
+                fs_type: row.fs_type,
                 mount_point: row.mount_point,
                 total_mb: row.total_mb,
                 used_mb: row.used_mb,
                 available_mb: row.available_mb,
                 used_percent: row.used_percent,
+                inodes_total: row.inodes_total,
+                inodes_used: row.inodes_used,
+                inodes_used_percent: row.inodes_used_percent,
             })
             .collect();
         Ok(volumes)
     }
 
     pub async fn update_from_db(&self, conn: &Arc<Mutex<Connection>>) -> Result<()> {
-        let rows = disk_queries::fetch_latest_disk_all(conn).await?;
+        let rows = DiskCollector::default().query(conn).await?;
         let mut map = self.data.write().await;
         map.clear();
         for row in rows {
@@ -317,17 +454,47 @@ impl GpuDetailStates {
     }
 
     pub async fn update_from_db(&self, conn: &Arc<Mutex<Connection>>) -> Result<()> {
-        let rows = crate::backend::db::gpu::queries::fetch_latest_gpu_all(conn).await?;
+        let rows = GpuCollector.query(conn).await?;
+        let process_rows =
+            crate::backend::db::gpu::queries::fetch_latest_gpu_processes_all(conn).await?;
         let mut map = self.data.write().await;
         map.clear();
 
         for row in rows {
+            let processes = process_rows
+                .iter()
+                .filter(|p| {
+                    p.host_id == row.host_id
+                        && match (&p.gpu_uuid, &row.gpu_uuid) {
+                            (Some(a), Some(b)) => a == b,
+                            _ => p.gpu_index == row.gpu_index,
+                        }
+                })
+                .map(|p| GpuProcessSnapshot {
+                    pid: p.pid,
+                    user: p.user.clone(),
+                    process_name: p.process_name.clone(),
+                    used_memory_mb: p.used_memory_mb,
+                })
+                .collect();
+
             let gpu_snapshot = GpuDetailSnapshot {
+                key: row.gpu_key,
                 index: row.gpu_index,
+                vendor: row.vendor,
+                uuid: row.gpu_uuid,
+                pci_bus_id: row.pci_bus_id,
+                parent_uuid: row.parent_uuid,
+                mig_device_index: row.mig_device_index,
                 name: row.name,
                 memory_total_mb: row.memory_total_mb,
                 memory_used_mb: row.memory_used_mb,
                 temperature_c: row.temperature_c,
+                utilization_gpu: row.utilization_gpu,
+                utilization_memory: row.utilization_memory,
+                power_draw_w: row.power_draw_w,
+                power_limit_w: row.power_limit_w,
+                processes,
             };
 
             map.entry(row.host_id)
@@ -336,7 +503,9 @@ impl GpuDetailStates {
         }
 
         for gpu_list in map.values_mut() {
-            gpu_list.sort_by_key(|gpu| gpu.index);
+            gpu_list.sort_by(|a, b| {
+                (a.index, a.mig_device_index, &a.key).cmp(&(b.index, b.mig_device_index, &b.key))
+            });
         }
 
         Ok(())
@@ -347,6 +516,378 @@ impl GpuDetailStates {
     }
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct SessionDetailSnapshot {
+    pub sessions: Vec<SessionInfo>,
+    pub last_logins: Vec<LastLoginInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionDetailStates {
+    data: Arc<RwLock<HashMap<String, SessionDetailSnapshot>>>,
+}
+
+impl Default for SessionDetailStates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionDetailStates {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub async fn get(&self, host_id: &str) -> Option<SessionDetailSnapshot> {
+        self.data.read().await.get(host_id).cloned()
+    }
+
+    pub async fn update_from_db(&self, conn: &Arc<Mutex<Connection>>) -> Result<()> {
+        let rows = SessionCollector.query(conn).await?;
+        let mut map = self.data.write().await;
+        map.clear();
+        for row in rows {
+            let mut sessions = row.sessions;
+            sessions.sort_by_key(|s| s.idle_seconds.unwrap_or(u64::MAX));
+            map.insert(
+                row.host_id,
+                SessionDetailSnapshot {
+                    sessions,
+                    last_logins: row.last_logins,
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortChange {
+    Unchanged,
+    Appeared,
+    Disappeared,
+}
+
+#[derive(Debug, Clone)]
+pub struct PortSnapshot {
+    pub listener: ListeningPort,
+    pub change: PortChange,
+}
+
+#[derive(Debug, Clone)]
+pub struct PortDetailStates {
+    data: Arc<RwLock<HashMap<String, Vec<PortSnapshot>>>>,
+}
+
+impl Default for PortDetailStates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PortDetailStates {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub async fn get(&self, host_id: &str) -> Option<Vec<PortSnapshot>> {
+        self.data.read().await.get(host_id).cloned()
+    }
+
+    pub async fn update_from_db(&self, conn: &Arc<Mutex<Connection>>) -> Result<()> {
+        let rows = PortsCollector.query(conn).await?;
+        let mut map = self.data.write().await;
+        map.clear();
+        for row in rows {
+            let mut ports: Vec<PortSnapshot> = row
+                .listeners
+                .iter()
+                .map(|listener| {
+                    let is_new = row.previous_listeners.as_ref().is_some_and(|previous| {
+                        !previous.iter().any(|p| p.key() == listener.key())
+                    });
+                    PortSnapshot {
+                        listener: listener.clone(),
+                        change: if is_new {
+                            PortChange::Appeared
+                        } else {
+                            PortChange::Unchanged
+                        },
+                    }
+                })
+                .collect();
+
+            for previous in row.previous_listeners.unwrap_or_default() {
+                if !row.listeners.iter().any(|l| l.key() == previous.key()) {
+                    ports.push(PortSnapshot {
+                        listener: previous,
+                        change: PortChange::Disappeared,
+                    });
+                }
+            }
+
+            ports.sort_by(|a, b| {
+                (a.listener.port, &a.listener.protocol, &a.listener.address).cmp(&(
+                    b.listener.port,
+                    &b.listener.protocol,
+                    &b.listener.address,
+                ))
+            });
+            map.insert(row.host_id, ports);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HostFactsSnapshot {
+    pub os_name: String,
+    pub kernel_release: String,
+    pub arch: String,
+    pub cpu_model: String,
+    pub cpu_count: u32,
+    pub cpu_sockets: Option<u32>,
+    pub vendor: Option<String>,
+    pub product: Option<String>,
+    pub total_memory_mb: u64,
+    pub virtualization: Option<String>,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct HostFactsStates {
+    data: Arc<RwLock<HashMap<String, HostFactsSnapshot>>>,
+}
+
+impl Default for HostFactsStates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HostFactsStates {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub async fn get(&self, host_id: &str) -> Option<HostFactsSnapshot> {
+        self.data.read().await.get(host_id).cloned()
+    }
+
+    pub async fn update_from_db(&self, conn: &Arc<Mutex<Connection>>) -> Result<()> {
+        let rows = FactsCollector.query(conn).await?;
+        let mut map = self.data.write().await;
+        map.clear();
+        for row in rows {
+            map.insert(
+                row.host_id,
+                HostFactsSnapshot {
+                    os_name: row.os_name,
+                    kernel_release: row.kernel_release,
+                    arch: row.arch,
+                    cpu_model: row.cpu_model,
+                    cpu_count: row.cpu_count,
+                    cpu_sockets: row.cpu_sockets,
+                    vendor: row.vendor,
+                    product: row.product,
+                    total_memory_mb: row.total_memory_mb,
+                    virtualization: row.virtualization,
+                    updated_at: row.updated_at,
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HostCapabilitiesSnapshot {
+    pub capabilities: HostCapabilities,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct HostCapabilitiesStates {
+    data: Arc<RwLock<HashMap<String, HostCapabilitiesSnapshot>>>,
+}
+
+impl Default for HostCapabilitiesStates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HostCapabilitiesStates {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub async fn get(&self, host_id: &str) -> Option<HostCapabilitiesSnapshot> {
+        self.data.read().await.get(host_id).cloned()
+    }
+
+    pub async fn update_from_db(&self, conn: &Arc<Mutex<Connection>>) -> Result<()> {
+        let rows = CapabilitiesCollector.query(conn).await?;
+        let mut map = self.data.write().await;
+        map.clear();
+        for row in rows {
+            let host_id = row.host_id.clone();
+            let updated_at = row.updated_at.clone();
+            map.insert(
+                host_id,
+                HostCapabilitiesSnapshot {
+                    capabilities: HostCapabilities::from(row),
+                    updated_at,
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Throughput from the last streamed sample; only streaming hosts have one.
+#[derive(Debug, Clone, Default)]
+pub struct NetRateSnapshot {
+    pub rx_bytes_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct NetRateStates {
+    data: Arc<RwLock<HashMap<String, NetRateSnapshot>>>,
+}
+
+impl Default for NetRateStates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NetRateStates {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub async fn get(&self, host_id: &str) -> Option<NetRateSnapshot> {
+        self.data.read().await.get(host_id).cloned()
+    }
+
+    pub async fn update_from_db(&self, conn: &Arc<Mutex<Connection>>) -> Result<()> {
+        let rows = net_queries::fetch_latest_net_all(conn).await?;
+        let mut map = self.data.write().await;
+        map.clear();
+        for row in rows {
+            map.insert(
+                row.host_id,
+                NetRateSnapshot {
+                    rx_bytes_per_sec: row.rx_bytes_per_sec,
+                    tx_bytes_per_sec: row.tx_bytes_per_sec,
+                    updated_at: row.timestamp,
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+/// One user-defined collector on one host; every numeric key it reports is a
+/// line on the chart.
+#[derive(Debug, Clone, Default)]
+pub struct CustomChartSnapshot {
+    pub name: String,
+    pub units: Option<String>,
+    pub series: Vec<CustomSeriesSnapshot>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CustomSeriesSnapshot {
+    pub key: String,
+    pub points: Vec<(String, f32, String)>, // (host_id, value, timestamp)
+}
+
+#[derive(Debug, Clone)]
+pub struct CustomTimelineStates {
+    data: Arc<RwLock<HashMap<String, Vec<CustomChartSnapshot>>>>,
+}
+
+impl Default for CustomTimelineStates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CustomTimelineStates {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub async fn get(&self, host_id: &str) -> Vec<CustomChartSnapshot> {
+        self.data
+            .read()
+            .await
+            .get(host_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub async fn update_from_db(&self, conn: &Arc<Mutex<Connection>>) -> Result<()> {
+        let rows = custom_queries::fetch_custom_timeline(conn).await?;
+        let mut charts: HashMap<String, Vec<CustomChartSnapshot>> = HashMap::new();
+        for row in rows {
+            let name = row
+                .job_name
+                .strip_prefix("custom:")
+                .unwrap_or(&row.job_name)
+                .to_string();
+            let host_charts = charts.entry(row.host_id.clone()).or_default();
+            let index = match host_charts.iter().position(|c| c.name == name) {
+                Some(index) => index,
+                None => {
+                    host_charts.push(CustomChartSnapshot {
+                        name,
+                        units: row.sample.units.clone(),
+                        series: Vec::new(),
+                    });
+                    host_charts.len() - 1
+                }
+            };
+            let chart = &mut host_charts[index];
+            for (key, value) in row.sample.values {
+                let point = (row.host_id.clone(), value as f32, row.timestamp.clone());
+                match chart.series.iter_mut().find(|s| s.key == key) {
+                    Some(series) => series.points.push(point),
+                    None => chart.series.push(CustomSeriesSnapshot {
+                        key,
+                        points: vec![point],
+                    }),
+                }
+            }
+        }
+        for host_charts in charts.values_mut() {
+            host_charts.sort_by(|a, b| a.name.cmp(&b.name));
+            for chart in host_charts.iter_mut() {
+                chart.series.sort_by(|a, b| a.key.cmp(&b.key));
+            }
+        }
+
+        *self.data.write().await = charts;
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct HostDetailsState {
     pub cpu: Arc<CpuDetailStates>,
@@ -355,17 +896,32 @@ pub struct HostDetailsState {
     pub mem_timeline: Arc<MemTimelineStates>,
     pub disk: Arc<DiskDetailStates>,
     pub gpu: Arc<GpuDetailStates>,
+    pub session: Arc<SessionDetailStates>,
+    pub ports: Arc<PortDetailStates>,
+    pub facts: Arc<HostFactsStates>,
+    pub custom: Arc<CustomTimelineStates>,
+    pub capabilities: Arc<HostCapabilitiesStates>,
+    pub net: Arc<NetRateStates>,
+    pub timeline: TimelineWindow,
 }
 
 impl HostDetailsState {
-    pub fn new() -> Self {
+    pub fn new(retention: &RetentionConfig) -> Self {
+        let timeline = TimelineWindow::new(retention.clone());
         Self {
             cpu: Arc::new(CpuDetailStates::new()),
-            cpu_timeline: Arc::new(CpuTimelineStates::new()),
+            cpu_timeline: Arc::new(CpuTimelineStates::new(timeline.clone())),
             mem: Arc::new(MemDetailStates::new()),
-            mem_timeline: Arc::new(MemTimelineStates::new()),
+            mem_timeline: Arc::new(MemTimelineStates::new(timeline.clone())),
             disk: Arc::new(DiskDetailStates::new()),
             gpu: Arc::new(GpuDetailStates::new()),
+            session: Arc::new(SessionDetailStates::new()),
+            ports: Arc::new(PortDetailStates::new()),
+            facts: Arc::new(HostFactsStates::new()),
+            custom: Arc::new(CustomTimelineStates::new()),
+            capabilities: Arc::new(HostCapabilitiesStates::new()),
+            net: Arc::new(NetRateStates::new()),
+            timeline,
         }
     }
 }
@@ -378,6 +934,12 @@ pub enum DetailsJobKind {
     MemTimeline(Arc<MemTimelineStates>),
     Disk(Arc<DiskDetailStates>),
     Gpu(Arc<GpuDetailStates>),
+    Session(Arc<SessionDetailStates>),
+    Ports(Arc<PortDetailStates>),
+    Facts(Arc<HostFactsStates>),
+    Custom(Arc<CustomTimelineStates>),
+    Capabilities(Arc<HostCapabilitiesStates>),
+    Net(Arc<NetRateStates>),
 }
 
 #[async_trait::async_trait]
@@ -390,6 +952,12 @@ impl StateJob for DetailsJobKind {
             DetailsJobKind::MemTimeline(_) => "mem_timeline",
             DetailsJobKind::Disk(_) => "disk_detail",
             DetailsJobKind::Gpu(_) => "gpu_detail",
+            DetailsJobKind::Session(_) => "session_detail",
+            DetailsJobKind::Ports(_) => "ports_detail",
+            DetailsJobKind::Facts(_) => "host_facts",
+            DetailsJobKind::Custom(_) => "custom_timeline",
+            DetailsJobKind::Capabilities(_) => "host_capabilities",
+            DetailsJobKind::Net(_) => "net_rate",
         }
     }
 
@@ -407,6 +975,12 @@ impl StateJob for DetailsJobKind {
                 .map_err(|e| anyhow::anyhow!(e)),
             DetailsJobKind::Disk(state) => state.update_from_db(conn).await,
             DetailsJobKind::Gpu(state) => state.update_from_db(conn).await,
+            DetailsJobKind::Session(state) => state.update_from_db(conn).await,
+            DetailsJobKind::Ports(state) => state.update_from_db(conn).await,
+            DetailsJobKind::Facts(state) => state.update_from_db(conn).await,
+            DetailsJobKind::Custom(state) => state.update_from_db(conn).await,
+            DetailsJobKind::Capabilities(state) => state.update_from_db(conn).await,
+            DetailsJobKind::Net(state) => state.update_from_db(conn).await,
         }
     }
 }
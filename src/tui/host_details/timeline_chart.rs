@@ -9,6 +9,15 @@ pub struct TimelineChart<'a> {
     pub y_bounds: (f64, f64),
     pub y_unit: &'a str,
     pub color: Color,
+    pub label: Option<&'a str>,
+    pub series: Vec<TimelineSeries<'a>>,
+}
+
+/// An additional line drawn on top of the primary data, sharing its x axis.
+pub struct TimelineSeries<'a> {
+    pub label: &'a str,
+    pub data: Vec<(String, f32, String)>, // (host_id, value, timestamp)
+    pub color: Color,
 }
 
 impl<'a> TimelineChart<'a> {
@@ -20,6 +29,8 @@ impl<'a> TimelineChart<'a> {
             y_bounds: (0.0, 100.0),
             y_unit: "%",
             color: Color::Cyan,
+            label: None,
+            series: Vec::new(),
         }
     }
 
@@ -43,6 +54,21 @@ impl<'a> TimelineChart<'a> {
         self
     }
 
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn series(
+        mut self,
+        label: &'a str,
+        data: Vec<(String, f32, String)>,
+        color: Color,
+    ) -> Self {
+        self.series.push(TimelineSeries { label, data, color });
+        self
+    }
+
     pub fn render(&self, frame: &mut Frame, area: Rect) {
         if self.data.is_empty() {
             let no_data = Paragraph::new("Loading timeline data...")
@@ -78,13 +104,44 @@ impl<'a> TimelineChart<'a> {
 
         let x_labels = self.create_time_labels(&host_data_with_time, data_count);
 
-        let datasets = vec![
-            Dataset::default()
-                .marker(symbols::Marker::Braille)
-                .style(Style::default().fg(self.color))
-                .graph_type(GraphType::Line)
-                .data(&chart_data),
-        ];
+        let series_data: Vec<Vec<(f64, f64)>> = self
+            .series
+            .iter()
+            .map(|series| {
+                let points: Vec<_> = series
+                    .data
+                    .iter()
+                    .filter(|(id, _, _)| id == self.host_id)
+                    .collect();
+                points
+                    .iter()
+                    .rev()
+                    .enumerate()
+                    .map(|(i, &(_, value, _))| (i as f64, *value as f64))
+                    .collect()
+            })
+            .collect();
+
+        let mut primary = Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .style(Style::default().fg(self.color))
+            .graph_type(GraphType::Line)
+            .data(&chart_data);
+        if let Some(label) = self.label {
+            primary = primary.name(label);
+        }
+
+        let mut datasets = vec![primary];
+        for (series, data) in self.series.iter().zip(series_data.iter()) {
+            datasets.push(
+                Dataset::default()
+                    .name(series.label)
+                    .marker(symbols::Marker::Braille)
+                    .style(Style::default().fg(series.color))
+                    .graph_type(GraphType::Line)
+                    .data(data),
+            );
+        }
 
         let chart = Chart::new(datasets)
             .block(Block::default().title(self.title.to_string()))
@@ -1,11 +1,31 @@
+use crate::tui::host_control;
 use crate::{App, AppMode};
 use crossterm::event::KeyCode;
+use futures::executor::block_on;
 
 pub fn handle_key(app: &mut App, key: crossterm::event::KeyEvent) {
     match key.code {
         KeyCode::Esc | KeyCode::Char('q') => {
             app.mode = AppMode::List;
         }
-        _ => {}
+        KeyCode::Char('S') => {
+            if let Some(host_id) = &app.selected_id {
+                block_on(app.executor.toggle_stream(host_id));
+            }
+        }
+        KeyCode::Char('t') => {
+            block_on(app.details_states.timeline.cycle());
+            let states = &app.details_states;
+            let refreshed = block_on(async {
+                states.cpu_timeline.update_from_db(&app.db).await?;
+                states.mem_timeline.update_from_db(&app.db).await
+            });
+            if let Err(e) = refreshed {
+                log::warn!("❌ Failed to reload timelines: {e}");
+            }
+        }
+        _ => {
+            host_control::handle_key(app, key);
+        }
     }
 }
@@ -3,8 +3,15 @@ use ratatui::widgets::*;
 
 use futures::executor::block_on;
 
+use super::states::{
+    CustomChartSnapshot, DiskVolumeSnapshot, GpuDetailSnapshot, GpuProcessSnapshot, PortChange,
+    PortSnapshot, SessionDetailSnapshot,
+};
 use super::timeline_chart::TimelineChart;
 use crate::App;
+use crate::tui::diagnostics::view::format_millis;
+use crate::tui::list_ssh::states::LatencySnapshot;
+use crate::tui::scheduler::view::describe_poll_rate;
 
 pub fn render(app: &mut App, frame: &mut Frame) {
     let area = frame.area();
@@ -20,6 +27,20 @@ pub fn render(app: &mut App, frame: &mut Frame) {
     let mem_detail = block_on(app.details_states.mem.get(host_id));
     let disk_detail = block_on(app.details_states.disk.get(host_id));
     let gpu_detail = block_on(app.details_states.gpu.get(host_id));
+    let session_detail = block_on(app.details_states.session.get(host_id));
+    let port_detail = block_on(app.details_states.ports.get(host_id));
+    let facts = block_on(app.details_states.facts.get(host_id));
+    let custom_charts = block_on(app.details_states.custom.get(host_id));
+    let capabilities = block_on(app.details_states.capabilities.get(host_id));
+    let net_rate = block_on(app.details_states.net.get(host_id));
+    let streaming = block_on(app.executor.is_streaming(host_id));
+    let latency = block_on(app.latency_states.get(host_id));
+    let (timeline_range, timeline_resolution) = block_on(app.details_states.timeline.get());
+    let timeline_span = format!(
+        "{}, {} • t: range",
+        timeline_range.label(),
+        timeline_resolution.label()
+    );
     let host_info = {
         let hosts = block_on(app.ssh_hosts.lock());
         hosts.get(host_id).cloned()
@@ -28,9 +49,10 @@ pub fn render(app: &mut App, frame: &mut Frame) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(4),
-            Constraint::Percentage(50),
-            Constraint::Percentage(50),
+            Constraint::Length(8),
+            Constraint::Percentage(40),
+            Constraint::Percentage(38),
+            Constraint::Percentage(22),
         ])
         .split(area);
 
@@ -42,15 +64,92 @@ pub fn render(app: &mut App, frame: &mut Frame) {
     frame.render_widget(info_block, chunks[0]);
 
     if let Some(info) = host_info {
+        let info_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(info_inner);
+
+        let schedule = app.scheduler.host(host_id);
+        let streaming = match (streaming, &net_rate) {
+            (false, _) => "  •  S: stream".to_string(),
+            (true, Some(net)) => format!(
+                "  •  Streaming: ↓ {} ↑ {}",
+                format_rate(net.rx_bytes_per_sec),
+                format_rate(net.tx_bytes_per_sec)
+            ),
+            (true, None) => "  •  Streaming...".to_string(),
+        };
         let lines = [
             format!("Name: {}", info.name),
             format!("User: {}@{}:{}", info.user, info.ip, info.port),
             format!("Identity: {}", info.identity_file),
+            format!("Polling: {}{}", describe_poll_rate(&schedule), streaming),
+            format!("Latency: {}", describe_latency(latency.as_ref())),
+            format!(
+                "Clock: {}",
+                describe_clock(latency.as_ref(), app.config.clock.max_skew_secs)
+            ),
         ];
         let paragraph = Paragraph::new(lines.join("\n"))
             .style(Style::default())
             .alignment(Alignment::Left);
-        frame.render_widget(paragraph, info_inner);
+        frame.render_widget(paragraph, info_chunks[0]);
+
+        let mut fact_lines = match &facts {
+            Some(facts) => {
+                let hardware = [facts.vendor.as_deref(), facts.product.as_deref()]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let sockets = facts
+                    .cpu_sockets
+                    .filter(|s| *s > 1)
+                    .map(|s| format!(", {} sockets", s))
+                    .unwrap_or_default();
+                vec![
+                    format!(
+                        "OS: {}  •  Kernel: {} ({})",
+                        facts.os_name, facts.kernel_release, facts.arch
+                    ),
+                    format!(
+                        "Hardware: {}  •  Virtualization: {}",
+                        if hardware.is_empty() { "-" } else { &hardware },
+                        facts.virtualization.as_deref().unwrap_or("-")
+                    ),
+                    format!(
+                        "CPU: {} × {}{}  •  RAM: {:.1} GB  •  Updated: {}",
+                        facts.cpu_model,
+                        facts.cpu_count,
+                        sockets,
+                        facts.total_memory_mb as f64 / 1024.0,
+                        facts.updated_at
+                    ),
+                ]
+            }
+            None => vec!["Collecting host facts...".to_string()],
+        };
+        fact_lines.push(match &capabilities {
+            Some(snapshot) => {
+                let caps = &snapshot.capabilities;
+                let tools = caps.tools();
+                format!(
+                    "Tools: {}  •  sudo -n: {}  •  Shell: {}",
+                    if tools.is_empty() {
+                        "-".to_string()
+                    } else {
+                        tools.join(" ")
+                    },
+                    if caps.sudo { "yes" } else { "no" },
+                    caps.shell.as_deref().unwrap_or("-")
+                )
+            }
+            None => "Probing capabilities...".to_string(),
+        });
+        let facts_paragraph = Paragraph::new(fact_lines.join("\n"))
+            .style(Style::default())
+            .alignment(Alignment::Left);
+        frame.render_widget(facts_paragraph, info_chunks[1]);
     } else {
         let paragraph = Paragraph::new("No host info")
             .block(Block::default())
@@ -134,7 +233,8 @@ pub fn render(app: &mut App, frame: &mut Frame) {
             .alignment(Alignment::Left);
         frame.render_widget(cpu_info, cpu_sections[2]);
 
-        let timeline_chart = TimelineChart::new("CPU Usage", host_id)
+        let cpu_title = format!("CPU Usage ({timeline_span})");
+        let timeline_chart = TimelineChart::new(&cpu_title, host_id)
             .data(cpu_timeline.timeline_data.clone())
             .y_bounds((0.0, 100.0))
             .y_unit("%")
@@ -180,26 +280,53 @@ pub fn render(app: &mut App, frame: &mut Frame) {
         .alignment(Alignment::Left);
         frame.render_widget(mem_paragraph, mem_sections[0]);
 
+        let swap_percent = if mem.swap_total_mb > 0 {
+            (mem.swap_used_mb as f32 / mem.swap_total_mb as f32) * 100.0
+        } else {
+            0.0
+        };
         let lines = [
-            format!("Used: {:.1}GB", mem.used_mb as f64 / 1024.0),
-            format!("Free: {:.1}GB", mem.free_mb as f64 / 1024.0),
             format!(
-                "Available: {:.1}GB",
-                (mem.total_mb - mem.used_mb) as f64 / 1024.0
+                "Used: {:.1}GB  •  Free: {:.1}GB",
+                mem.used_mb as f64 / 1024.0,
+                mem.free_mb as f64 / 1024.0
+            ),
+            format!("Available: {:.1}GB", mem.available_mb as f64 / 1024.0),
+            format!(
+                "Buff/Cache: {:.1}GB  •  Shared: {:.1}GB",
+                mem.buffers_cache_mb as f64 / 1024.0,
+                mem.shared_mb as f64 / 1024.0
+            ),
+            format!(
+                "Swap: {:.1}GB / {:.1}GB",
+                mem.swap_used_mb as f64 / 1024.0,
+                mem.swap_total_mb as f64 / 1024.0
             ),
+            render_wide_bar("Swp", swap_percent),
         ];
 
-        let details_paragraph = Paragraph::new(lines.join(
-            "
-",
-        ))
-        .style(Style::default())
-        .alignment(Alignment::Left);
+        let details_paragraph = Paragraph::new(lines.join("\n"))
+            .style(Style::default())
+            .alignment(Alignment::Left);
         frame.render_widget(details_paragraph, mem_sections[1]);
 
-        let mem_timeline_chart = TimelineChart::new("Memory Usage", host_id)
+        let stacked_max = mem_timeline
+            .stacked_swap_data
+            .iter()
+            .filter(|(id, _, _)| id == host_id)
+            .map(|(_, value, _)| *value as f64)
+            .fold(100.0, f64::max);
+        let mem_title = format!("Memory Usage ({timeline_span})");
+        let mem_timeline_chart = TimelineChart::new(&mem_title, host_id)
             .data(mem_timeline.timeline_data.clone())
-            .y_bounds((0.0, 100.0))
+            .label("Used")
+            .series(
+                "+Cache",
+                mem_timeline.stacked_cache_data.clone(),
+                Color::Blue,
+            )
+            .series("+Swap", mem_timeline.stacked_swap_data.clone(), Color::Red)
+            .y_bounds((0.0, stacked_max.ceil()))
             .y_unit("%")
             .color(Color::Green);
 
@@ -291,20 +418,31 @@ pub fn render(app: &mut App, frame: &mut Frame) {
                 let table_inner = table_block.inner(disk_chunks[2]);
                 frame.render_widget(table_block, disk_chunks[2]);
 
-                let header = Row::new(vec!["Mount Point", "Size", "Used", "Avail", "Use%"])
-                    .style(
-                        Style::default()
-                            .fg(Color::Yellow)
-                            .add_modifier(Modifier::BOLD),
-                    )
-                    .height(1);
+                let header = Row::new(vec![
+                    "Mount Point",
+                    "Device",
+                    "Type",
+                    "Size",
+                    "Used",
+                    "Avail",
+                    "Use%",
+                    "IUse%",
+                ])
+                .style(
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .height(1);
 
                 let rows: Vec<Row> = top_volumes
                     .iter()
                     .map(|vol| {
-                        let row_style = if vol.used_percent > 90.0 {
+                        let peak_percent =
+                            vol.used_percent.max(vol.inodes_used_percent.unwrap_or(0.0));
+                        let row_style = if peak_percent > 90.0 {
                             Style::default().fg(Color::Red)
-                        } else if vol.used_percent > 75.0 {
+                        } else if peak_percent > 75.0 {
                             Style::default().fg(Color::Yellow)
                         } else {
                             Style::default()
@@ -312,10 +450,13 @@ pub fn render(app: &mut App, frame: &mut Frame) {
 
                         Row::new(vec![
                             vol.mount_point.clone(),
+                            vol.device.clone().unwrap_or_else(|| "-".to_string()),
+                            vol.fs_type.clone().unwrap_or_else(|| "-".to_string()),
                             format!("{:.1}G", vol.total_mb as f64 / 1024.0),
                             format!("{:.1}G", vol.used_mb as f64 / 1024.0),
                             format!("{:.1}G", vol.available_mb as f64 / 1024.0),
                             format!("{:.1}%", vol.used_percent),
+                            format_inode_usage(vol),
                         ])
                         .style(row_style)
                     })
@@ -324,11 +465,14 @@ pub fn render(app: &mut App, frame: &mut Frame) {
                 let table = Table::new(
                     rows,
                     [
-                        Constraint::Percentage(35), // Mount Point
-                        Constraint::Percentage(16), // Size
-                        Constraint::Percentage(16), // Used
-                        Constraint::Percentage(16), // Available
-                        Constraint::Percentage(17), // Use%
+                        Constraint::Percentage(22), // Mount Point
+                        Constraint::Percentage(18), // Device
+                        Constraint::Percentage(9),  // Type
+                        Constraint::Percentage(10), // Size
+                        Constraint::Percentage(10), // Used
+                        Constraint::Percentage(10), // Available
+                        Constraint::Percentage(10), // Use%
+                        Constraint::Percentage(11), // IUse%
                     ],
                 )
                 .header(header)
@@ -367,8 +511,9 @@ pub fn render(app: &mut App, frame: &mut Frame) {
                     .direction(Direction::Vertical)
                     .constraints([
                         Constraint::Length(3), // Memory Gauge
-                        Constraint::Length(3), // Temperature Gauge
-                        Constraint::Min(0),    // Info
+                        Constraint::Length(3), // Utilization Gauge
+                        Constraint::Length(2), // Temperature / Power
+                        Constraint::Min(0),    // Processes
                     ])
                     .split(gpu_inner);
 
@@ -381,10 +526,8 @@ pub fn render(app: &mut App, frame: &mut Frame) {
 
                     let mem_bar = render_wide_bar("Mem", usage_percent);
                     let mem_paragraph = Paragraph::new(format!(
-                        "GPU {}: {} - Memory {:.1}GB
-{}",
-                        gpu.index,
-                        gpu.name,
+                        "{} - Memory {:.1}GB\n{}",
+                        gpu_label(gpu),
                         total_mb as f64 / 1024.0,
                         mem_bar
                     ))
@@ -393,45 +536,73 @@ pub fn render(app: &mut App, frame: &mut Frame) {
                     .alignment(Alignment::Left);
                     frame.render_widget(mem_paragraph, gpu_sections[0]);
                 } else {
-                    let no_mem_info = Paragraph::new(format!(
-                        "GPU {}: {}
-Memory: N/A",
-                        gpu.index, gpu.name
-                    ))
-                    .block(Block::default())
-                    .alignment(Alignment::Left);
-                    frame.render_widget(no_mem_info, gpu_sections[0]);
-                }
-
-                if let Some(temp) = gpu.temperature_c {
-                    let temp_paragraph = Paragraph::new(format!("Temperature: {:.1}°C", temp))
-                        .block(Block::default())
-                        .style(Style::default())
-                        .alignment(Alignment::Left);
-                    frame.render_widget(temp_paragraph, gpu_sections[1]);
-                } else {
-                    let no_temp_info = Paragraph::new("Temperature: N/A")
+                    let no_mem_info = Paragraph::new(format!("{}\nMemory: N/A", gpu_label(gpu)))
                         .block(Block::default())
                         .alignment(Alignment::Left);
-                    frame.render_widget(no_temp_info, gpu_sections[1]);
+                    frame.render_widget(no_mem_info, gpu_sections[0]);
                 }
 
-                let info_lines = [
-                    format!("Index: {}", gpu.index),
-                    format!("Name: {}", gpu.name),
+                let util_text = match gpu.utilization_gpu {
+                    Some(util) => format!(
+                        "Utilization (memory controller: {})\n{}",
+                        format_optional_percent(gpu.utilization_memory),
+                        render_wide_bar("GPU", util)
+                    ),
+                    None => "Utilization: N/A".to_string(),
+                };
+                let util_paragraph = Paragraph::new(util_text)
+                    .block(Block::default())
+                    .style(Style::default())
+                    .alignment(Alignment::Left);
+                frame.render_widget(util_paragraph, gpu_sections[1]);
+
+                let temp_text = match gpu.temperature_c {
+                    Some(temp) => format!("Temperature: {:.1}°C", temp),
+                    None => "Temperature: N/A".to_string(),
+                };
+                let status_lines = [
+                    temp_text,
+                    format!(
+                        "Power: {}  Bus: {}",
+                        format_power(gpu.power_draw_w, gpu.power_limit_w),
+                        gpu.pci_bus_id.as_deref().unwrap_or("N/A")
+                    ),
                 ];
-                let info_paragraph = Paragraph::new(info_lines.join(
-                    "
-",
-                ))
-                .style(Style::default())
-                .alignment(Alignment::Left);
-                frame.render_widget(info_paragraph, gpu_sections[2]);
+                let status_paragraph = Paragraph::new(status_lines.join("\n"))
+                    .block(Block::default())
+                    .style(Style::default())
+                    .alignment(Alignment::Left);
+                frame.render_widget(status_paragraph, gpu_sections[2]);
+
+                render_gpu_processes(frame, &gpu.processes, gpu_sections[3]);
             } else {
-                let mut lines = vec![format!("GPU Count: {}", gpus.len())];
+                let physical_count = gpus.iter().filter(|gpu| gpu.parent_uuid.is_none()).count();
+                let mig_count = gpus.len() - physical_count;
+                let mut lines = vec![if mig_count > 0 {
+                    format!(
+                        "GPU Count: {} ({} MIG instances)",
+                        physical_count, mig_count
+                    )
+                } else {
+                    format!("GPU Count: {}", gpus.len())
+                }];
 
-                for gpu in &gpus {
-                    lines.push(format!("┌─ GPU {}: {}", gpu.index, gpu.name));
+                for (i, gpu) in gpus.iter().enumerate() {
+                    if gpu.parent_uuid.is_some() {
+                        lines.push(format!(
+                            "├─ {} (device {})",
+                            gpu.name,
+                            gpu.mig_device_index
+                                .map(|idx| idx.to_string())
+                                .unwrap_or_else(|| "?".to_string())
+                        ));
+                        if i + 1 < gpus.len() && gpus[i + 1].parent_uuid.is_none() {
+                            lines.push("│".to_string());
+                        }
+                        continue;
+                    }
+
+                    lines.push(format!("┌─ {}", gpu_label(gpu)));
 
                     if let (Some(total_mb), Some(used_mb)) =
                         (gpu.memory_total_mb, gpu.memory_used_mb)
@@ -448,18 +619,36 @@ Memory: N/A",
                         lines.push("│  Memory: N/A".to_string());
                     }
 
+                    if let Some(util) = gpu.utilization_gpu {
+                        lines.push(render_bar("│  Util", util));
+                    }
+
                     if let Some(temp) = gpu.temperature_c {
-                        lines.push(format!("│  Temp: {:.1}°C", temp));
+                        lines.push(format!(
+                            "│  Temp: {:.1}°C  Power: {}",
+                            temp,
+                            format_power(gpu.power_draw_w, gpu.power_limit_w)
+                        ));
                     } else {
                         lines.push("│  Temp: N/A".to_string());
                     }
 
-                    if gpu.index < gpus.len() as u32 - 1 {
+                    for process in &gpu.processes {
+                        lines.push(format!(
+                            "│  {} {} {} {}",
+                            process.pid,
+                            process.user,
+                            process.process_name,
+                            format_process_memory(process.used_memory_mb)
+                        ));
+                    }
+
+                    if i + 1 < gpus.len() && gpus[i + 1].parent_uuid.is_none() {
                         lines.push("│".to_string());
                     }
                 }
 
-                let paragraph = Paragraph::new(lines.join(""))
+                let paragraph = Paragraph::new(lines.join("\n"))
                     .style(Style::default())
                     .alignment(Alignment::Left);
                 frame.render_widget(paragraph, gpu_inner);
@@ -476,6 +665,423 @@ Memory: N/A",
             .alignment(Alignment::Center);
         frame.render_widget(paragraph, gpu_inner);
     }
+
+    if custom_charts.is_empty() {
+        let activity_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(62), Constraint::Percentage(38)])
+            .split(chunks[3]);
+        render_sessions(frame, session_detail, activity_chunks[0]);
+        render_ports(frame, port_detail, activity_chunks[1]);
+    } else {
+        let activity_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(40),
+                Constraint::Percentage(25),
+                Constraint::Percentage(35),
+            ])
+            .split(chunks[3]);
+        render_sessions(frame, session_detail, activity_chunks[0]);
+        render_ports(frame, port_detail, activity_chunks[1]);
+        render_custom_charts(frame, &custom_charts, host_id, activity_chunks[2]);
+    }
+}
+
+const CUSTOM_SERIES_COLORS: [Color; 6] = [
+    Color::Cyan,
+    Color::Yellow,
+    Color::Magenta,
+    Color::Green,
+    Color::Blue,
+    Color::Red,
+];
+
+fn render_custom_charts(
+    frame: &mut Frame,
+    charts: &[CustomChartSnapshot],
+    host_id: &str,
+    area: Rect,
+) {
+    let block = Block::default()
+        .title("Custom")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::White));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chart_areas = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![
+            Constraint::Ratio(1, charts.len() as u32);
+            charts.len()
+        ])
+        .split(inner);
+
+    for (chart, chart_area) in charts.iter().zip(chart_areas.iter()) {
+        let Some((primary, rest)) = chart.series.split_first() else {
+            continue;
+        };
+        let max = chart
+            .series
+            .iter()
+            .flat_map(|s| s.points.iter().map(|(_, value, _)| *value as f64))
+            .fold(0.0, f64::max);
+        let min = chart
+            .series
+            .iter()
+            .flat_map(|s| s.points.iter().map(|(_, value, _)| *value as f64))
+            .fold(0.0, f64::min);
+        let upper = if max > min { max * 1.1 } else { min + 1.0 };
+
+        let mut timeline = TimelineChart::new(&chart.name, host_id)
+            .data(primary.points.clone())
+            .label(&primary.key)
+            .y_bounds((min.floor(), upper.ceil()))
+            .y_unit(chart.units.as_deref().unwrap_or(""))
+            .color(CUSTOM_SERIES_COLORS[0]);
+        for (i, series) in rest.iter().enumerate() {
+            timeline = timeline.series(
+                &series.key,
+                series.points.clone(),
+                CUSTOM_SERIES_COLORS[(i + 1) % CUSTOM_SERIES_COLORS.len()],
+            );
+        }
+        timeline.render(frame, *chart_area);
+    }
+}
+
+fn render_sessions(frame: &mut Frame, session: Option<SessionDetailSnapshot>, area: Rect) {
+    let session_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(area);
+
+    let sessions = session
+        .as_ref()
+        .map(|s| s.sessions.as_slice())
+        .unwrap_or(&[]);
+    let users = {
+        let mut users: Vec<&str> = sessions.iter().map(|s| s.user.as_str()).collect();
+        users.sort();
+        users.dedup();
+        users.len()
+    };
+
+    let session_block = Block::default()
+        .title(format!(
+            "Sessions ({} users, {} sessions)",
+            users,
+            sessions.len()
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::White));
+    let session_inner = session_block.inner(session_chunks[0]);
+    frame.render_widget(session_block, session_chunks[0]);
+
+    if session.is_none() {
+        let paragraph = Paragraph::new("No Session data")
+            .block(Block::default())
+            .alignment(Alignment::Center);
+        frame.render_widget(paragraph, session_inner);
+    } else if sessions.is_empty() {
+        let paragraph = Paragraph::new("Nobody is logged in")
+            .block(Block::default())
+            .alignment(Alignment::Center);
+        frame.render_widget(paragraph, session_inner);
+    } else {
+        let header = Row::new(vec!["User", "TTY", "From", "Login", "Idle"])
+            .style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .height(1);
+
+        let rows: Vec<Row> = sessions
+            .iter()
+            .map(|s| {
+                let row_style = if s.idle_seconds == Some(0) {
+                    Style::default().fg(Color::Green)
+                } else {
+                    Style::default()
+                };
+                Row::new(vec![
+                    s.user.clone(),
+                    s.tty.clone(),
+                    s.from.clone().unwrap_or_else(|| "local".to_string()),
+                    s.login_time.clone(),
+                    format_idle(s.idle_seconds, &s.idle),
+                ])
+                .style(row_style)
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(18), // User
+                Constraint::Percentage(14), // TTY
+                Constraint::Percentage(28), // From
+                Constraint::Percentage(25), // Login
+                Constraint::Percentage(15), // Idle
+            ],
+        )
+        .header(header)
+        .block(Block::default());
+        frame.render_widget(table, session_inner);
+    }
+
+    let last_block = Block::default()
+        .title("Recent Logins")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::White));
+    let last_inner = last_block.inner(session_chunks[1]);
+    frame.render_widget(last_block, session_chunks[1]);
+
+    let last_lines: Vec<String> = session
+        .map(|s| s.last_logins)
+        .unwrap_or_default()
+        .iter()
+        .map(|login| match &login.from {
+            Some(from) => format!(
+                "{} ({}) from {}  {}",
+                login.user, login.tty, from, login.when
+            ),
+            None => format!("{} ({})  {}", login.user, login.tty, login.when),
+        })
+        .collect();
+    let paragraph = if last_lines.is_empty() {
+        Paragraph::new("No login history").alignment(Alignment::Center)
+    } else {
+        Paragraph::new(last_lines.join("\n")).alignment(Alignment::Left)
+    };
+    frame.render_widget(paragraph, last_inner);
+}
+
+fn render_ports(frame: &mut Frame, ports: Option<Vec<PortSnapshot>>, area: Rect) {
+    let listening = ports
+        .as_ref()
+        .map(|p| {
+            p.iter()
+                .filter(|p| p.change != PortChange::Disappeared)
+                .count()
+        })
+        .unwrap_or(0);
+    let block = Block::default()
+        .title(format!("Listening Ports ({})", listening))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::White));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let Some(ports) = ports else {
+        let paragraph = Paragraph::new("No Port data")
+            .block(Block::default())
+            .alignment(Alignment::Center);
+        frame.render_widget(paragraph, inner);
+        return;
+    };
+
+    if ports.is_empty() {
+        let paragraph = Paragraph::new("No listening sockets")
+            .block(Block::default())
+            .alignment(Alignment::Center);
+        frame.render_widget(paragraph, inner);
+        return;
+    }
+
+    let header = Row::new(vec!["", "Proto", "Address", "Port", "Process"])
+        .style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+        .height(1);
+
+    let rows: Vec<Row> = ports
+        .iter()
+        .map(|p| {
+            let (marker, style) = match p.change {
+                PortChange::Appeared => ("+", Style::default().fg(Color::Green)),
+                PortChange::Disappeared => (
+                    "-",
+                    Style::default()
+                        .fg(Color::Red)
+                        .add_modifier(Modifier::CROSSED_OUT),
+                ),
+                PortChange::Unchanged => (" ", Style::default()),
+            };
+            let process = match (&p.listener.process, p.listener.pid) {
+                (Some(name), Some(pid)) => format!("{} ({})", name, pid),
+                (Some(name), None) => name.clone(),
+                _ => "-".to_string(),
+            };
+            Row::new(vec![
+                marker.to_string(),
+                p.listener.protocol.clone(),
+                p.listener.address.clone(),
+                p.listener.port.to_string(),
+                process,
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(1),      // Change marker
+            Constraint::Length(5),      // Proto
+            Constraint::Percentage(35), // Address
+            Constraint::Length(6),      // Port
+            Constraint::Min(8),         // Process
+        ],
+    )
+    .header(header)
+    .block(Block::default());
+    frame.render_widget(table, inner);
+}
+
+/// Where the last collection cycle spent its time.
+fn describe_latency(latency: Option<&LatencySnapshot>) -> String {
+    let Some(latency) = latency else {
+        return "-".to_string();
+    };
+    let phase = |ms: Option<u64>| ms.map_or("-".to_string(), format_millis);
+    let status = match latency.status.as_str() {
+        "ok" => String::new(),
+        "timed_out" => "timed out  •  ".to_string(),
+        _ => "failed  •  ".to_string(),
+    };
+    format!(
+        "{status}connect {}  •  auth {}  •  command {}  •  parse {}  •  queued {}",
+        phase(latency.connect_ms),
+        phase(latency.auth_ms),
+        phase(latency.command_ms),
+        phase(latency.parse_ms),
+        format_millis(latency.queue_lag_ms)
+    )
+}
+
+/// The host's clock offset from ours, flagged when beyond `max_skew_secs`.
+fn describe_clock(latency: Option<&LatencySnapshot>, max_skew_secs: f64) -> String {
+    let Some((latency, offset_ms)) = latency.and_then(|l| Some((l, l.clock_offset_ms?))) else {
+        return "-".to_string();
+    };
+    let offset = format!(
+        "{:+.3}s (±{:.3}s)",
+        offset_ms / 1000.0,
+        latency.clock_round_trip_ms.unwrap_or(0.0) / 2000.0
+    );
+    match latency.clock_skew(max_skew_secs) {
+        Some(_) => format!("{offset}  ⚠ skewed beyond {max_skew_secs}s, check NTP"),
+        None => offset,
+    }
+}
+
+fn format_rate(bytes_per_sec: f64) -> String {
+    match bytes_per_sec {
+        rate if rate >= 1024.0 * 1024.0 => format!("{:.1} MB/s", rate / (1024.0 * 1024.0)),
+        rate if rate >= 1024.0 => format!("{:.1} KB/s", rate / 1024.0),
+        rate => format!("{:.0} B/s", rate),
+    }
+}
+
+fn format_idle(idle_seconds: Option<u64>, raw: &str) -> String {
+    match idle_seconds {
+        Some(0) => "active".to_string(),
+        Some(secs) if secs >= 3600 => format!("{}h{:02}m", secs / 3600, secs % 3600 / 60),
+        Some(secs) => format!("{}m", secs / 60),
+        None => raw.to_string(),
+    }
+}
+
+fn render_gpu_processes(frame: &mut Frame, processes: &[GpuProcessSnapshot], area: Rect) {
+    let table_block = Block::default().title(format!("Processes ({})", processes.len()));
+    let table_inner = table_block.inner(area);
+    frame.render_widget(table_block, area);
+
+    if processes.is_empty() {
+        let paragraph = Paragraph::new("No compute processes")
+            .block(Block::default())
+            .alignment(Alignment::Left);
+        frame.render_widget(paragraph, table_inner);
+        return;
+    }
+
+    let header = Row::new(vec!["PID", "User", "Process", "Mem"])
+        .style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+        .height(1);
+
+    let rows: Vec<Row> = processes
+        .iter()
+        .map(|process| {
+            Row::new(vec![
+                process.pid.to_string(),
+                process.user.clone(),
+                process.process_name.clone(),
+                format_process_memory(process.used_memory_mb),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Percentage(15), // PID
+            Constraint::Percentage(20), // User
+            Constraint::Percentage(45), // Process
+            Constraint::Percentage(20), // Mem
+        ],
+    )
+    .header(header)
+    .block(Block::default());
+
+    frame.render_widget(table, table_inner);
+}
+
+fn gpu_label(gpu: &GpuDetailSnapshot) -> String {
+    match gpu.vendor.as_deref() {
+        Some(vendor) if vendor != "unknown" => {
+            format!("GPU {}: {} [{}]", gpu.index, gpu.name, vendor)
+        }
+        _ => format!("GPU {}: {}", gpu.index, gpu.name),
+    }
+}
+
+fn format_optional_percent(value: Option<f32>) -> String {
+    value
+        .map(|v| format!("{:.0}%", v))
+        .unwrap_or_else(|| "N/A".to_string())
+}
+
+fn format_power(draw_w: Option<f32>, limit_w: Option<f32>) -> String {
+    match (draw_w, limit_w) {
+        (Some(draw), Some(limit)) => format!("{:.0}W / {:.0}W", draw, limit),
+        (Some(draw), None) => format!("{:.0}W", draw),
+        _ => "N/A".to_string(),
+    }
+}
+
+fn format_process_memory(used_memory_mb: Option<u64>) -> String {
+    used_memory_mb
+        .map(|mb| format!("{:.1}G", mb as f64 / 1024.0))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn format_inode_usage(vol: &DiskVolumeSnapshot) -> String {
+    match (vol.inodes_used, vol.inodes_total, vol.inodes_used_percent) {
+        (Some(used), Some(total), _) if total > 0 => {
+            format!("{:.1}%", used as f64 / total as f64 * 100.0)
+        }
+        (_, _, Some(percent)) => format!("{:.1}%", percent),
+        _ => "-".to_string(),
+    }
 }
 
 fn render_bar(label: &str, percent: f32) -> String {
@@ -1,6 +1,9 @@
-use crate::backend::db::cpu::queries as cpu_queries;
-use crate::backend::db::disk::queries as disk_queries;
-use crate::backend::db::mem::queries as mem_queries;
+use crate::backend::db::telemetry::queries as telemetry_queries;
+use crate::backend::jobs::cpu::CpuCollector;
+use crate::backend::jobs::disk::DiskCollector;
+use crate::backend::jobs::mem::MemCollector;
+use crate::backend::jobs::packages::PackagesCollector;
+use crate::backend::jobs::session::SessionCollector;
 use crate::tui::states_update::StateJob;
 use anyhow::Result;
 use rusqlite::Connection;
@@ -29,6 +32,64 @@ pub struct MemSnapshot {
     pub used_percent: f32,
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct SessionSnapshot {
+    pub user_count: u32,
+    pub session_count: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PackageSnapshot {
+    pub manager: Option<String>,
+    pub pending_count: u32,
+    pub security_count: Option<u32>,
+    pub reboot_required: bool,
+}
+
+/// The latest collection cycle of a host. Phases are in milliseconds and
+/// missing when the cycle failed before reaching them.
+#[derive(Debug, Clone, Default)]
+pub struct LatencySnapshot {
+    pub status: String,
+    pub connect_ms: Option<u64>,
+    pub auth_ms: Option<u64>,
+    pub command_ms: Option<u64>,
+    pub parse_ms: Option<u64>,
+    pub queue_lag_ms: u64,
+    pub jobs_ok: usize,
+    pub jobs_failed: usize,
+    /// How far the host's clock is ahead of ours, when it could be read.
+    pub clock_offset_ms: Option<f64>,
+    pub clock_round_trip_ms: Option<f64>,
+    pub timestamp: String,
+}
+
+impl LatencySnapshot {
+    /// Time from connecting until the results were parsed.
+    pub fn total_ms(&self) -> Option<u64> {
+        Some(self.connect_ms? + self.auth_ms? + self.command_ms? + self.parse_ms?)
+    }
+
+    /// The host's clock offset in seconds, if it is off by more than
+    /// `max_skew_secs` even allowing for the network delay.
+    pub fn clock_skew(&self, max_skew_secs: f64) -> Option<f64> {
+        let offset = self.clock_offset_ms? / 1000.0;
+        let uncertainty = self.clock_round_trip_ms.unwrap_or(0.0) / 2000.0;
+        (offset.abs() - uncertainty > max_skew_secs).then_some(offset)
+    }
+}
+
+/// A host's collection over the last hour.
+#[derive(Debug, Clone, Default)]
+pub struct HealthSnapshot {
+    pub cycles: u64,
+    pub ok: u64,
+    pub timed_out: u64,
+    pub avg_total_ms: Option<f64>,
+    pub avg_queue_lag_ms: f64,
+    pub max_queue_lag_ms: u64,
+}
+
 #[derive(Debug, Clone)]
 pub struct CpuStates {
     data: Arc<RwLock<HashMap<String, CpuSnapshot>>>,
@@ -62,6 +123,50 @@ impl Default for MemStates {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct SessionStates {
+    data: Arc<RwLock<HashMap<String, SessionSnapshot>>>,
+}
+
+impl Default for SessionStates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PackageStates {
+    data: Arc<RwLock<HashMap<String, PackageSnapshot>>>,
+}
+
+impl Default for PackageStates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LatencyStates {
+    data: Arc<RwLock<HashMap<String, LatencySnapshot>>>,
+}
+
+impl Default for LatencyStates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HealthStates {
+    data: Arc<RwLock<HashMap<String, HealthSnapshot>>>,
+}
+
+impl Default for HealthStates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DiskStates {
     pub fn new() -> Self {
         Self {
@@ -74,7 +179,7 @@ impl DiskStates {
     }
 
     pub async fn update_from_db(&self, conn: &Arc<Mutex<Connection>>) -> Result<()> {
-        let rows = disk_queries::fetch_latest_disk_all(conn).await?;
+        let rows = DiskCollector::default().query(conn).await?;
         log::info!("Fetched {} disk rows from DB", rows.len());
         let mut map = self.data.write().await;
         map.clear();
@@ -113,7 +218,7 @@ impl MemStates {
     }
 
     pub async fn update_from_db(&self, conn: &Arc<Mutex<Connection>>) -> Result<()> {
-        let rows = mem_queries::fetch_latest_mem_all(conn).await?;
+        let rows = MemCollector.query(conn).await?;
         log::info!("Fetched {} mem rows from DB", rows.len());
         let mut map = self.data.write().await;
         map.clear();
@@ -148,7 +253,7 @@ impl CpuStates {
     }
 
     pub async fn update_from_db(&self, conn: &Arc<Mutex<Connection>>) -> Result<()> {
-        let rows = cpu_queries::fetch_latest_cpu_all(conn).await?;
+        let rows = CpuCollector.query(conn).await?;
         log::info!("Fetched {} CPU rows from DB", rows.len());
         let mut map = self.data.write().await;
         map.clear();
@@ -175,11 +280,150 @@ impl CpuStates {
     }
 }
 
+impl SessionStates {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub async fn update_from_db(&self, conn: &Arc<Mutex<Connection>>) -> Result<()> {
+        let rows = SessionCollector.query(conn).await?;
+        log::info!("Fetched {} session rows from DB", rows.len());
+        let mut map = self.data.write().await;
+        map.clear();
+        for row in rows {
+            map.insert(
+                row.host_id,
+                SessionSnapshot {
+                    user_count: row.user_count,
+                    session_count: row.session_count,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    pub async fn snapshot_map(&self) -> HashMap<String, SessionSnapshot> {
+        self.data.read().await.clone()
+    }
+}
+
+impl PackageStates {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub async fn update_from_db(&self, conn: &Arc<Mutex<Connection>>) -> Result<()> {
+        let rows = PackagesCollector.query(conn).await?;
+        log::info!("Fetched {} package rows from DB", rows.len());
+        let mut map = self.data.write().await;
+        map.clear();
+        for row in rows {
+            map.insert(
+                row.host_id,
+                PackageSnapshot {
+                    manager: row.manager,
+                    pending_count: row.pending_count,
+                    security_count: row.security_count,
+                    reboot_required: row.reboot_required,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    pub async fn snapshot_map(&self) -> HashMap<String, PackageSnapshot> {
+        self.data.read().await.clone()
+    }
+}
+
+impl LatencyStates {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub async fn get(&self, host_id: &str) -> Option<LatencySnapshot> {
+        self.data.read().await.get(host_id).cloned()
+    }
+
+    pub async fn update_from_db(&self, conn: &Arc<Mutex<Connection>>) -> Result<()> {
+        let rows = telemetry_queries::fetch_latest_cycle_all(conn).await?;
+        log::info!("Fetched {} cycle rows from DB", rows.len());
+        let mut map = self.data.write().await;
+        map.clear();
+        for row in rows {
+            map.insert(
+                row.host_id,
+                LatencySnapshot {
+                    status: row.status,
+                    connect_ms: row.connect_ms,
+                    auth_ms: row.auth_ms,
+                    command_ms: row.command_ms,
+                    parse_ms: row.parse_ms,
+                    queue_lag_ms: row.queue_lag_ms,
+                    jobs_ok: row.jobs_ok,
+                    jobs_failed: row.jobs_failed,
+                    clock_offset_ms: row.clock_offset_ms,
+                    clock_round_trip_ms: row.clock_round_trip_ms,
+                    timestamp: row.timestamp,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    pub async fn snapshot_map(&self) -> HashMap<String, LatencySnapshot> {
+        self.data.read().await.clone()
+    }
+}
+
+impl HealthStates {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub async fn update_from_db(&self, conn: &Arc<Mutex<Connection>>) -> Result<()> {
+        let rows = telemetry_queries::fetch_cycle_health_all(conn).await?;
+        log::info!("Fetched {} cycle health rows from DB", rows.len());
+        let mut map = self.data.write().await;
+        map.clear();
+        for row in rows {
+            map.insert(
+                row.host_id,
+                HealthSnapshot {
+                    cycles: row.cycles,
+                    ok: row.ok,
+                    timed_out: row.timed_out,
+                    avg_total_ms: row.avg_total_ms,
+                    avg_queue_lag_ms: row.avg_queue_lag_ms,
+                    max_queue_lag_ms: row.max_queue_lag_ms,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    pub async fn snapshot_map(&self) -> HashMap<String, HealthSnapshot> {
+        self.data.read().await.clone()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum ListSshJobKind {
     Cpu(Arc<CpuStates>),
     Mem(Arc<MemStates>),
     Disk(Arc<DiskStates>),
+    Session(Arc<SessionStates>),
+    Packages(Arc<PackageStates>),
+    Latency(Arc<LatencyStates>),
+    Health(Arc<HealthStates>),
 }
 
 #[async_trait::async_trait]
@@ -189,6 +433,10 @@ impl StateJob for ListSshJobKind {
             ListSshJobKind::Cpu(_) => "cpu",
             ListSshJobKind::Mem(_) => "mem",
             ListSshJobKind::Disk(_) => "disk",
+            ListSshJobKind::Session(_) => "session",
+            ListSshJobKind::Packages(_) => "packages",
+            ListSshJobKind::Latency(_) => "latency",
+            ListSshJobKind::Health(_) => "health",
         }
     }
 
@@ -197,6 +445,10 @@ impl StateJob for ListSshJobKind {
             ListSshJobKind::Cpu(state) => state.update_from_db(conn).await,
             ListSshJobKind::Mem(state) => state.update_from_db(conn).await,
             ListSshJobKind::Disk(state) => state.update_from_db(conn).await,
+            ListSshJobKind::Session(state) => state.update_from_db(conn).await,
+            ListSshJobKind::Packages(state) => state.update_from_db(conn).await,
+            ListSshJobKind::Latency(state) => state.update_from_db(conn).await,
+            ListSshJobKind::Health(state) => state.update_from_db(conn).await,
         }
     }
 }
@@ -1,4 +1,5 @@
 use crate::App;
+use crate::tui::host_control;
 use crossterm::event::KeyCode;
 
 pub fn handle_key(app: &mut App, key: crossterm::event::KeyEvent) {
@@ -29,7 +30,9 @@ pub fn handle_key(app: &mut App, key: crossterm::event::KeyEvent) {
         KeyCode::Char('q') | KeyCode::Esc => {
             app.running = false;
         }
-        _ => {}
+        _ => {
+            host_control::handle_key(app, key);
+        }
     }
 }
 
@@ -1,8 +1,7 @@
 use super::themed_table::TableColors;
-use super::view_table_row::render as render_table_row;
+use super::view_table_row::{HostMetrics, render as render_table_row};
 use crate::backend::db::get_default_db_path;
 use crate::ssh_config::SshHostInfo;
-use crate::tui::list_ssh::states::{CpuSnapshot, DiskSnapshot, MemSnapshot};
 use crate::{App, AppMode};
 use futures::executor::block_on;
 use ratatui::prelude::*;
@@ -10,11 +9,9 @@ use ratatui::text::Line;
 use ratatui::widgets::*;
 
 type HostEntry = (
-    String,               // Host ID
-    SshHostInfo,          // Host info
-    Option<CpuSnapshot>,  // CPU Snapshot
-    Option<MemSnapshot>,  // Memory Snapshot
-    Option<DiskSnapshot>, // Disk Snapshot
+    String,      // Host ID
+    SshHostInfo, // Host info
+    HostMetrics, // Latest snapshots
 );
 
 pub fn render(app: &mut App, frame: &mut Frame) {
@@ -63,6 +60,9 @@ pub fn render(app: &mut App, frame: &mut Frame) {
     let cpu_map = block_on(app.cpu_states.snapshot_map());
     let mem_map = block_on(app.mem_states.snapshot_map());
     let disk_map = block_on(app.disk_states.snapshot_map());
+    let session_map = block_on(app.session_states.snapshot_map());
+    let package_map = block_on(app.package_states.snapshot_map());
+    let latency_map = block_on(app.latency_states.snapshot_map());
 
     let mut host_entries: Vec<HostEntry> = hosts
         .iter()
@@ -75,17 +75,25 @@ pub fn render(app: &mut App, frame: &mut Frame) {
             }
         })
         .map(|(k, v)| {
-            let cpu = cpu_map.get(k).cloned();
-            let mem = mem_map.get(k).cloned();
-            let disk = disk_map.get(k).cloned();
-            (k.clone(), v.clone(), cpu, mem, disk)
+            let metrics = HostMetrics {
+                cpu: cpu_map.get(k).cloned(),
+                mem: mem_map.get(k).cloned(),
+                disk: disk_map.get(k).cloned(),
+                session: session_map.get(k).cloned(),
+                packages: package_map.get(k).cloned(),
+                latency: latency_map.get(k).cloned(),
+                clock_skew: latency_map
+                    .get(k)
+                    .and_then(|l| l.clock_skew(app.config.clock.max_skew_secs)),
+            };
+            (k.clone(), v.clone(), metrics)
         })
         .collect();
 
-    host_entries.sort_by_key(|(_, h, _, _, _)| h.name.clone());
+    host_entries.sort_by_key(|(_, h, _)| h.name.clone());
     app.visible_hosts = host_entries
         .iter()
-        .map(|(id, info, _, _, _)| (id.clone(), info.clone()))
+        .map(|(id, info, _)| (id.clone(), info.clone()))
         .collect();
 
     let grid_area = chunks[2];
@@ -106,7 +114,7 @@ pub fn render(app: &mut App, frame: &mut Frame) {
     let rows = host_entries[start_index..end_index]
         .iter()
         .enumerate()
-        .map(|(i, (_, info, cpu, mem, disk))| render_table_row(i, info, &colors, cpu, mem, disk));
+        .map(|(i, (_, info, metrics))| render_table_row(i, info, &colors, metrics));
 
     let header = Row::new(vec![
         Cell::from("Name"),
@@ -114,6 +122,9 @@ pub fn render(app: &mut App, frame: &mut Frame) {
         Cell::from("CPU"),
         Cell::from("Mem"),
         Cell::from("Disk"),
+        Cell::from("Users"),
+        Cell::from("Patches"),
+        Cell::from("Latency"),
     ])
     .style(
         Style::default()
@@ -130,6 +141,9 @@ pub fn render(app: &mut App, frame: &mut Frame) {
             Constraint::Length(16),
             Constraint::Length(16),
             Constraint::Length(20),
+            Constraint::Length(12),
+            Constraint::Length(20),
+            Constraint::Length(20),
         ],
     )
     .header(header)
@@ -140,19 +154,21 @@ pub fn render(app: &mut App, frame: &mut Frame) {
 
     frame.render_stateful_widget(table, grid_area, &mut app.table_state);
 
-    let footer = Paragraph::new(vec![Line::from("ESC: Exit | ↑↓: Scroll | /: Search")])
-        .alignment(Alignment::Center)
-        .style(
-            Style::default()
-                .fg(colors.row_fg)
-                .bg(colors.normal_row_color),
-        )
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Controls")
-                .border_style(Style::default().fg(colors.footer_border_color)),
-        );
+    let footer = Paragraph::new(vec![Line::from(
+        "ESC: Exit | ↑↓: Scroll | /: Search | s: Scheduler | d: Diagnostics | r: Refresh | p: Pause | +/-: Interval | X: Stop",
+    )])
+    .alignment(Alignment::Center)
+    .style(
+        Style::default()
+            .fg(colors.row_fg)
+            .bg(colors.normal_row_color),
+    )
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Controls")
+            .border_style(Style::default().fg(colors.footer_border_color)),
+    );
 
     frame.render_widget(footer, chunks[3]);
 }
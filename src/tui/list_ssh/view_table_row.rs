@@ -1,18 +1,31 @@
 use super::themed_table::TableColors;
 use crate::ssh_config::SshHostInfo;
-use crate::tui::list_ssh::states::{CpuSnapshot, DiskSnapshot, MemSnapshot};
+use crate::tui::diagnostics::view::format_millis;
+use crate::tui::list_ssh::states::{
+    CpuSnapshot, DiskSnapshot, LatencySnapshot, MemSnapshot, PackageSnapshot, SessionSnapshot,
+};
 use ratatui::prelude::*;
 use ratatui::widgets::*;
 
+#[derive(Debug, Clone, Default)]
+pub struct HostMetrics {
+    pub cpu: Option<CpuSnapshot>,
+    pub mem: Option<MemSnapshot>,
+    pub disk: Option<DiskSnapshot>,
+    pub session: Option<SessionSnapshot>,
+    pub packages: Option<PackageSnapshot>,
+    pub latency: Option<LatencySnapshot>,
+    /// Seconds the host's clock is off, when beyond the configured limit.
+    pub clock_skew: Option<f64>,
+}
+
 pub fn render(
     i: usize,
     info: &SshHostInfo,
     colors: &TableColors,
-    cpu: &Option<CpuSnapshot>,
-    mem: &Option<MemSnapshot>,
-    disk: &Option<DiskSnapshot>,
+    metrics: &HostMetrics,
 ) -> Row<'static> {
-    let bg = if i % 2 == 0 {
+    let bg = if i.is_multiple_of(2) {
         colors.normal_row_color
     } else {
         colors.alt_row_color
@@ -20,12 +33,14 @@ pub fn render(
 
     let user_at_host = format!("{}@{}:{}", info.user, info.ip, info.port);
 
-    let cpu_text = cpu
+    let cpu_text = metrics
+        .cpu
         .as_ref()
         .map(|c| format!("{:.1}% / {}cores", c.usage_percent, c.core_count))
         .unwrap_or_else(|| "-".to_string());
 
-    let mem_text = mem
+    let mem_text = metrics
+        .mem
         .as_ref()
         .map(|m| {
             let gb = m.total_mb as f64 / 1024.0;
@@ -37,7 +52,8 @@ pub fn render(
         })
         .unwrap_or_else(|| "-".to_string());
 
-    let disk_text = disk
+    let disk_text = metrics
+        .disk
         .as_ref()
         .map(|d| {
             let gb = d.total_mb as f64 / 1024.0;
@@ -49,12 +65,53 @@ pub fn render(
         })
         .unwrap_or_else(|| "-".to_string());
 
+    let users_text = metrics
+        .session
+        .as_ref()
+        .map(|s| format!("{} ({} tty)", s.user_count, s.session_count))
+        .unwrap_or_else(|| "-".to_string());
+
+    let patches_text = metrics
+        .packages
+        .as_ref()
+        .map(|p| {
+            let mut text = match (p.manager.as_deref(), p.security_count) {
+                (None, _) => "n/a".to_string(),
+                (Some(_), Some(security)) if security > 0 => {
+                    format!("{} ({} sec)", p.pending_count, security)
+                }
+                (Some(_), _) if p.pending_count == 0 => "up to date".to_string(),
+                (Some(_), _) => p.pending_count.to_string(),
+            };
+            if p.reboot_required {
+                text.push_str(" ⟳ reboot");
+            }
+            text
+        })
+        .unwrap_or_else(|| "-".to_string());
+
+    let latency = match &metrics.latency {
+        Some(l) => match (l.status.as_str(), l.total_ms()) {
+            ("ok", Some(total)) => match metrics.clock_skew {
+                Some(skew) => Cell::from(format!("{} ⏱ {skew:+.1}s", format_millis(total)))
+                    .style(Style::default().fg(Color::Yellow)),
+                None => Cell::from(format_millis(total)),
+            },
+            ("timed_out", _) => Cell::from("timed out").style(Style::default().fg(Color::Red)),
+            _ => Cell::from("failed").style(Style::default().fg(Color::Red)),
+        },
+        None => Cell::from("-"),
+    };
+
     Row::new(vec![
         Cell::from(info.name.clone()),
         Cell::from(user_at_host),
         Cell::from(cpu_text),
         Cell::from(mem_text),
         Cell::from(disk_text),
+        Cell::from(users_text),
+        Cell::from(patches_text),
+        latency,
     ])
     .style(Style::default().bg(bg))
     .height(2)
@@ -1,5 +1,8 @@
+pub mod diagnostics;
+pub mod host_control;
 pub mod host_details;
 pub mod list_ssh;
+pub mod scheduler;
 pub mod states_update;
 
 #[tokio::test]
@@ -19,7 +22,6 @@ async fn test_cpu_job_executor_runs() {
     {
         let insert = CpuResultInsert {
             host_id: "test-host".to_string(),
-            model_name: "FakeCPU".to_string(),
             core_count: 8,
             usage_percent: 33.3,
             per_core: vec![10.0, 20.0, 30.0, 40.0, 10.0, 20.0, 30.0, 40.0],
@@ -29,7 +31,7 @@ async fn test_cpu_job_executor_runs() {
 
     let job_group = StatesJobGroup {
         name: "list_view".into(),
-        interval: Duration::from_millis(100),
+        interval: tokio::sync::watch::channel(Duration::from_millis(100)).1,
         jobs: vec![job],
     };
 
@@ -0,0 +1,185 @@
+use crate::App;
+use crate::backend::jobs::adaptive::PollRate;
+use crate::backend::jobs::scheduler::{HostSchedule, ScheduleState};
+use crate::tui::list_ssh::themed_table::TableColors;
+use ratatui::prelude::*;
+use ratatui::text::Line;
+use ratatui::widgets::*;
+use std::time::Duration;
+use tokio::time::Instant;
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 60 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else if secs > 0 {
+        format!("{}s", secs)
+    } else {
+        format!("{}ms", duration.as_millis())
+    }
+}
+
+/// The host's effective polling interval and why it differs from the
+/// collector defaults.
+pub fn describe_poll_rate(schedule: &HostSchedule) -> String {
+    let Some(interval) = schedule.poll_interval else {
+        return "-".to_string();
+    };
+    let mut reasons = vec![];
+    if let Some(scale) = schedule.interval_scale {
+        reasons.push(format!("×{scale}"));
+    }
+    match schedule.rate {
+        PollRate::Normal => {}
+        PollRate::Focused => reasons.push("focused".to_string()),
+        PollRate::BackedOff(backoff) => reasons.push(format!("unchanged, ×{backoff:.1}")),
+    }
+    if reasons.is_empty() {
+        format!("every {}", format_duration(interval))
+    } else {
+        format!(
+            "every {} ({})",
+            format_duration(interval),
+            reasons.join(", ")
+        )
+    }
+}
+
+fn lag_color(lag: Duration) -> Color {
+    match lag.as_secs() {
+        0..5 => Color::Green,
+        5..30 => Color::Yellow,
+        _ => Color::Red,
+    }
+}
+
+pub fn render(app: &mut App, frame: &mut Frame) {
+    let colors = TableColors::default();
+    let snapshot = app.scheduler.snapshot();
+    let now = Instant::now();
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Length(3), // Summary
+            Constraint::Min(0),    // Table
+            Constraint::Length(3), // Footer
+        ])
+        .split(frame.area());
+
+    let title = Paragraph::new("Scheduler")
+        .style(Style::default().add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center);
+    frame.render_widget(title, chunks[0]);
+
+    let max_lag = snapshot
+        .hosts
+        .values()
+        .map(|h| h.lag(now))
+        .max()
+        .unwrap_or_default();
+    let summary = Line::from(vec![
+        Span::raw(format!(
+            "Running: {}/{} • Queued: ",
+            snapshot.running, snapshot.max_concurrent
+        )),
+        Span::styled(
+            snapshot.queued.to_string(),
+            Style::default().fg(if snapshot.queued > 0 {
+                Color::Yellow
+            } else {
+                Color::Green
+            }),
+        ),
+        Span::raw(" • Max behind: "),
+        Span::styled(
+            format_duration(max_lag),
+            Style::default().fg(lag_color(max_lag)),
+        ),
+        Span::raw(format!(" • Per bastion: {}", snapshot.max_per_bastion)),
+    ]);
+    frame.render_widget(Paragraph::new(summary), chunks[1]);
+
+    let hosts = app.visible_hosts.clone();
+    let mut entries: Vec<(String, HostSchedule)> = hosts
+        .iter()
+        .map(|(id, info)| {
+            let schedule = snapshot.hosts.get(id).cloned().unwrap_or_default();
+            (info.name.clone(), schedule)
+        })
+        .collect();
+    // Hosts furthest behind first.
+    entries.sort_by(|(a_name, a), (b_name, b)| {
+        b.lag(now).cmp(&a.lag(now)).then_with(|| a_name.cmp(b_name))
+    });
+
+    let rows = entries.iter().map(|(name, schedule)| {
+        let (state, state_color) = match schedule.state {
+            ScheduleState::Idle => ("idle", Color::Gray),
+            ScheduleState::Queued => ("queued", Color::Yellow),
+            ScheduleState::Running => ("running", Color::Green),
+            ScheduleState::Paused => ("paused", Color::Blue),
+            ScheduleState::Stopped => ("stopped", Color::DarkGray),
+        };
+        let lag = schedule.lag(now);
+        let next_run = match schedule.next_due {
+            Some(due) if due > now => format!("in {}", format_duration(due - now)),
+            Some(_) => "now".to_string(),
+            None => "-".to_string(),
+        };
+        Row::new(vec![
+            Cell::from(name.clone()),
+            Cell::from(schedule.bastion.clone().unwrap_or_else(|| "-".into())),
+            Cell::from(state).style(Style::default().fg(state_color)),
+            Cell::from(format_duration(lag)).style(Style::default().fg(lag_color(lag))),
+            Cell::from(next_run),
+            Cell::from(describe_poll_rate(schedule)),
+        ])
+        .style(
+            Style::default()
+                .fg(colors.row_fg)
+                .bg(colors.normal_row_color),
+        )
+    });
+
+    let header = Row::new(vec![
+        "Name", "Bastion", "State", "Behind", "Next run", "Interval",
+    ])
+    .style(
+        Style::default()
+            .fg(colors.header_fg)
+            .bg(colors.header_bg)
+            .add_modifier(Modifier::BOLD),
+    );
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(24),
+            Constraint::Length(24),
+            Constraint::Length(10),
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Length(30),
+        ],
+    )
+    .header(header)
+    .block(Block::default().borders(Borders::ALL).title("Hosts"));
+    frame.render_widget(table, chunks[2]);
+
+    let footer = Paragraph::new(vec![Line::from("ESC/s: Back")])
+        .alignment(Alignment::Center)
+        .style(
+            Style::default()
+                .fg(colors.row_fg)
+                .bg(colors.normal_row_color),
+        )
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Controls")
+                .border_style(Style::default().fg(colors.footer_border_color)),
+        );
+    frame.render_widget(footer, chunks[3]);
+}
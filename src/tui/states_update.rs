@@ -1,9 +1,12 @@
+use crate::backend::shutdown::join_within;
 use anyhow::Result;
 use rusqlite::Connection;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::{Mutex, RwLock};
-use tokio::{task, time};
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock, watch};
+use tokio::task::{self, JoinHandle};
+use tokio::time;
 
 #[async_trait::async_trait]
 pub trait StateJob: Send + Sync {
@@ -15,7 +18,8 @@ pub trait StateJob: Send + Sync {
 #[derive(Clone, Debug)]
 pub struct StatesJobGroup<T: StateJob> {
     pub name: String,
-    pub interval: std::time::Duration,
+    /// Time between updates; a new value takes effect at once.
+    pub interval: watch::Receiver<Duration>,
     pub jobs: Vec<T>,
 }
 
@@ -23,6 +27,8 @@ pub struct StatesJobGroup<T: StateJob> {
 pub struct StatesJobExecutor<T: StateJob> {
     groups: Arc<RwLock<HashMap<String, StatesJobGroup<T>>>>,
     db: Arc<Mutex<Connection>>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+    shutdown: watch::Sender<bool>,
 }
 
 impl<T: StateJob + 'static + Clone> StatesJobExecutor<T> {
@@ -30,6 +36,8 @@ impl<T: StateJob + 'static + Clone> StatesJobExecutor<T> {
         Self {
             groups: Arc::new(RwLock::new(HashMap::new())),
             db,
+            tasks: Mutex::new(Vec::new()),
+            shutdown: watch::channel(false).0,
         }
     }
 
@@ -40,24 +48,48 @@ impl<T: StateJob + 'static + Clone> StatesJobExecutor<T> {
 
     pub async fn run_all(&self) {
         let groups = self.groups.read().await;
+        let mut tasks = self.tasks.lock().await;
         for group in groups.values().cloned() {
             let db = self.db.clone();
-            task::spawn(async move {
-                run_group_task(group, db).await;
-            });
+            let shutdown = self.shutdown.subscribe();
+            tasks.push(task::spawn(async move {
+                run_group_task(group, db, shutdown).await;
+            }));
+        }
+    }
+
+    /// Lets running updates finish, then stops every group. Groups still
+    /// busy after `grace` are aborted.
+    pub async fn shutdown(&self, grace: Duration) {
+        self.shutdown.send_replace(true);
+        let tasks = std::mem::take(&mut *self.tasks.lock().await);
+        let aborted = join_within(tasks, grace).await;
+        if aborted > 0 {
+            log::warn!("⚠️ Aborted {aborted} states job groups that did not stop in time");
         }
     }
 }
 
 async fn run_group_task<T: StateJob + 'static + Clone>(
-    group: StatesJobGroup<T>,
+    mut group: StatesJobGroup<T>,
     conn: Arc<Mutex<Connection>>,
+    mut shutdown: watch::Receiver<bool>,
 ) {
     loop {
         if let Err(e) = run_group_once(&group, &conn).await {
             log::error!("❌ Error running group '{}': {e}", group.name);
         }
-        time::sleep(group.interval).await;
+        let interval = *group.interval.borrow_and_update();
+        let interval_changed = async {
+            if group.interval.changed().await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        };
+        tokio::select! {
+            _ = time::sleep(interval) => {}
+            _ = interval_changed => {}
+            _ = shutdown.wait_for(|stop| *stop) => return,
+        }
     }
 }
 